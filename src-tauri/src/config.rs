@@ -17,6 +17,22 @@ fn default_true() -> bool {
     true
 }
 
+/// Listing codes: letters and digits, then up to 19 more including `-` and
+/// `/`, which is what `parse_folder_name` reads back from a folder suffix.
+pub const DEFAULT_CODE_PATTERN: &str = "[A-Za-z0-9][A-Za-z0-9/-]{0,19}";
+
+fn default_code_pattern() -> String {
+    DEFAULT_CODE_PATTERN.to_string()
+}
+
+/// Database backups kept in `{app data}/backups` before the oldest are
+/// removed. 0 turns the automatic backups off.
+pub const DEFAULT_BACKUP_RETENTION: u32 = 14;
+
+const fn default_backup_retention() -> u32 {
+    DEFAULT_BACKUP_RETENTION
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WatermarkConfig {
@@ -45,6 +61,8 @@ impl Default for WatermarkConfig {
     }
 }
 
+// Independent on/off settings, not a state machine.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
     // Legacy field for backward compatibility
@@ -67,6 +85,26 @@ pub struct AppConfig {
     #[serde(rename = "setsFolderPath")]
     #[serde(default)]
     pub sets_folder_path: String,
+    // Deleted properties' folders are moved here; empty leaves them in place
+    #[serde(rename = "trashFolderPath")]
+    #[serde(default)]
+    pub trash_folder_path: String,
+    // Regex a listing code must match in full
+    #[serde(rename = "codePattern")]
+    #[serde(default = "default_code_pattern")]
+    pub code_pattern: String,
+    // Number of database backups to keep; 0 disables automatic backups
+    #[serde(rename = "backupRetention")]
+    #[serde(default = "default_backup_retention")]
+    pub backup_retention: u32,
+    // Import new property folders as they appear in the NEW folder
+    #[serde(rename = "watchFolders")]
+    #[serde(default = "default_true")]
+    pub watch_folders: bool,
+    // Watch the DONE, NOT_FOUND and ARCHIVE folders as well
+    #[serde(rename = "watchAllRoots")]
+    #[serde(default)]
+    pub watch_all_roots: bool,
     #[serde(rename = "isValidPath")]
     pub is_valid_path: bool,
     #[serde(rename = "lastUpdated")]
@@ -94,6 +132,11 @@ impl Default for AppConfig {
             not_found_folder_path: String::new(),
             archive_folder_path: String::new(),
             sets_folder_path: String::new(),
+            trash_folder_path: String::new(),
+            code_pattern: default_code_pattern(),
+            backup_retention: DEFAULT_BACKUP_RETENTION,
+            watch_folders: true,
+            watch_all_roots: false,
             is_valid_path: false,
             use_builtin_editor: true,
             fast_editor_path: None,
@@ -229,6 +272,12 @@ pub async fn save_config(
     let cache = app.state::<ConfigCache>();
     let mut guard = cache.write().await;
     *guard = Some(config);
+    drop(guard);
+
+    // Folder paths or the watch settings may have changed
+    if let Err(e) = crate::database::restart_folder_watcher(&app).await {
+        eprintln!("Failed to restart folder watcher: {e}");
+    }
 
     Ok(CommandResult {
         success: true,
@@ -250,6 +299,12 @@ pub async fn reset_config(app: tauri::AppHandle) -> Result<CommandResult, String
     let cache = app.state::<ConfigCache>();
     let mut guard = cache.write().await;
     *guard = None;
+    drop(guard);
+
+    // Without a config there is nothing to watch
+    if let Err(e) = crate::database::restart_folder_watcher(&app).await {
+        eprintln!("Failed to stop folder watcher: {e}");
+    }
 
     Ok(CommandResult {
         success: true,
@@ -367,6 +422,11 @@ mod tests {
         assert!(config.not_found_folder_path.is_empty());
         assert!(config.archive_folder_path.is_empty());
         assert!(config.sets_folder_path.is_empty());
+        assert!(config.trash_folder_path.is_empty());
+        assert_eq!(config.code_pattern, DEFAULT_CODE_PATTERN);
+        assert_eq!(config.backup_retention, DEFAULT_BACKUP_RETENTION);
+        assert!(config.watch_folders);
+        assert!(!config.watch_all_roots);
         assert!(!config.is_valid_path);
         assert!(config.use_builtin_editor);
         assert!(config.root_path.is_none());
@@ -420,6 +480,10 @@ mod tests {
 
         assert!(config.new_folder_path.is_empty());
         assert!(config.use_builtin_editor); // default_true
+        assert_eq!(config.code_pattern, DEFAULT_CODE_PATTERN);
+        assert_eq!(config.backup_retention, DEFAULT_BACKUP_RETENTION);
+        assert!(config.watch_folders);
+        assert!(!config.watch_all_roots);
         assert_eq!(config.watermark_config.size_mode, "proportional");
     }
 
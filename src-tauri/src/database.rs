@@ -6,34 +6,80 @@ use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use tauri::Manager;
 
+use events::{record_property_event, PropertyEventKind};
+use journal::{
+    discard_journal_entry, journal_status_change, run_status_change,
+    run_status_change_with_progress, run_status_changes, StatusChange, CITY_MERGE_SOURCE,
+};
+use tags::{attach_tags, has_all_tags_condition};
+
 // ── Submodules (extracted from this file) ──────────────────────────
+mod backups;
+mod bulk;
+mod catalogue;
 mod cities;
+mod codes;
 mod editor;
+mod events;
+mod images;
+mod journal;
+mod manifest;
 mod migrations;
+mod orphans;
 mod repair;
 mod scan;
+mod search;
+mod set_restore;
+mod set_verify;
+mod set_zip;
 mod sets;
+mod statistics;
+mod tags;
 mod thumbnails;
+mod transfer;
+mod trash;
 mod types;
+mod watcher;
 mod watermark;
 
 #[cfg(test)]
 mod test_support;
 
-pub use cities::{get_cities, search_cities};
+pub use backups::{create_backup, list_backups, restore_backup, spawn_backup_schedule};
+pub use bulk::bulk_update_property_status;
+pub use catalogue::{export_properties, import_properties};
+pub use cities::{get_cities, merge_cities, rename_city, search_cities};
+pub use codes::{
+    add_property_code, get_property_by_code, get_property_codes, remove_property_code,
+    set_primary_property_code, set_property_code,
+};
 pub use editor::{
     get_full_property_path, open_image_in_advanced_editor, open_image_in_editor,
     open_images_in_folder, open_property_folder,
 };
+pub use events::get_property_events;
+pub use images::get_property_images;
 pub use migrations::init_database;
+pub use orphans::{apply_orphan_actions, find_orphans};
 pub use repair::repair_property_statuses;
 pub use scan::scan_and_import_properties;
-pub use sets::{complete_set, delete_set, get_set_properties, get_sets, open_sets_folder};
+pub use search::search_properties;
+pub use set_restore::restore_set;
+pub use set_verify::verify_set;
+pub use set_zip::{cancel_set_completion, SetZipState};
+pub use sets::{
+    complete_set, delete_set, get_set_properties, get_sets, open_sets_folder,
+    preview_set_completion,
+};
+pub use statistics::get_statistics;
+pub use tags::{get_tags, search_tags, tag_properties, untag_properties};
 pub use thumbnails::{
     get_gallery_thumbnail_path, get_thumbnail_paths_batch, list_thumbnails,
     pregenerate_gallery_thumbnails,
 };
+pub use trash::{delete_property, get_trashed_properties, purge_trash, restore_property};
 pub use types::{CommandResult, Property};
+pub use watcher::{restart_folder_watcher, FolderWatcherState};
 pub use watermark::{
     clear_watermark_folders, copy_and_watermark_images, generate_watermark_preview,
     list_watermark_aggelia_images, list_watermark_images,
@@ -41,7 +87,7 @@ pub use watermark::{
 
 // Imported for the test module that still lives in this file.
 #[cfg(test)]
-use migrations::run_migrations;
+use migrations::{get_schema_version, run_migrations, SCHEMA_VERSION};
 #[cfg(test)]
 use test_support::{add_property_to_database, setup_test_db};
 
@@ -284,7 +330,7 @@ pub(super) async fn get_property_base_path(
 
 // Helper to find where a property folder actually exists across all status folders
 // Returns (full_path, actual_status) if found
-fn find_actual_folder_location(
+pub(super) fn find_actual_folder_location(
     config: &crate::config::AppConfig,
     folder_path: &str,
 ) -> Option<(PathBuf, String)> {
@@ -308,6 +354,80 @@ fn find_actual_folder_location(
     None
 }
 
+// Work out where a property folder has to move for a status change: from
+// wherever it currently is (expected location first, then every status
+// folder) to the folder for `new_status`. `None` if it is already there or
+// cannot be found on disk (folder might have been manually deleted).
+pub(super) fn plan_folder_move(
+    config: &crate::config::AppConfig,
+    folder_path: &str,
+    current_status: &str,
+    new_status: &str,
+) -> Result<Option<(PathBuf, PathBuf)>, String> {
+    let new_base_path = get_base_path_for_status(config, new_status)
+        .map_err(|e| format!("Failed to get property path: {e}"))?;
+    let folder_path_buf = folder_path_to_pathbuf(folder_path);
+    let new_path = new_base_path.join(&folder_path_buf);
+
+    let expected_old_path = get_base_path_for_status(config, current_status)
+        .ok()
+        .map(|b| b.join(&folder_path_buf));
+    let actual_old_path = match expected_old_path {
+        Some(path) if path.exists() => Some(path),
+        _ => find_actual_folder_location(config, folder_path).map(|(path, _)| path),
+    };
+
+    Ok(actual_old_path
+        .filter(|old_path| *old_path != new_path)
+        .map(|old_path| (old_path, new_path)))
+}
+
+// Move a property folder to `new_path`, creating its parent first. Renames
+// on Windows fail transiently while Explorer or an image viewer still holds
+// a handle, so one retry is attempted after a short pause. A copy whose
+// original could not be fully removed is only logged here; callers that
+// report back use `move_folder_with_progress`. Blocking; call from
+// spawn_blocking.
+pub(super) fn move_folder(old_path: &Path, new_path: &Path) -> Result<(), String> {
+    let warning = move_folder_with_progress(old_path, new_path, &mut |_| {})?;
+    if let Some(warning) = warning {
+        eprintln!("{warning}");
+    }
+    Ok(())
+}
+
+// `move_folder`, falling back to a verified copy when the two paths are on
+// different filesystems. `on_progress` is only called while copying.
+// Returns a warning when the copy left part of the original behind.
+fn move_folder_with_progress(
+    old_path: &Path,
+    new_path: &Path,
+    on_progress: &mut dyn FnMut(transfer::CopyProgress),
+) -> Result<Option<String>, String> {
+    if let Some(parent) = new_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            format!(
+                "Failed to create parent directory: {e}. \
+                Hint: Make sure no files are open in the folder and try again."
+            )
+        })?;
+    }
+    match fs::rename(old_path, new_path) {
+        Ok(()) => return Ok(None),
+        Err(e) if transfer::is_cross_device(&e) => {
+            return transfer::copy_verify_and_remove(old_path, new_path, on_progress);
+        }
+        Err(_) => {}
+    }
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    fs::rename(old_path, new_path).map(|()| None).map_err(|e| {
+        format!(
+            "Failed to move folder: {e}. \
+            Hint: Close any open files/folders and File Explorer windows for this property, then try again."
+        )
+    })
+}
+
 // Property CRUD operations
 #[tauri::command]
 pub async fn create_property(
@@ -416,10 +536,11 @@ pub async fn create_property(
 pub async fn get_properties(app: tauri::AppHandle) -> Result<CommandResult, String> {
     let pool = get_database_pool(&app)?;
 
-    let rows = sqlx::query("SELECT * FROM properties ORDER BY created_at DESC")
-        .fetch_all(pool)
-        .await
-        .map_err(|e| format!("Failed to fetch properties: {}", e))?;
+    let rows =
+        sqlx::query("SELECT * FROM properties WHERE deleted_at IS NULL ORDER BY created_at DESC")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to fetch properties: {}", e))?;
 
     let mut properties = Vec::new();
 
@@ -441,6 +562,7 @@ pub async fn get_properties(app: tauri::AppHandle) -> Result<CommandResult, Stri
             folder_path: row.get("folder_path"),
             notes: row.get("notes"),
             code: row.get("code"),
+            tags: Vec::new(),
             created_at,
             updated_at,
             completed: None,
@@ -449,6 +571,8 @@ pub async fn get_properties(app: tauri::AppHandle) -> Result<CommandResult, Stri
         properties.push(property);
     }
 
+    attach_tags(pool, &mut properties).await?;
+
     Ok(CommandResult {
         success: true,
         error: None,
@@ -460,11 +584,25 @@ pub async fn get_properties(app: tauri::AppHandle) -> Result<CommandResult, Stri
 pub async fn get_properties_by_status(
     app: tauri::AppHandle,
     status: String,
+    tags: Option<Vec<String>>,
 ) -> Result<CommandResult, String> {
     let pool = get_database_pool(&app)?;
 
-    let rows = sqlx::query("SELECT * FROM properties WHERE status = ? ORDER BY created_at DESC")
-        .bind(&status)
+    // Optional tag filter: only properties carrying every requested tag
+    let (tag_filter, tag_binds) = match has_all_tags_condition(&tags.unwrap_or_default()) {
+        Some((condition, binds)) => (format!(" AND {condition}"), binds),
+        None => (String::new(), Vec::new()),
+    };
+    let sql = format!(
+        "SELECT * FROM properties WHERE status = ? AND deleted_at IS NULL{tag_filter} \
+         ORDER BY created_at DESC"
+    );
+
+    let mut query = sqlx::query(&sql).bind(&status);
+    for tag in &tag_binds {
+        query = query.bind(tag);
+    }
+    let rows = query
         .fetch_all(pool)
         .await
         .map_err(|e| format!("Failed to fetch properties: {}", e))?;
@@ -488,6 +626,7 @@ pub async fn get_properties_by_status(
             folder_path: row.get("folder_path"),
             notes: row.get("notes"),
             code: row.get("code"),
+            tags: Vec::new(),
             created_at,
             updated_at,
             completed: None,
@@ -496,6 +635,8 @@ pub async fn get_properties_by_status(
         properties.push(property);
     }
 
+    attach_tags(pool, &mut properties).await?;
+
     Ok(CommandResult {
         success: true,
         error: None,
@@ -520,9 +661,6 @@ pub async fn update_property_status(
         });
     }
 
-    let now = chrono::Utc::now();
-    let now_timestamp = now.timestamp_millis();
-
     // Get current property info
     let property_row = sqlx::query("SELECT * FROM properties WHERE id = ?")
         .bind(property_id)
@@ -540,207 +678,72 @@ pub async fn update_property_status(
     // Use the database folder_path for operations, don't reconstruct it
     let folder_path = db_folder_path;
 
-    // IMPORTANT: Move folder FIRST before updating database
-    // This ensures we don't update the database if the folder move fails
-    if current_status != new_status {
-        let config_result = crate::config::get_cached_config(&app).await;
-        if let Ok(Some(config)) = config_result {
-            // Get base paths using actual folder_path from database
-            let new_base = get_base_path_for_status(&config, &new_status);
-            let old_base = get_base_path_for_status(&config, &current_status);
-
-            match new_base {
-                Ok(new_base_path) => {
-                    let folder_path_buf = folder_path_to_pathbuf(&folder_path);
-                    let new_path = new_base_path.join(&folder_path_buf);
-
-                    // First try the expected location based on current_status
-                    let expected_old_path = old_base.ok().map(|b| b.join(&folder_path_buf));
-
-                    // Find actual folder location - check expected location first, then search all
-                    let actual_old_path = match expected_old_path {
-                        Some(ref path) if path.exists() => Some(path.clone()),
-                        _ => {
-                            // Folder not at expected location, search all status folders
-                            find_actual_folder_location(&config, &folder_path).map(|(path, _)| path)
-                        }
-                    };
-
-                    if let Some(old_path) = actual_old_path {
-                        if old_path != new_path {
-                            // Move folder on a blocking thread
-                            let old_p = old_path.clone();
-                            let new_p = new_path.clone();
-                            let move_result = tokio::task::spawn_blocking(move || {
-                                if let Some(parent) = new_p.parent() {
-                                    if let Err(e) = fs::create_dir_all(parent) {
-                                        return Err(format!(
-                                            "Failed to create parent directory: {}. \
-                                            Hint: Make sure no files are open in the folder and try again.",
-                                            e
-                                        ));
-                                    }
-                                }
-                                if let Err(_e) = fs::rename(&old_p, &new_p) {
-                                    std::thread::sleep(std::time::Duration::from_millis(100));
-                                    if let Err(e2) = fs::rename(&old_p, &new_p) {
-                                        return Err(format!(
-                                            "Failed to move folder: {}. \
-                                            Hint: Close any open files/folders and File Explorer windows for this property, then try again.",
-                                            e2
-                                        ));
-                                    }
-                                }
-                                Ok(())
-                            })
-                            .await
-                            .map_err(|e| format!("Task join error: {e}"))?;
-
-                            if let Err(e) = move_result {
-                                return Ok(CommandResult {
-                                    success: false,
-                                    error: Some(e),
-                                    data: None,
-                                });
-                            }
-                        }
+    // (old, new) absolute paths if the folder has to move with the status
+    let folder_move = if current_status == new_status {
+        None
+    } else {
+        match crate::config::get_cached_config(&app).await {
+            Ok(Some(config)) => {
+                match plan_folder_move(&config, &folder_path, &current_status, &new_status) {
+                    Ok(folder_move) => folder_move,
+                    Err(e) => {
+                        return Ok(CommandResult {
+                            success: false,
+                            error: Some(e),
+                            data: None,
+                        });
                     }
-                    // If folder not found anywhere, just update status without moving
-                    // (folder might have been manually deleted)
-                }
-                Err(e) => {
-                    return Ok(CommandResult {
-                        success: false,
-                        error: Some(format!("Failed to get property path: {}", e)),
-                        data: None,
-                    });
                 }
             }
+            _ => None,
         }
-    }
+    };
 
-    // Only update database AFTER folder move succeeded
-    let result = sqlx::query(
-        "UPDATE properties SET status = ?, folder_path = ?, updated_at = ? WHERE id = ?",
-    )
-    .bind(&new_status)
-    .bind(&folder_path)
-    .bind(now_timestamp)
-    .bind(property_id)
-    .execute(pool)
-    .await;
+    // The move is journaled before it happens and the row is only updated
+    // after it succeeds, so a crash in between is resolved on next startup.
+    let change = StatusChange {
+        property_id,
+        old_status: current_status,
+        new_status,
+        folder_path,
+        folder_move,
+        source: "update_property_status".to_string(),
+    };
 
-    match result {
-        Ok(_) => Ok(CommandResult {
+    let on_progress = transfer::emit_move_progress(&app, property_id);
+    match run_status_change_with_progress(pool, &change, on_progress).await {
+        Ok(warning) => Ok(CommandResult {
             success: true,
             error: None,
-            data: None,
+            data: warning.map(|warning| serde_json::json!({ "warning": warning })),
         }),
         Err(e) => Ok(CommandResult {
             success: false,
-            error: Some(format!("Failed to update property: {}", e)),
+            error: Some(e),
             data: None,
         }),
     }
 }
 
-#[tauri::command]
-pub async fn set_property_code(
-    app: tauri::AppHandle,
-    property_id: i64,
-    code: String,
-) -> Result<CommandResult, String> {
-    let pool = get_database_pool(&app)?;
-
-    // Validate code is not empty
-    let code = code.trim();
-    if code.is_empty() {
-        return Ok(CommandResult {
-            success: false,
-            error: Some("Code cannot be empty".to_string()),
-            data: None,
-        });
-    }
-
-    let now = chrono::Utc::now();
-    let now_timestamp = now.timestamp_millis();
-
-    // Get current property info
-    let property_row = sqlx::query("SELECT * FROM properties WHERE id = ?")
-        .bind(property_id)
-        .fetch_one(pool)
-        .await
-        .map_err(|e| format!("Property not found: {}", e))?;
-
-    let name: String = property_row.get("name");
-    let city: String = property_row.get("city");
-    let status: String = property_row.get("status");
-    let folder_path: String = property_row.get("folder_path");
-
-    // Extract the actual folder name from the stored folder_path (format: "city/folder_name")
-    // This ensures we use the real folder name on disk, not a reconstructed one
-    let old_folder_name = folder_path
-        .split('/')
-        .next_back()
-        .unwrap_or(&name)
-        .to_string();
-
-    // For folder names, replace "/" with "-" since "/" is not allowed in folder names
-    // This allows codes like "204905/44538" to be saved as "204905-44538" in the folder name
-    let folder_safe_code = code.replace('/', "-");
-    let new_folder_name = format!("{} ({})", name, folder_safe_code);
-
-    // Calculate new folder path (relative) for database storage
-    let new_folder_path = format!("{}/{}", city, new_folder_name);
-
-    // Get config for absolute paths
-    let config = crate::config::get_cached_config(&app)
-        .await
-        .map_err(|e| format!("Failed to load config: {}", e))?
-        .ok_or("App configuration not found")?;
-
-    let base_path = get_base_path_for_status(&config, &status)?;
-    let old_absolute_path = base_path.join(&city).join(&old_folder_name);
-    let new_absolute_path = base_path.join(&city).join(&new_folder_name);
-
-    // Only rename if paths are different and old path exists
-    if old_absolute_path != new_absolute_path {
-        tokio::task::spawn_blocking(move || {
-            if old_absolute_path.exists() {
-                fs::rename(&old_absolute_path, &new_absolute_path)
-                    .map_err(|e| format!("Failed to rename folder: {}", e))?;
-            }
-            Ok::<_, String>(())
-        })
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
-    }
-
-    // Update database with new code and folder_path
-    let result =
-        sqlx::query("UPDATE properties SET code = ?, folder_path = ?, updated_at = ? WHERE id = ?")
-            .bind(code)
-            .bind(&new_folder_path)
-            .bind(now_timestamp)
-            .bind(property_id)
-            .execute(pool)
-            .await;
-
-    match result {
-        Ok(_) => Ok(CommandResult {
-            success: true,
-            error: None,
-            data: Some(serde_json::json!({
-                "new_folder_path": new_folder_path,
-                "code": code
-            })),
-        }),
-        Err(e) => Ok(CommandResult {
-            success: false,
-            error: Some(format!("Failed to update property code: {}", e)),
-            data: None,
-        }),
+// Rename a property folder in place. Only renames if the paths differ and
+// the old folder exists; returns the (old, new) pair when it did.
+pub(super) async fn rename_folder_if_present(
+    old_path: PathBuf,
+    new_path: PathBuf,
+) -> Result<Option<(PathBuf, PathBuf)>, String> {
+    if old_path == new_path {
+        return Ok(None);
     }
+    tokio::task::spawn_blocking(move || {
+        if old_path.exists() {
+            fs::rename(&old_path, &new_path)
+                .map_err(|e| format!("Failed to rename folder: {}", e))?;
+            return Ok(Some((old_path, new_path)));
+        }
+        Ok(None)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
 }
 
 #[tauri::command]
@@ -820,7 +823,7 @@ pub async fn update_property(
     let base_path = get_base_path_for_status(&config, &status)?;
 
     // Handle folder operations if name or city changed
-    if name_changed || city_changed {
+    let folder_move: Option<(PathBuf, PathBuf)> = if name_changed || city_changed {
         let old_absolute_path = base_path.join(&old_city).join(&old_folder_name);
         let new_absolute_path = base_path.join(city).join(&new_folder_name);
         let city_changed_flag = city_changed;
@@ -847,29 +850,73 @@ pub async fn update_property(
                 if old_absolute_path != new_absolute_path {
                     fs::rename(&old_absolute_path, &new_absolute_path)
                         .map_err(|e| format!("Failed to move/rename folder: {}", e))?;
+                    return Ok(Some((old_absolute_path, new_absolute_path)));
                 }
             }
-            Ok::<_, String>(())
+            Ok::<_, String>(None)
         })
         .await
-        .map_err(|e| format!("Task join error: {e}"))??;
-    }
+        .map_err(|e| format!("Task join error: {e}"))??
+    } else {
+        None
+    };
 
     // Update database
-    let result = sqlx::query(
-        "UPDATE properties SET name = ?, city = ?, notes = ?, folder_path = ?, updated_at = ? WHERE id = ?",
-    )
-    .bind(name)
-    .bind(city)
-    .bind(&notes)
-    .bind(&new_folder_path)
-    .bind(now_timestamp)
-    .bind(property_id)
-    .execute(pool)
+    let result: Result<(), String> = async {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        sqlx::query(
+            "UPDATE properties SET name = ?, city = ?, notes = ?, folder_path = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(name)
+        .bind(city)
+        .bind(&notes)
+        .bind(&new_folder_path)
+        .bind(now_timestamp)
+        .bind(property_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if name_changed {
+            record_property_event(
+                &mut tx,
+                property_id,
+                PropertyEventKind::Rename,
+                Some(&old_name),
+                Some(name),
+                "update_property",
+            )
+            .await?;
+        }
+        if city_changed {
+            record_property_event(
+                &mut tx,
+                property_id,
+                PropertyEventKind::CityChange,
+                Some(&old_city),
+                Some(city),
+                "update_property",
+            )
+            .await?;
+        }
+        if let Some((old_path, new_path)) = &folder_move {
+            record_property_event(
+                &mut tx,
+                property_id,
+                PropertyEventKind::FolderMove,
+                Some(&old_path.to_string_lossy()),
+                Some(&new_path.to_string_lossy()),
+                "update_property",
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())
+    }
     .await;
 
     match result {
-        Ok(_) => {
+        Ok(()) => {
             // Also update city usage count
             let _ = sqlx::query(
                 "INSERT INTO cities (name, usage_count, created_at) VALUES (?, 1, ?)
@@ -899,31 +946,7 @@ pub async fn update_property(
     }
 }
 
-#[tauri::command]
-pub async fn delete_property(
-    app: tauri::AppHandle,
-    property_id: i64,
-) -> Result<CommandResult, String> {
-    let pool = get_database_pool(&app)?;
-
-    let result = sqlx::query("DELETE FROM properties WHERE id = ?")
-        .bind(property_id)
-        .execute(pool)
-        .await;
-
-    match result {
-        Ok(_) => Ok(CommandResult {
-            success: true,
-            error: None,
-            data: None,
-        }),
-        Err(e) => Ok(CommandResult {
-            success: false,
-            error: Some(format!("Failed to delete property: {}", e)),
-            data: None,
-        }),
-    }
-}
+// delete_property moved to database/trash.rs (soft delete)
 
 // City commands moved to database/cities.rs
 #[tauri::command]
@@ -956,10 +979,14 @@ pub async fn get_property_by_id(
                 folder_path: row.get("folder_path"),
                 notes: row.get("notes"),
                 code: row.get("code"),
+                tags: Vec::new(),
                 created_at,
                 updated_at,
                 completed: None,
             };
+            let mut properties = [property];
+            attach_tags(pool, &mut properties).await?;
+            let [property] = properties;
 
             Ok(CommandResult {
                 success: true,
@@ -1099,6 +1126,14 @@ pub async fn reset_database_with_proper_dates(
     // Get the existing database pool from app state
     let pool = get_database_pool(&app)?;
 
+    // Keep a copy of what is about to be wiped
+    backups::create_backup_in(
+        pool,
+        &backups::backups_dir(&app)?,
+        backups::BackupKind::PreReset,
+    )
+    .await?;
+
     // Delete all data from tables (this avoids file locking issues on Windows)
     sqlx::query("DELETE FROM properties")
         .execute(pool)
@@ -1110,6 +1145,22 @@ pub async fn reset_database_with_proper_dates(
         .await
         .map_err(|e| format!("Failed to clear cities table: {}", e))?;
 
+    sqlx::query("DELETE FROM property_events")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to clear property_events table: {e}"))?;
+
+    // Forget cached city mtimes so the next scan re-imports everything
+    sqlx::query("DELETE FROM scan_state")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to clear scan_state table: {e}"))?;
+
+    sqlx::query("DELETE FROM images")
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to clear images table: {e}"))?;
+
     // Reset SQLite auto-increment counters
     sqlx::query("DELETE FROM sqlite_sequence WHERE name='properties' OR name='cities'")
         .execute(pool)
@@ -1940,6 +1991,35 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn plan_folder_move_finds_folder_outside_expected_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        // DB says NEW, but the folder was left in DONE.
+        let property_dir = tmp.path().join("DONE").join("Athens").join("Villa");
+        fs::create_dir_all(&property_dir).unwrap();
+        let config = AppConfig {
+            new_folder_path: tmp.path().join("NEW").to_string_lossy().to_string(),
+            done_folder_path: tmp.path().join("DONE").to_string_lossy().to_string(),
+            not_found_folder_path: tmp.path().join("NF").to_string_lossy().to_string(),
+            archive_folder_path: tmp.path().join("ARCHIVE").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        };
+
+        let planned = plan_folder_move(&config, "Athens/Villa", "NEW", "ARCHIVE").unwrap();
+        assert_eq!(
+            planned,
+            Some((
+                property_dir.clone(),
+                tmp.path().join("ARCHIVE").join("Athens").join("Villa")
+            ))
+        );
+        // Already where the new status expects it: nothing to move.
+        assert_eq!(
+            plan_folder_move(&config, "Athens/Villa", "NEW", "DONE").unwrap(),
+            None
+        );
+    }
+
     // ── create_property_folder_structure (filesystem) ────────────────
 
     #[tokio::test]
@@ -2615,4 +2695,144 @@ mod tests {
             "idx_properties_created_at index missing after migrations"
         );
     }
+
+    #[tokio::test]
+    async fn migrations_record_schema_version() {
+        let pool = setup_test_db().await;
+        assert_eq!(get_schema_version(&pool).await.unwrap(), SCHEMA_VERSION);
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM schema_version")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn migrations_refuse_newer_schema_version() {
+        let pool = setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?, 'future', 0)",
+        )
+        .bind(SCHEMA_VERSION + 1)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let err = run_migrations(&pool).await.unwrap_err();
+        assert!(err.contains("newer"), "unexpected error: {err}");
+    }
+
+    #[tokio::test]
+    async fn migrations_adopt_legacy_unversioned_database() {
+        // A database from before schema_version existed: properties without
+        // status/code columns, one row still using the old completed flag.
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE properties (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                city TEXT NOT NULL,
+                completed BOOLEAN NOT NULL DEFAULT 0,
+                folder_path TEXT NOT NULL,
+                notes TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO properties (name, city, completed, folder_path, created_at, updated_at)
+             VALUES ('Villa', 'Athens', 1, 'Athens/Villa', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        run_migrations(&pool).await.unwrap();
+
+        let row = sqlx::query("SELECT status, code FROM properties WHERE name = 'Villa'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("status"), "DONE");
+        assert_eq!(row.get::<Option<String>, _>("code"), None);
+        assert_eq!(get_schema_version(&pool).await.unwrap(), SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn migrations_clear_duplicate_codes() {
+        // Before codes were unique two properties could share one.
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE properties (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                city TEXT NOT NULL,
+                completed BOOLEAN NOT NULL DEFAULT 0,
+                folder_path TEXT NOT NULL,
+                notes TEXT,
+                code TEXT,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO properties (name, city, folder_path, code, created_at, updated_at)
+             VALUES ('First', 'Athens', 'Athens/First', 'AB1', 0, 0),
+                    ('Second', 'Athens', 'Athens/Second', 'ab1', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        run_migrations(&pool).await.unwrap();
+
+        let codes: Vec<(String, Option<String>)> =
+            sqlx::query_as("SELECT name, code FROM properties ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            codes,
+            vec![
+                ("First".to_string(), Some("AB1".to_string())),
+                ("Second".to_string(), None)
+            ]
+        );
+        let logged: String = sqlx::query_scalar(
+            "SELECT old_value FROM property_events WHERE event_type = 'code_assigned'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(logged, "ab1");
+    }
+
+    #[tokio::test]
+    async fn migrations_do_not_rerun_status_backfill() {
+        // A property moved back to NEW keeps its legacy completed = 1 flag.
+        // Re-running migrations must not flip it to DONE again.
+        let pool = setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO properties (name, city, completed, status, folder_path, created_at, updated_at)
+             VALUES ('Villa', 'Athens', 1, 'NEW', 'Athens/Villa', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        run_migrations(&pool).await.unwrap();
+
+        let status: String = sqlx::query_scalar("SELECT status FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, "NEW");
+    }
 }
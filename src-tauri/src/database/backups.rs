@@ -0,0 +1,552 @@
+//! Database backups. `properties.db` is the only copy of the listing codes
+//! and set history, so a `VACUUM INTO` snapshot is written to
+//! `{app data}/backups` on startup and then once a day while the app runs,
+//! keeping the newest `backup_retention` files.
+//!
+//! `restore_backup` never swaps files under the open pool. The backup is
+//! copied to a staging file, checked with `PRAGMA integrity_check` and
+//! migrated to the current schema there, and only then copied table by
+//! table into the live database in one transaction. The live database is
+//! backed up first, so a restore can itself be undone. The folder-move
+//! journal is left as it is and the caches of what is on disk are emptied,
+//! since the backup's copies of them describe another moment.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sqlx::{Acquire, Row, SqlitePool};
+use tauri::Manager;
+
+use crate::database::get_database_pool;
+use crate::database::migrations::run_migrations;
+use crate::database::types::{BackupInfo, CommandResult};
+
+const BACKUP_DIR: &str = "backups";
+const BACKUP_PREFIX: &str = "properties-";
+const BACKUP_TIMESTAMP: &str = "%Y%m%d-%H%M%S-%3f";
+/// Scratch copy a backup is verified and migrated in before a restore.
+const STAGING_FILE: &str = ".restore-staging.db";
+const BACKUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// The live folder-move journal, which must survive a restore.
+const JOURNAL_TABLE: &str = "pending_operations";
+/// Caches of what is on disk now, which a backup's copy would not match.
+/// A restore empties them and they fill up again as folders are read.
+const DISK_CACHE_TABLES: [&str; 2] = ["scan_state", "images"];
+
+/// Why a backup was taken; part of the file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BackupKind {
+    Startup,
+    Daily,
+    Manual,
+    PreRestore,
+    PreReset,
+}
+
+impl BackupKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Startup => "startup",
+            Self::Daily => "daily",
+            Self::Manual => "manual",
+            Self::PreRestore => "pre-restore",
+            Self::PreReset => "pre-reset",
+        }
+    }
+}
+
+pub(super) fn backups_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(app_data_dir.join(BACKUP_DIR))
+}
+
+/// Write a `VACUUM INTO` snapshot of `pool` to `dir`. Returns its path.
+pub(super) async fn create_backup_in(
+    pool: &SqlitePool,
+    dir: &Path,
+    kind: BackupKind,
+) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create backup folder {}: {e}", dir.display()))?;
+
+    let stamp = chrono::Utc::now().format(BACKUP_TIMESTAMP);
+    let mut path = dir.join(format!("{BACKUP_PREFIX}{stamp}-{}.db", kind.as_str()));
+    // Two backups within the same millisecond get a counter.
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{BACKUP_PREFIX}{stamp}-{}-{n}.db", kind.as_str()));
+        n += 1;
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to back up database: {e}"))?;
+    Ok(path)
+}
+
+/// Parse `properties-YYYYmmdd-HHMMSS-mmm-kind[-n].db` into its timestamp
+/// (milliseconds) and kind.
+fn parse_backup_name(file_name: &str) -> Option<(i64, String)> {
+    let stem = file_name.strip_prefix(BACKUP_PREFIX)?.strip_suffix(".db")?;
+    // The timestamp is the fixed-width "YYYYmmdd-HHMMSS-mmm" at the start.
+    let (stamp, kind) = (stem.get(..19)?, stem.get(20..)?);
+    let created_at = chrono::NaiveDateTime::parse_from_str(stamp, BACKUP_TIMESTAMP).ok()?;
+    let kind = kind.trim_end_matches(|c: char| c.is_ascii_digit() || c == '-');
+    Some((created_at.and_utc().timestamp_millis(), kind.to_string()))
+}
+
+/// Backups in `dir`, newest first. A missing folder has none.
+fn list_backups_in(dir: &Path) -> Result<Vec<BackupInfo>, String> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let (created_at, kind) = parse_backup_name(&file_name)?;
+            let size_bytes = entry.metadata().ok()?.len();
+            Some(BackupInfo {
+                file_name,
+                kind,
+                created_at,
+                size_bytes,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+    Ok(backups)
+}
+
+/// Delete all but the newest `retention` backups. Returns the removed file
+/// names.
+fn prune_backups(dir: &Path, retention: usize) -> Result<Vec<String>, String> {
+    let mut removed = Vec::new();
+    for backup in list_backups_in(dir)?.into_iter().skip(retention) {
+        fs::remove_file(dir.join(&backup.file_name))
+            .map_err(|e| format!("Failed to remove backup {}: {e}", backup.file_name))?;
+        removed.push(backup.file_name);
+    }
+    Ok(removed)
+}
+
+/// Take a backup and apply the retention from the config. Does nothing when
+/// the retention is 0.
+async fn run_scheduled_backup(app: &tauri::AppHandle, kind: BackupKind) -> Result<(), String> {
+    let retention = crate::config::get_cached_config(app)
+        .await?
+        .map_or(crate::config::DEFAULT_BACKUP_RETENTION, |config| {
+            config.backup_retention
+        });
+    if retention == 0 {
+        return Ok(());
+    }
+    let pool = get_database_pool(app)?;
+    let dir = backups_dir(app)?;
+    create_backup_in(pool, &dir, kind).await?;
+    prune_backups(&dir, retention as usize)?;
+    Ok(())
+}
+
+/// Back up the database now and every 24 hours while the app runs.
+pub fn spawn_backup_schedule(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(BACKUP_INTERVAL);
+        // The first tick completes immediately: that is the startup backup.
+        let mut kind = BackupKind::Startup;
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_scheduled_backup(&app, kind).await {
+                eprintln!("Scheduled database backup failed: {e}");
+            }
+            kind = BackupKind::Daily;
+        }
+    });
+}
+
+/// Copy `backup` to `staging`, check its integrity and bring its schema up
+/// to date, so it can be copied into the live database as is.
+async fn prepare_staging_copy(backup: &Path, staging: &Path) -> Result<(), String> {
+    fs::copy(backup, staging).map_err(|e| format!("Failed to copy backup: {e}"))?;
+
+    let staging_pool = SqlitePool::connect_with(
+        sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(staging)
+            .create_if_missing(false),
+    )
+    .await
+    .map_err(|e| format!("Failed to open backup: {e}"))?;
+
+    let result = async {
+        let problems: Vec<String> = sqlx::query_scalar("PRAGMA integrity_check")
+            .fetch_all(&staging_pool)
+            .await
+            .map_err(|e| format!("Failed to check backup integrity: {e}"))?;
+        if problems != ["ok"] {
+            return Err(format!(
+                "Backup failed the integrity check: {}",
+                problems.join("; ")
+            ));
+        }
+        run_migrations(&staging_pool).await
+    }
+    .await;
+
+    staging_pool.close().await;
+    result
+}
+
+/// Tables whose rows a restore copies: everything except `SQLite`'s own
+/// tables, `schema_version`, the journal, the disk caches, and full-text
+/// indexes, which the triggers on `properties` rebuild as the rows are
+/// copied.
+async fn restorable_tables(conn: &mut sqlx::SqliteConnection) -> Result<Vec<String>, String> {
+    let rows = sqlx::query(
+        "SELECT name, sql FROM main.sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != 'schema_version'
+         ORDER BY rowid",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to list tables: {e}"))?;
+
+    let virtual_tables: Vec<String> = rows
+        .iter()
+        .filter(|row| {
+            row.get::<Option<String>, _>("sql")
+                .is_some_and(|sql| sql.starts_with("CREATE VIRTUAL TABLE"))
+        })
+        .map(|row| row.get("name"))
+        .collect();
+    Ok(rows
+        .iter()
+        .map(|row| row.get::<String, _>("name"))
+        .filter(|name| {
+            name != JOURNAL_TABLE
+                && !DISK_CACHE_TABLES.contains(&name.as_str())
+                && !virtual_tables
+                    .iter()
+                    .any(|v| name == v || name.starts_with(&format!("{v}_")))
+        })
+        .collect())
+}
+
+/// Replace the contents of the live database with the staged backup, in one
+/// transaction. Tables are copied in creation order, which puts parents
+/// before children; each is emptied right before its copy so rows added by
+/// triggers on an earlier table are replaced too.
+async fn restore_from_staging(pool: &SqlitePool, staging: &Path) -> Result<(), String> {
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    sqlx::query("ATTACH DATABASE ? AS backup")
+        .bind(staging.to_string_lossy().to_string())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to open backup: {e}"))?;
+
+    let result = async {
+        let tables = restorable_tables(&mut conn).await?;
+        let mut tx = conn.begin().await.map_err(|e| e.to_string())?;
+        sqlx::query("PRAGMA defer_foreign_keys = ON")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        for table in &tables {
+            let columns: Vec<String> =
+                sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{table}')"))
+                    .fetch_all(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to read columns of {table}: {e}"))?;
+            let columns = columns
+                .iter()
+                .map(|c| format!("\"{c}\""))
+                .collect::<Vec<_>>()
+                .join(", ");
+            sqlx::query(&format!("DELETE FROM main.\"{table}\""))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to clear {table}: {e}"))?;
+            sqlx::query(&format!(
+                "INSERT INTO main.\"{table}\" ({columns}) SELECT {columns} FROM backup.\"{table}\""
+            ))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to restore {table}: {e}"))?;
+        }
+        for table in DISK_CACHE_TABLES {
+            sqlx::query(&format!("DELETE FROM main.\"{table}\""))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to clear {table}: {e}"))?;
+        }
+        // `tags` comes back with its saved counts before `property_tags`,
+        // whose insert trigger then counts every row a second time.
+        sqlx::query(
+            "UPDATE tags SET usage_count =
+             (SELECT COUNT(*) FROM property_tags WHERE tag_id = tags.id)",
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to recount tags: {e}"))?;
+        tx.commit().await.map_err(|e| e.to_string())
+    }
+    .await;
+
+    let _ = sqlx::query("DETACH DATABASE backup")
+        .execute(&mut *conn)
+        .await;
+    result
+}
+
+/// Restore `file_name` from `dir` into the live database, after backing up
+/// the current state. Returns the name of that safety backup.
+async fn restore_backup_in(
+    pool: &SqlitePool,
+    dir: &Path,
+    file_name: &str,
+) -> Result<String, String> {
+    if parse_backup_name(file_name).is_none() || file_name.contains(['/', '\\']) {
+        return Err(format!("'{file_name}' is not a backup"));
+    }
+    let backup = dir.join(file_name);
+    if !backup.is_file() {
+        return Err(format!("Backup '{file_name}' not found"));
+    }
+
+    let staging = dir.join(STAGING_FILE);
+    let result = async {
+        prepare_staging_copy(&backup, &staging).await?;
+        let safety = create_backup_in(pool, dir, BackupKind::PreRestore).await?;
+        restore_from_staging(pool, &staging).await?;
+        Ok(safety
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default())
+    }
+    .await;
+    let _ = fs::remove_file(&staging);
+    result
+}
+
+/// List the database backups, newest first. `data` is a `BackupInfo[]`.
+#[tauri::command]
+pub async fn list_backups(app: tauri::AppHandle) -> Result<CommandResult, String> {
+    let dir = backups_dir(&app)?;
+    let backups = tokio::task::spawn_blocking(move || list_backups_in(&dir))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??;
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(backups).map_err(|e| e.to_string())?),
+    })
+}
+
+/// Back up the database now. `data` is the new backup's `BackupInfo`.
+#[tauri::command]
+pub async fn create_backup(app: tauri::AppHandle) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let dir = backups_dir(&app)?;
+
+    match create_backup_in(pool, &dir, BackupKind::Manual).await {
+        Ok(path) => {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let backup = list_backups_in(&dir)?
+                .into_iter()
+                .find(|backup| backup.file_name == file_name);
+            Ok(CommandResult {
+                success: true,
+                error: None,
+                data: Some(serde_json::to_value(backup).map_err(|e| e.to_string())?),
+            })
+        }
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+/// Replace the database contents with a backup from `list_backups`. The
+/// backup must pass `PRAGMA integrity_check`; the current database is backed
+/// up first. `data` is `{ safetyBackup }`, the name of that backup.
+#[tauri::command]
+pub async fn restore_backup(
+    app: tauri::AppHandle,
+    file_name: String,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let dir = backups_dir(&app)?;
+
+    match restore_backup_in(pool, &dir, &file_name).await {
+        Ok(safety_backup) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::json!({ "safetyBackup": safety_backup })),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+
+    /// `VACUUM INTO` from the shared in-memory test database writes another
+    /// in-memory database, so the backup tests need one on disk.
+    async fn setup_file_db(dir: &Path) -> SqlitePool {
+        let pool = SqlitePool::connect_with(
+            sqlx::sqlite::SqliteConnectOptions::new()
+                .filename(dir.join("properties.db"))
+                .create_if_missing(true),
+        )
+        .await
+        .unwrap();
+        run_migrations(&pool).await.unwrap();
+        pool
+    }
+
+    #[test]
+    fn backup_names_round_trip() {
+        assert_eq!(
+            parse_backup_name("properties-20261017-093000-123-daily.db"),
+            Some((1_792_229_400_123, "daily".to_string()))
+        );
+        assert_eq!(
+            parse_backup_name("properties-20261017-093000-123-pre-restore-2.db"),
+            Some((1_792_229_400_123, "pre-restore".to_string()))
+        );
+        assert!(parse_backup_name("properties.db").is_none());
+        assert!(parse_backup_name(STAGING_FILE).is_none());
+    }
+
+    #[tokio::test]
+    async fn backups_rotate_and_restore_into_the_live_pool() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pool = setup_file_db(tmp.path()).await;
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa (AB1)", Some("AB1"))
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO tags (name, created_at) VALUES ('sea view', 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO property_tags (property_id, tag_id, created_at)
+             SELECT p.id, t.id, 0 FROM properties p, tags t",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query("INSERT INTO scan_state (path, mtime, scanned_at) VALUES ('/old', 1, 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let first = create_backup_in(&pool, tmp.path(), BackupKind::Manual)
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            create_backup_in(&pool, tmp.path(), BackupKind::Daily)
+                .await
+                .unwrap();
+        }
+        let removed = prune_backups(tmp.path(), 2).unwrap();
+        assert_eq!(removed.len(), 1);
+        let backups = list_backups_in(tmp.path()).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(backups.iter().all(|b| b.kind == "daily"));
+        assert!(!first.exists());
+
+        sqlx::query("DELETE FROM properties")
+            .execute(&pool)
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Loft", "Rome", "NEW", "Loft", None)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO pending_operations
+             (property_id, old_status, new_status, folder_path, source_path, dest_path, source, created_at)
+             VALUES (2, 'NEW', 'DONE', 'Rome/Loft', 'NEW/Rome/Loft', 'DONE/Rome/Loft', 'test', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let safety = restore_backup_in(&pool, tmp.path(), &backups[0].file_name)
+            .await
+            .unwrap();
+        assert!(safety.contains("pre-restore"));
+        assert!(!tmp.path().join(STAGING_FILE).exists());
+
+        let names: Vec<String> = sqlx::query_scalar("SELECT name FROM properties")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(names, vec!["Villa".to_string()]);
+        // Trigger-maintained tables follow the restored rows.
+        let codes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM property_codes")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(codes, 1);
+        let hits: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM properties_fts WHERE properties_fts MATCH 'villa'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(hits, 1);
+        let usage: i64 = sqlx::query_scalar("SELECT usage_count FROM tags WHERE name = 'sea view'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(usage, 1);
+        // The live journal stays; the stale scan cache does not come back.
+        let journal: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pending_operations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(journal, 1);
+        let scans: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scan_state")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(scans, 0);
+    }
+
+    #[tokio::test]
+    async fn corrupt_backup_is_rejected() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let name = "properties-20261017-093000-000-manual.db";
+        fs::write(tmp.path().join(name), b"not a database").unwrap();
+
+        assert!(restore_backup_in(&pool, tmp.path(), name).await.is_err());
+        assert!(restore_backup_in(&pool, tmp.path(), "../properties.db")
+            .await
+            .is_err());
+        assert_eq!(list_backups_in(tmp.path()).unwrap().len(), 1);
+    }
+}
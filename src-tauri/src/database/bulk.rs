@@ -0,0 +1,174 @@
+//! Bulk status changes. `bulk_update_property_status` plans every property's
+//! folder move up front with a single config lookup, then hands them to
+//! `journal::run_status_changes`, which moves the folders in parallel and
+//! commits each row on its own, so one failure never affects the others.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use sqlx::Row;
+use tauri::Emitter;
+
+use crate::database::types::{BulkStatusItemResult, BulkStatusResult, CommandResult};
+use crate::database::{get_database_pool, plan_folder_move, run_status_changes, StatusChange};
+
+/// Progress event payload emitted while a bulk status change runs.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BulkStatusProgressEvent {
+    phase: String,  // "move" or "update"
+    current: usize, // number of items finished in this phase
+    total: usize,   // items in this phase
+}
+
+/// Resolve every property up front. Properties that cannot be planned get
+/// their error straight away (`Some`) and are left out of the run; the rest
+/// get a `None` slot, filled in order from the returned changes.
+async fn plan_changes(
+    pool: &sqlx::SqlitePool,
+    config: Option<&crate::config::AppConfig>,
+    property_ids: &[i64],
+    new_status: &str,
+) -> Result<(Vec<Option<BulkStatusItemResult>>, Vec<StatusChange>), String> {
+    let mut results: Vec<Option<BulkStatusItemResult>> = Vec::with_capacity(property_ids.len());
+    let mut changes: Vec<StatusChange> = Vec::new();
+    for &property_id in property_ids {
+        let row = sqlx::query(
+            "SELECT status, folder_path FROM properties WHERE id = ? AND deleted_at IS NULL",
+        )
+        .bind(property_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load property {property_id}: {e}"))?;
+
+        let Some(row) = row else {
+            results.push(Some(BulkStatusItemResult {
+                property_id,
+                success: false,
+                error: Some("Property not found".to_string()),
+            }));
+            continue;
+        };
+        let current_status: String = row.get("status");
+        let folder_path: String = row.get("folder_path");
+
+        let folder_move = match (config, current_status == new_status) {
+            (Some(config), false) => {
+                let config = config.clone();
+                let (fp, from, to) = (
+                    folder_path.clone(),
+                    current_status.clone(),
+                    new_status.to_string(),
+                );
+                tokio::task::spawn_blocking(move || plan_folder_move(&config, &fp, &from, &to))
+                    .await
+                    .map_err(|e| format!("Task join error: {e}"))?
+            }
+            _ => Ok(None),
+        };
+        match folder_move {
+            Ok(folder_move) => {
+                results.push(None);
+                changes.push(StatusChange {
+                    property_id,
+                    old_status: current_status,
+                    new_status: new_status.to_string(),
+                    folder_path,
+                    folder_move,
+                    source: "bulk_update_property_status".to_string(),
+                });
+            }
+            Err(e) => results.push(Some(BulkStatusItemResult {
+                property_id,
+                success: false,
+                error: Some(e),
+            })),
+        }
+    }
+
+    Ok((results, changes))
+}
+
+/// Change the status of many properties at once. `data` is a
+/// `BulkStatusResult` with one entry per requested property, in order.
+#[tauri::command]
+pub async fn bulk_update_property_status(
+    app: tauri::AppHandle,
+    property_ids: Vec<i64>,
+    new_status: String,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    if !["NEW", "DONE", "NOT_FOUND", "ARCHIVE"].contains(&new_status.as_str()) {
+        return Ok(CommandResult {
+            success: false,
+            error: Some(format!("Invalid status: {new_status}")),
+            data: None,
+        });
+    }
+
+    let config = crate::config::get_cached_config(&app).await?;
+
+    // Each property once, in the order requested.
+    let mut seen = HashSet::new();
+    let property_ids: Vec<i64> = property_ids
+        .into_iter()
+        .filter(|id| seen.insert(*id))
+        .collect();
+
+    let (mut results, changes) =
+        plan_changes(pool, config.as_ref(), &property_ids, &new_status).await?;
+
+    // "move" counts folder moves, "update" counts rows as they are committed.
+    let app_for_progress = app.clone();
+    let outcomes = run_status_changes(
+        pool,
+        &changes,
+        move |current, total| {
+            let _ = app_for_progress.emit(
+                "bulk-status-progress",
+                BulkStatusProgressEvent {
+                    phase: "move".to_string(),
+                    current,
+                    total,
+                },
+            );
+        },
+        |current, total| {
+            let _ = app.emit(
+                "bulk-status-progress",
+                BulkStatusProgressEvent {
+                    phase: "update".to_string(),
+                    current,
+                    total,
+                },
+            );
+        },
+    )
+    .await;
+
+    let mut outcomes = changes.iter().zip(outcomes);
+    for slot in results.iter_mut().filter(|slot| slot.is_none()) {
+        if let Some((change, outcome)) = outcomes.next() {
+            *slot = Some(BulkStatusItemResult {
+                property_id: change.property_id,
+                success: outcome.is_ok(),
+                error: outcome.err(),
+            });
+        }
+    }
+
+    let results: Vec<BulkStatusItemResult> = results.into_iter().flatten().collect();
+    let updated = results.iter().filter(|r| r.success).count();
+    let summary = BulkStatusResult {
+        updated,
+        failed: results.len() - updated,
+        results,
+    };
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(summary).map_err(|e| e.to_string())?),
+    })
+}
@@ -0,0 +1,923 @@
+//! Property catalogue export and import, for handing the office a
+//! spreadsheet of what was shot and taking their corrections back.
+//!
+//! `export_properties` writes CSV or JSON with each property's image counts
+//! per subfolder. `import_properties` reads a CSV and creates or updates
+//! rows, matching existing ones by code first and folder path second. A
+//! status change goes through `run_status_change`, so the folder moves to
+//! the new status root under the journal, and a code change through
+//! `apply_primary_code`, so the folder takes the new suffix. Lines that
+//! would change the name, city or folder of an existing property are
+//! refused, since the import does not rename or move folders otherwise.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sqlx::{Connection, Row, SqliteConnection, SqlitePool};
+
+use crate::config::AppConfig;
+use crate::database::codes::{apply_primary_code, validate_code_format};
+use crate::database::types::{
+    CommandResult, ExportFormat, ImageCounts, Property, PropertyExportOptions,
+    PropertyExportResult, PropertyImportResult,
+};
+use crate::database::{
+    attach_tags, get_base_path_for_status, get_database_pool, get_relative_folder_path,
+    list_image_filenames, plan_folder_move, run_status_change, StatusChange,
+};
+use crate::folder_conventions::{AGGELIA, INTERNET, WATERMARK};
+use crate::paths::validate_relative_folder_path;
+
+const VALID_STATUSES: &[&str] = &["NEW", "DONE", "NOT_FOUND", "ARCHIVE"];
+
+/// Column order of exported CSV files.
+const CSV_HEADERS: &[&str] = &[
+    "id",
+    "name",
+    "city",
+    "status",
+    "code",
+    "folder_path",
+    "notes",
+    "tags",
+    "created_at",
+    "updated_at",
+    "images",
+    "internet_images",
+    "internet_aggelia_images",
+    "watermark_images",
+    "watermark_aggelia_images",
+];
+
+/// Written first so Excel reads the file as UTF-8 (Greek names otherwise
+/// come out garbled).
+const UTF8_BOM: &str = "\u{feff}";
+
+/// One exported property. Timestamps are RFC 3339 for spreadsheet use;
+/// `image_counts` is `None` when the folder is not on disk.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportRecord {
+    id: i64,
+    name: String,
+    city: String,
+    status: String,
+    code: Option<String>,
+    folder_path: String,
+    notes: Option<String>,
+    tags: Vec<String>,
+    created_at: String,
+    updated_at: String,
+    image_counts: Option<ImageCounts>,
+}
+
+fn count_images(property_dir: &Path) -> ImageCounts {
+    let count = |dir: PathBuf| list_image_filenames(&dir).map_or(0, |images| images.len());
+    ImageCounts {
+        originals: count(property_dir.to_path_buf()),
+        internet: count(property_dir.join(INTERNET)),
+        internet_aggelia: count(property_dir.join(INTERNET).join(AGGELIA)),
+        watermark: count(property_dir.join(WATERMARK)),
+        watermark_aggelia: count(property_dir.join(WATERMARK).join(AGGELIA)),
+    }
+}
+
+/// Image counts for a property's folder, or `None` when it is not on disk.
+/// Blocking.
+pub(super) fn count_property_images(
+    config: &AppConfig,
+    status: &str,
+    folder_path: &str,
+) -> Option<ImageCounts> {
+    get_base_path_for_status(config, status)
+        .ok()
+        .zip(validate_relative_folder_path(folder_path).ok())
+        .map(|(base, relative)| base.join(relative))
+        .filter(|dir| dir.is_dir())
+        .map(|dir| count_images(&dir))
+}
+
+/// Properties matching `options`, ordered by city and name, with tags.
+async fn load_export_properties(
+    pool: &SqlitePool,
+    options: &PropertyExportOptions,
+) -> Result<Vec<Property>, String> {
+    if let Some(invalid) = options
+        .statuses
+        .iter()
+        .find(|s| !VALID_STATUSES.contains(&s.as_str()))
+    {
+        return Err(format!("Invalid status: {invalid}"));
+    }
+
+    let placeholders = |n: usize| vec!["?"; n].join(", ");
+    let mut conditions = vec!["deleted_at IS NULL".to_string()];
+    if !options.statuses.is_empty() {
+        conditions.push(format!(
+            "status IN ({})",
+            placeholders(options.statuses.len())
+        ));
+    }
+    if !options.cities.is_empty() {
+        conditions.push(format!(
+            "city COLLATE NOCASE IN ({})",
+            placeholders(options.cities.len())
+        ));
+    }
+    if options.created_from.is_some() {
+        conditions.push("created_at >= ?".to_string());
+    }
+    if options.created_to.is_some() {
+        conditions.push("created_at <= ?".to_string());
+    }
+    let sql = format!(
+        "SELECT * FROM properties WHERE {} ORDER BY city COLLATE NOCASE, name COLLATE NOCASE",
+        conditions.join(" AND ")
+    );
+
+    let mut query = sqlx::query(&sql);
+    for value in options.statuses.iter().chain(&options.cities) {
+        query = query.bind(value);
+    }
+    for bound in [options.created_from, options.created_to]
+        .into_iter()
+        .flatten()
+    {
+        query = query.bind(bound);
+    }
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load properties: {e}"))?;
+
+    let mut properties: Vec<Property> = rows
+        .into_iter()
+        .map(|row| {
+            let created_at_timestamp: i64 = row.get("created_at");
+            let updated_at_timestamp: i64 = row.get("updated_at");
+
+            Property {
+                id: Some(row.get("id")),
+                name: row.get("name"),
+                city: row.get("city"),
+                status: row.get("status"),
+                folder_path: row.get("folder_path"),
+                notes: row.get("notes"),
+                code: row.get("code"),
+                tags: Vec::new(),
+                created_at: chrono::DateTime::from_timestamp_millis(created_at_timestamp)
+                    .unwrap_or_else(chrono::Utc::now),
+                updated_at: chrono::DateTime::from_timestamp_millis(updated_at_timestamp)
+                    .unwrap_or_else(chrono::Utc::now),
+                completed: None,
+            }
+        })
+        .collect();
+    attach_tags(pool, &mut properties).await?;
+
+    Ok(properties)
+}
+
+/// Turn properties into export records, counting images on disk. Blocking.
+fn build_export_records(properties: Vec<Property>, config: &AppConfig) -> Vec<ExportRecord> {
+    let timestamp =
+        |at: chrono::DateTime<chrono::Utc>| at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    properties
+        .into_iter()
+        .map(|property| {
+            let image_counts =
+                count_property_images(config, &property.status, &property.folder_path);
+            ExportRecord {
+                id: property.id.unwrap_or_default(),
+                name: property.name,
+                city: property.city,
+                status: property.status,
+                code: property.code,
+                folder_path: property.folder_path,
+                notes: property.notes,
+                tags: property.tags,
+                created_at: timestamp(property.created_at),
+                updated_at: timestamp(property.updated_at),
+                image_counts,
+            }
+        })
+        .collect()
+}
+
+fn write_csv(records: &[ExportRecord]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(UTF8_BOM.as_bytes().to_vec());
+    writer
+        .write_record(CSV_HEADERS)
+        .map_err(|e| format!("Failed to write CSV: {e}"))?;
+    for record in records {
+        let counts = record.image_counts.map_or_else(
+            || vec![String::new(); 5],
+            |c| {
+                [
+                    c.originals,
+                    c.internet,
+                    c.internet_aggelia,
+                    c.watermark,
+                    c.watermark_aggelia,
+                ]
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+            },
+        );
+        let fields = [
+            record.id.to_string(),
+            record.name.clone(),
+            record.city.clone(),
+            record.status.clone(),
+            record.code.clone().unwrap_or_default(),
+            record.folder_path.clone(),
+            record.notes.clone().unwrap_or_default(),
+            record.tags.join("; "),
+            record.created_at.clone(),
+            record.updated_at.clone(),
+        ];
+        writer
+            .write_record(fields.iter().chain(&counts))
+            .map_err(|e| format!("Failed to write CSV: {e}"))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write CSV: {e}"))
+}
+
+/// Write the properties matching `options` to `path` as CSV or JSON.
+async fn export_properties_to(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    path: &Path,
+    options: &PropertyExportOptions,
+) -> Result<usize, String> {
+    let properties = load_export_properties(pool, options).await?;
+    let (config, path, format) = (config.clone(), path.to_path_buf(), options.format);
+    tokio::task::spawn_blocking(move || {
+        let records = build_export_records(properties, &config);
+        let bytes = match format {
+            ExportFormat::Csv => write_csv(&records)?,
+            ExportFormat::Json => serde_json::to_vec_pretty(&records).map_err(|e| e.to_string())?,
+        };
+        std::fs::write(&path, bytes)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        Ok(records.len())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+/// Export the property catalogue to `path`. `data` is a
+/// `PropertyExportResult`.
+#[tauri::command]
+pub async fn export_properties(
+    app: tauri::AppHandle,
+    path: String,
+    options: PropertyExportOptions,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let config = crate::config::get_cached_config(&app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    match export_properties_to(pool, &config, Path::new(&path), &options).await {
+        Ok(exported) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(
+                serde_json::to_value(PropertyExportResult { path, exported })
+                    .map_err(|e| e.to_string())?,
+            ),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+/// One CSV line of an import. Blank cells are `None` and leave the stored
+/// value alone.
+#[derive(Debug, Default)]
+struct ImportRow {
+    line: usize,
+    name: Option<String>,
+    city: Option<String>,
+    status: Option<String>,
+    code: Option<String>,
+    folder_path: Option<String>,
+    notes: Option<String>,
+}
+
+/// Parse an import CSV. Columns are found by header name, case-insensitively;
+/// unknown columns (such as the image counts of an export) are ignored.
+/// Semicolon-separated files, as Excel writes them in many locales, are
+/// detected from the header line.
+fn parse_import_csv(text: &str) -> Result<Vec<ImportRow>, String> {
+    let text = text.trim_start_matches(UTF8_BOM);
+    let header_line = text.lines().next().unwrap_or_default();
+    let delimiter = if header_line.contains(';') && !header_line.contains(',') {
+        b';'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    let columns: HashMap<String, usize> = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV header: {e}"))?
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.to_lowercase(), i))
+        .collect();
+    if !columns.contains_key("code") && !columns.contains_key("folder_path") {
+        return Err("The CSV needs a 'code' or 'folder_path' column".to_string());
+    }
+
+    let mut rows = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| format!("Failed to read CSV line {}: {e}", i + 2))?;
+        let cell = |name: &str| {
+            columns
+                .get(name)
+                .and_then(|&i| record.get(i))
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        rows.push(ImportRow {
+            // Line 1 is the header.
+            line: i + 2,
+            name: cell("name"),
+            city: cell("city"),
+            status: cell("status").map(|status| status.to_uppercase()),
+            code: cell("code"),
+            folder_path: cell("folder_path"),
+            notes: cell("notes"),
+        });
+    }
+    Ok(rows)
+}
+
+/// Check a row's values on their own, before any lookups.
+fn validate_import_row(row: &ImportRow, code_pattern: &str) -> Result<(), String> {
+    if let Some(status) = row.status.as_deref() {
+        if !VALID_STATUSES.contains(&status) {
+            return Err(format!("Invalid status: {status}"));
+        }
+    }
+    if let Some(code) = row.code.as_deref() {
+        validate_code_format(code, code_pattern)?;
+    }
+    if let Some(folder_path) = row.folder_path.as_deref() {
+        let relative = validate_relative_folder_path(folder_path)
+            .map_err(|e| format!("Invalid folder_path '{folder_path}': {e}"))?;
+        if relative.components().count() != 2 {
+            return Err(format!(
+                "Invalid folder_path '{folder_path}': expected 'City/Folder'"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// What importing one row did.
+enum ImportOutcome {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// Changes to an updated property that touch its folder, run once the
+/// import is committed.
+struct FolderChanges {
+    property_id: i64,
+    status: Option<StatusChange>,
+    code: Option<String>,
+}
+
+/// Find the live property a row refers to: by code first, then by folder
+/// path. A code held by a trashed property is an error rather than a
+/// duplicate.
+async fn find_import_match(
+    conn: &mut SqliteConnection,
+    row: &ImportRow,
+) -> Result<Option<i64>, String> {
+    if let Some(code) = row.code.as_deref() {
+        let found: Option<(i64, Option<i64>)> =
+            sqlx::query_as("SELECT id, deleted_at FROM properties WHERE code = ? COLLATE NOCASE")
+                .bind(code)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(|e| format!("Failed to look up code: {e}"))?;
+        match found {
+            Some((_, Some(_))) => {
+                return Err(format!("Code '{code}' belongs to a property in the trash"))
+            }
+            Some((id, None)) => return Ok(Some(id)),
+            None => {}
+        }
+    }
+    let Some(folder_path) = row.folder_path.as_deref() else {
+        return Ok(None);
+    };
+    sqlx::query_scalar("SELECT id FROM properties WHERE folder_path = ? AND deleted_at IS NULL")
+        .bind(folder_path)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to look up folder: {e}"))
+}
+
+/// Create a property from a row. Needs at least a name and a city.
+async fn create_from_row(
+    conn: &mut SqliteConnection,
+    row: &ImportRow,
+    now: i64,
+) -> Result<ImportOutcome, String> {
+    let (Some(name), Some(city)) = (row.name.as_deref(), row.city.as_deref()) else {
+        return Err("No matching property; 'name' and 'city' are needed to create one".into());
+    };
+    let folder_path = row.folder_path.clone().unwrap_or_else(|| {
+        let folder_name = row.code.as_ref().map_or_else(
+            || name.to_string(),
+            |code| format!("{name} ({})", code.replace('/', "-")),
+        );
+        get_relative_folder_path(city, &folder_name)
+    });
+
+    // The insert trigger adds the property_codes row for the code.
+    sqlx::query(
+        "INSERT INTO properties (name, city, status, folder_path, notes, code, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(name)
+    .bind(city)
+    .bind(row.status.as_deref().unwrap_or("NEW"))
+    .bind(&folder_path)
+    .bind(&row.notes)
+    .bind(&row.code)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to create property: {e}"))?;
+
+    sqlx::query(
+        "INSERT INTO cities (name, usage_count, created_at) VALUES (?, 1, ?)
+         ON CONFLICT(name) DO UPDATE SET usage_count = usage_count + 1",
+    )
+    .bind(city)
+    .bind(now)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to update city: {e}"))?;
+
+    Ok(ImportOutcome::Created)
+}
+
+/// Apply a row's non-blank values to an existing property and log each
+/// change in the audit log. A new status or code is not written here: both
+/// come back in `FolderChanges` (the status with its folder move planned)
+/// for `import_rows` to run once the import is committed, and `queued_codes`
+/// holds the codes earlier lines queued that way. Name, city and folder
+/// changes would leave the folder behind, so they are refused.
+async fn update_from_row(
+    conn: &mut SqliteConnection,
+    config: &AppConfig,
+    property_id: i64,
+    row: &ImportRow,
+    queued_codes: &HashMap<i64, String>,
+    now: i64,
+) -> Result<(ImportOutcome, FolderChanges), String> {
+    let (name, city, status, folder_path, notes, code): (
+        String,
+        String,
+        String,
+        String,
+        Option<String>,
+        Option<String>,
+    ) = sqlx::query_as(
+        "SELECT name, city, status, folder_path, notes, code FROM properties WHERE id = ?",
+    )
+    .bind(property_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to load property {property_id}: {e}"))?;
+
+    if row.city.as_ref().is_some_and(|new| *new != city)
+        || row
+            .folder_path
+            .as_ref()
+            .is_some_and(|new| *new != folder_path)
+    {
+        return Err(format!(
+            "Cannot move '{folder_path}' by import; change its city or folder in the app"
+        ));
+    }
+    if row.name.as_ref().is_some_and(|new| *new != name) {
+        return Err(format!(
+            "Cannot rename '{folder_path}' by import; rename it in the app"
+        ));
+    }
+
+    let new_notes = row.notes.clone().or_else(|| notes.clone());
+    let code = queued_codes.get(&property_id).cloned().or(code);
+    // Codes compare case-insensitively.
+    let new_code = row.code.clone().filter(|new| {
+        !code
+            .as_ref()
+            .is_some_and(|old| new.eq_ignore_ascii_case(old))
+    });
+
+    let status_change = match row.status.as_deref() {
+        Some(new_status) if new_status != status => Some(StatusChange {
+            property_id,
+            folder_move: plan_folder_move(config, &folder_path, &status, new_status)?,
+            old_status: status,
+            new_status: new_status.to_string(),
+            folder_path,
+            source: "import_properties".to_string(),
+        }),
+        _ => None,
+    };
+
+    let notes_changed = new_notes != notes;
+    let outcome = if notes_changed || status_change.is_some() || new_code.is_some() {
+        ImportOutcome::Updated
+    } else {
+        ImportOutcome::Unchanged
+    };
+    let changes = FolderChanges {
+        property_id,
+        status: status_change,
+        code: new_code,
+    };
+    if notes_changed {
+        sqlx::query("UPDATE properties SET notes = ?, updated_at = ? WHERE id = ?")
+            .bind(&new_notes)
+            .bind(now)
+            .bind(property_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to update property {property_id}: {e}"))?;
+    }
+
+    Ok((outcome, changes))
+}
+
+async fn import_row(
+    conn: &mut SqliteConnection,
+    config: &AppConfig,
+    row: &ImportRow,
+    queued_codes: &HashMap<i64, String>,
+) -> Result<(ImportOutcome, Option<FolderChanges>), String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let property_id = find_import_match(conn, row).await?;
+
+    if let Some(code) = row.code.as_deref() {
+        let holder: Option<(String, String)> = sqlx::query_as(
+            "SELECT name, city FROM properties WHERE code = ? COLLATE NOCASE AND id IS NOT ?",
+        )
+        .bind(code)
+        .bind(property_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to look up code: {e}"))?;
+        if let Some((name, city)) = holder {
+            return Err(format!(
+                "Code '{code}' is already used by '{name}' in {city}"
+            ));
+        }
+    }
+    if let Some(folder_path) = row.folder_path.as_deref() {
+        let holder: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM properties WHERE folder_path = ? AND deleted_at IS NULL AND id IS NOT ?",
+        )
+        .bind(folder_path)
+        .bind(property_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to look up folder: {e}"))?;
+        if let Some(id) = holder {
+            return Err(format!("Folder '{folder_path}' belongs to property {id}"));
+        }
+    }
+
+    match property_id {
+        Some(id) => {
+            let (outcome, changes) =
+                update_from_row(conn, config, id, row, queued_codes, now).await?;
+            Ok((outcome, Some(changes)))
+        }
+        None => Ok((create_from_row(conn, row, now).await?, None)),
+    }
+}
+
+/// Import the rows in one transaction, each inside its own savepoint so a
+/// bad line is skipped without leaving half of it behind. Status and code
+/// changes run after the commit, through `run_status_change` and
+/// `apply_primary_code`, which move and rename the folder like any other
+/// status or code change. With `dry_run` the transaction is rolled back at
+/// the end and neither runs.
+async fn import_rows(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    rows: &[ImportRow],
+    dry_run: bool,
+) -> Result<PropertyImportResult, String> {
+    let mut result = PropertyImportResult {
+        dry_run,
+        ..PropertyImportResult::default()
+    };
+    let mut folder_changes = Vec::new();
+    let mut queued_codes = HashMap::new();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {e}"))?;
+
+    for row in rows {
+        if let Err(e) = validate_import_row(row, &config.code_pattern) {
+            result.errors.push(format!("Line {}: {e}", row.line));
+            continue;
+        }
+        let mut savepoint = tx
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start savepoint: {e}"))?;
+        match import_row(&mut savepoint, config, row, &queued_codes).await {
+            Ok((outcome, changes)) => {
+                savepoint
+                    .commit()
+                    .await
+                    .map_err(|e| format!("Failed to release savepoint: {e}"))?;
+                match outcome {
+                    ImportOutcome::Created => result.created += 1,
+                    ImportOutcome::Updated => result.updated += 1,
+                    ImportOutcome::Unchanged => result.unchanged += 1,
+                }
+                if let Some(changes) = changes {
+                    if let Some(code) = &changes.code {
+                        queued_codes.insert(changes.property_id, code.clone());
+                    }
+                    folder_changes.push((row.line, changes));
+                }
+            }
+            Err(e) => {
+                savepoint
+                    .rollback()
+                    .await
+                    .map_err(|e| format!("Failed to roll back savepoint: {e}"))?;
+                result.errors.push(format!("Line {}: {e}", row.line));
+            }
+        }
+    }
+
+    if dry_run {
+        tx.rollback().await
+    } else {
+        tx.commit().await
+    }
+    .map_err(|e| format!("Failed to finish import: {e}"))?;
+    if dry_run {
+        return Ok(result);
+    }
+
+    for (line, changes) in folder_changes {
+        if let Some(change) = changes.status {
+            match run_status_change(pool, &change).await {
+                Ok(None) => {}
+                Ok(Some(warning)) => result.errors.push(format!("Line {line}: {warning}")),
+                Err(e) => result.errors.push(format!("Line {line}: {e}")),
+            }
+        }
+        if let Some(code) = changes.code {
+            if let Err(e) = apply_primary_code(
+                pool,
+                config,
+                changes.property_id,
+                &code,
+                "import_properties",
+            )
+            .await
+            {
+                result.errors.push(format!("Line {line}: {e}"));
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Create or update properties from the CSV at `path`. Rows match existing
+/// properties by `code`, then by `folder_path`; blank cells keep the stored
+/// value. A new status moves the folder the way `update_property_status`
+/// does and a new code renames it the way `set_property_code` does; a new
+/// name, city or folder for an existing property is refused. With
+/// `dry_run` nothing is saved or moved. `data` is a `PropertyImportResult`.
+#[tauri::command]
+pub async fn import_properties(
+    app: tauri::AppHandle,
+    path: String,
+    dry_run: Option<bool>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let config = crate::config::get_cached_config(&app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    let read_path = path.clone();
+    let text = tokio::task::spawn_blocking(move || std::fs::read_to_string(&read_path))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?
+        .map_err(|e| format!("Failed to read {path}: {e}"));
+
+    let result = match text.and_then(|text| parse_import_csv(&text)) {
+        Ok(rows) => import_rows(pool, &config, &rows, dry_run.unwrap_or(false)).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(result) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+
+    #[tokio::test]
+    async fn export_filters_and_counts_images_per_subfolder() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = AppConfig {
+            new_folder_path: tmp.path().join("NEW").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        };
+        let villa = tmp.path().join("NEW/Athens/Villa (AB1)");
+        std::fs::create_dir_all(villa.join("INTERNET/AGGELIA")).unwrap();
+        for file in [
+            "1.jpg",
+            "2.JPG",
+            "notes.txt",
+            "INTERNET/1.jpg",
+            "INTERNET/AGGELIA/1.jpg",
+        ] {
+            std::fs::write(villa.join(file), b"x").unwrap();
+        }
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa (AB1)", Some("AB1"))
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Loft", "Rome", "DONE", "Loft", None)
+            .await
+            .unwrap();
+
+        let options = PropertyExportOptions {
+            format: ExportFormat::Csv,
+            statuses: vec!["NEW".into()],
+            cities: vec!["athens".into()],
+            created_from: Some(0),
+            created_to: None,
+        };
+        let path = tmp.path().join("export.csv");
+        let exported = export_properties_to(&pool, &config, &path, &options)
+            .await
+            .unwrap();
+        assert_eq!(exported, 1);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with(UTF8_BOM));
+        let lines: Vec<&str> = text.trim_start_matches(UTF8_BOM).lines().collect();
+        assert_eq!(lines[0], CSV_HEADERS.join(","));
+        assert!(lines[1].contains(",Villa,Athens,NEW,AB1,Athens/Villa (AB1),"));
+        assert!(lines[1].ends_with(",2,1,1,0,0"));
+
+        let options = PropertyExportOptions {
+            format: ExportFormat::Json,
+            statuses: Vec::new(),
+            cities: Vec::new(),
+            created_from: None,
+            created_to: None,
+        };
+        let path = tmp.path().join("export.json");
+        export_properties_to(&pool, &config, &path, &options)
+            .await
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json[0]["name"], "Villa");
+        assert_eq!(json[0]["imageCounts"]["internetAggelia"], 1);
+        // DONE is not configured, so the Loft folder cannot be counted.
+        assert!(json[1]["imageCounts"].is_null());
+    }
+
+    #[tokio::test]
+    async fn import_matches_by_code_then_folder_and_skips_bad_lines() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = AppConfig {
+            new_folder_path: tmp.path().join("NEW").to_string_lossy().to_string(),
+            done_folder_path: tmp.path().join("DONE").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        };
+        std::fs::create_dir_all(tmp.path().join("NEW/Athens/Villa (AB1)")).unwrap();
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa (AB1)", Some("AB1"))
+            .await
+            .unwrap();
+        std::fs::create_dir_all(tmp.path().join("NEW/Rome/Loft")).unwrap();
+        add_property_to_database(&pool, "Loft", "Rome", "NEW", "Loft", None)
+            .await
+            .unwrap();
+
+        let csv = "\u{feff}Code;Folder_Path;Name;City;Status;Notes;images\n\
+                   ab1;;;;DONE;Shot twice;4\n\
+                   CD2;Rome/Loft;;;;;\n\
+                   ;Paris/Flat;Flat;Paris;;;\n\
+                   ;../escape;X;Y;;;\n\
+                   EF3;;;;;;\n\
+                   cd2;Rome/Loft;;;;;\n\
+                   AB1;;;Paris;;;\n\
+                   AB1;;Villa Two;;;;\n";
+        let rows = parse_import_csv(csv).unwrap();
+        assert_eq!(rows.len(), 8);
+
+        let preview = import_rows(&pool, &config, &rows, true).await.unwrap();
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+        assert!(tmp.path().join("NEW/Athens/Villa (AB1)").exists());
+        assert!(tmp.path().join("NEW/Rome/Loft").exists());
+
+        let result = import_rows(&pool, &config, &rows, false).await.unwrap();
+        assert_eq!(
+            (result.created, result.updated, result.unchanged),
+            (1, 2, 1)
+        );
+        assert_eq!(result.errors.len(), 4, "{:?}", result.errors);
+        assert!(result.errors[0].starts_with("Line 5: Invalid folder_path"));
+        assert!(result.errors[1].starts_with("Line 6: No matching property"));
+        assert!(result.errors[2].starts_with("Line 8: Cannot move"));
+        assert!(result.errors[3].starts_with("Line 9: Cannot rename"));
+        assert_eq!(preview.errors, result.errors);
+        // The status change moved the folder to the DONE root.
+        assert!(!tmp.path().join("NEW/Athens/Villa (AB1)").exists());
+        assert!(tmp.path().join("DONE/Athens/Villa (AB1)").exists());
+        // The code change renamed the folder to carry the new suffix.
+        assert!(!tmp.path().join("NEW/Rome/Loft").exists());
+        assert!(tmp.path().join("NEW/Rome/Loft (CD2)").exists());
+
+        let rows: Vec<(String, String, Option<String>, String)> =
+            sqlx::query_as("SELECT name, status, code, folder_path FROM properties ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "Villa".into(),
+                    "DONE".into(),
+                    Some("AB1".into()),
+                    "Athens/Villa (AB1)".into()
+                ),
+                (
+                    "Loft".into(),
+                    "NEW".into(),
+                    Some("CD2".into()),
+                    "Rome/Loft (CD2)".into()
+                ),
+                ("Flat".into(), "NEW".into(), None, "Paris/Flat".into()),
+            ]
+        );
+        let primary: String = sqlx::query_scalar(
+            "SELECT code FROM property_codes WHERE property_id = 2 AND is_primary = 1",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(primary, "CD2");
+        let notes: Option<String> = sqlx::query_scalar("SELECT notes FROM properties WHERE id = 1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(notes.as_deref(), Some("Shot twice"));
+    }
+}
@@ -1,12 +1,156 @@
 //! City autocomplete commands. Used by the property add/edit modals to
 //! show recently-used city names ordered by usage count.
 //!
+//! Search compares normalized keys rather than the raw names: diacritics
+//! are stripped and case folded on both sides, and by default Greek is
+//! transliterated to Latin so "athina" finds "Αθήνα" and "Ηράκλειο" finds
+//! "Irakleio".
+//!
+//! Also `merge_cities` / `rename_city`, which fold misspelled duplicates
+//! like "Athína" into one city: the property rows, the city folders under
+//! every status folder and the `cities` usage counts. The folder moves are
+//! journaled in `pending_operations` like status changes, so a merge
+//! interrupted between the moves and the database write is finished on the
+//! next start.
+//!
 //! Extracted from database.rs in the database-module split.
 
-use sqlx::Row;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use sqlx::{Row, SqlitePool};
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::config::AppConfig;
+use crate::database::orphans::status_roots;
+use crate::database::search::fold_search_text;
+use crate::database::types::{City, CityFolderMove, CityMergePlan, CommandResult};
+use crate::database::{
+    discard_journal_entry, get_database_pool, journal_status_change, move_folder,
+    record_property_event, PropertyEventKind, StatusChange, CITY_MERGE_SOURCE,
+};
+
+/// Greek letter pairs transliterated as one sound. Checked before
+/// `GREEK_LETTERS`.
+const GREEK_DIGRAPHS: &[(&str, &str)] = &[("ου", "ou"), ("αυ", "av"), ("ευ", "ev"), ("ηυ", "iv")];
+
+/// Lowercase unaccented Greek letters and their Latin spelling.
+const GREEK_LETTERS: &[(char, &str)] = &[
+    ('α', "a"),
+    ('β', "v"),
+    ('γ', "g"),
+    ('δ', "d"),
+    ('ε', "e"),
+    ('ζ', "z"),
+    ('η', "i"),
+    ('θ', "th"),
+    ('ι', "i"),
+    ('κ', "k"),
+    ('λ', "l"),
+    ('μ', "m"),
+    ('ν', "n"),
+    ('ξ', "x"),
+    ('ο', "o"),
+    ('π', "p"),
+    ('ρ', "r"),
+    ('σ', "s"),
+    ('τ', "t"),
+    ('υ', "y"),
+    ('φ', "f"),
+    ('χ', "ch"),
+    ('ψ', "ps"),
+    ('ω', "o"),
+];
+
+/// Latin spellings that transliterations disagree on ("Glyfada" and
+/// "Glifada", "Chalandri" and "Halandri"), mapped to one form.
+const LATIN_VARIANTS: &[(&str, &str)] = &[("ch", "h"), ("kh", "h"), ("ph", "f"), ("y", "i")];
 
-use crate::database::get_database_pool;
-use crate::database::types::{City, CommandResult};
+/// Maximum number of cities `search_cities` returns.
+const SEARCH_LIMIT: usize = 10;
+
+fn transliterate_greek(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some((from, to)) = GREEK_DIGRAPHS
+            .iter()
+            .find(|(from, _)| rest.starts_with(from))
+        {
+            out.push_str(to);
+            rest = &rest[from.len()..];
+            continue;
+        }
+        match GREEK_LETTERS.iter().find(|(from, _)| *from == c) {
+            Some((_, to)) => out.push_str(to),
+            None => out.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+
+    let latin = LATIN_VARIANTS
+        .iter()
+        .fold(out, |text, (from, to)| text.replace(from, to));
+    // Doubled letters are spelled both ways too ("Kifissia").
+    let mut previous = None;
+    latin
+        .chars()
+        .filter(|&c| {
+            let repeated = c.is_alphabetic() && previous == Some(c);
+            previous = Some(c);
+            !repeated
+        })
+        .collect()
+}
+
+/// Normalize a city name or query for comparison: strip diacritics, fold
+/// case, collapse punctuation and spacing to single spaces and, with
+/// `transliterate`, spell Greek in Latin letters.
+pub(super) fn city_search_key(text: &str, transliterate: bool) -> String {
+    let stripped: String = text.nfd().filter(|c| !is_combining_mark(*c)).collect();
+    let folded = fold_search_text(&stripped)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if transliterate {
+        transliterate_greek(&folded)
+    } else {
+        folded
+    }
+}
+
+/// How well a city key matches a query key; lower is better. `None` when
+/// the query does not occur in the name at all.
+fn match_rank(name_key: &str, query_key: &str) -> Option<u8> {
+    if name_key == query_key {
+        Some(0)
+    } else if name_key.starts_with(query_key) {
+        Some(1)
+    } else if name_key.split(' ').any(|word| word.starts_with(query_key)) {
+        Some(2)
+    } else if name_key.contains(query_key) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+fn city_from_row(row: &sqlx::sqlite::SqliteRow) -> City {
+    let created_at_timestamp: i64 = row.get("created_at");
+    let created_at = chrono::DateTime::from_timestamp_millis(created_at_timestamp)
+        .unwrap_or_else(chrono::Utc::now);
+
+    City {
+        id: Some(row.get("id")),
+        name: row.get("name"),
+        usage_count: row.get("usage_count"),
+        created_at,
+    }
+}
 
 // City operations for autocomplete
 #[tauri::command]
@@ -18,22 +162,7 @@ pub async fn get_cities(app: tauri::AppHandle) -> Result<CommandResult, String>
         .await
         .map_err(|e| format!("Failed to fetch cities: {}", e))?;
 
-    let mut cities = Vec::new();
-
-    for row in rows {
-        let created_at_timestamp: i64 = row.get("created_at");
-        let created_at = chrono::DateTime::from_timestamp_millis(created_at_timestamp)
-            .unwrap_or_else(chrono::Utc::now);
-
-        let city = City {
-            id: Some(row.get("id")),
-            name: row.get("name"),
-            usage_count: row.get("usage_count"),
-            created_at,
-        };
-
-        cities.push(city);
-    }
+    let cities: Vec<City> = rows.iter().map(city_from_row).collect();
 
     Ok(CommandResult {
         success: true,
@@ -42,40 +171,659 @@ pub async fn get_cities(app: tauri::AppHandle) -> Result<CommandResult, String>
     })
 }
 
+/// Cities matching `query`, best match first, then by usage count.
+/// Matching ignores accents and case; with `transliterate` (the default)
+/// Greek and Latin spellings of a name match each other too.
+async fn search_cities_in(
+    pool: &SqlitePool,
+    query: &str,
+    transliterate: bool,
+) -> Result<Vec<City>, String> {
+    // The cities table stays small, so it is ranked in memory rather than
+    // keeping a normalized copy of every name in SQL.
+    let rows = sqlx::query("SELECT * FROM cities")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to search cities: {}", e))?;
+
+    let query_key = city_search_key(query, transliterate);
+    let mut ranked: Vec<(u8, City)> = rows
+        .iter()
+        .map(city_from_row)
+        .filter_map(|city| {
+            match_rank(&city_search_key(&city.name, transliterate), &query_key)
+                .map(|rank| (rank, city))
+        })
+        .collect();
+    ranked.sort_by(|(rank_a, a), (rank_b, b)| {
+        rank_a
+            .cmp(rank_b)
+            .then(b.usage_count.cmp(&a.usage_count))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(ranked
+        .into_iter()
+        .take(SEARCH_LIMIT)
+        .map(|(_, city)| city)
+        .collect())
+}
+
 #[tauri::command]
-pub async fn search_cities(app: tauri::AppHandle, query: String) -> Result<CommandResult, String> {
+pub async fn search_cities(
+    app: tauri::AppHandle,
+    query: String,
+    transliterate: Option<bool>,
+) -> Result<CommandResult, String> {
     let pool = get_database_pool(&app)?;
 
-    let search_pattern = format!("%{}%", query);
+    let cities = search_cities_in(pool, &query, transliterate.unwrap_or(true)).await?;
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(cities).map_err(|e| e.to_string())?),
+    })
+}
+
+/// A property row whose city changes in a merge.
+#[derive(Debug)]
+struct CityRow {
+    id: i64,
+    city: String,
+    status: String,
+    folder_path: String,
+    trashed: bool,
+}
+
+/// List the folder moves for merging `sources` into `target` across all
+/// status folders, and the targets that already exist. Blocking.
+fn plan_city_folder_moves(
+    roots: &[(String, PathBuf)],
+    sources: &[String],
+    target: &str,
+    tracked: &HashMap<(String, String), i64>,
+) -> Result<(Vec<CityFolderMove>, Vec<String>), String> {
+    let mut moves = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut destinations = HashSet::new();
+
+    for (status, root) in roots {
+        let target_dir = crate::paths::safe_join(root, target)
+            .map_err(|e| format!("Invalid city name '{target}': {e}"))?;
+        for source in sources {
+            let source_dir = crate::paths::safe_join(root, source)
+                .map_err(|e| format!("Invalid city name '{source}': {e}"))?;
+            if !source_dir.is_dir() {
+                continue;
+            }
+            let entries = fs::read_dir(&source_dir)
+                .map_err(|e| format!("Failed to read {}: {e}", source_dir.display()))?;
+            for entry in entries.flatten() {
+                if !entry.path().is_dir() {
+                    continue;
+                }
+                let folder_name = entry.file_name().to_string_lossy().to_string();
+                let from = entry.path();
+                let to = target_dir.join(&folder_name);
+                // A case-only rename finds its own folder on case-insensitive disks.
+                let same_folder =
+                    from.to_string_lossy().to_lowercase() == to.to_string_lossy().to_lowercase();
+                if (to.exists() && !same_folder) || !destinations.insert(to.clone()) {
+                    conflicts.push(to.to_string_lossy().to_string());
+                }
+                moves.push(CityFolderMove {
+                    status: status.clone(),
+                    property_id: tracked
+                        .get(&(status.clone(), format!("{source}/{folder_name}")))
+                        .copied(),
+                    from: from.to_string_lossy().to_string(),
+                    to: to.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+
+    Ok((moves, conflicts))
+}
+
+/// Move every planned folder. If one fails, the ones already moved are put
+/// back so the disk is left as it was. Blocking.
+fn move_city_folders(moves: &[CityFolderMove]) -> Result<(), String> {
+    for (done, folder_move) in moves.iter().enumerate() {
+        if let Err(e) = move_folder(
+            &PathBuf::from(&folder_move.from),
+            &PathBuf::from(&folder_move.to),
+        ) {
+            undo_city_folder_moves(&moves[..done]);
+            return Err(format!("Failed to move {}: {e}", folder_move.from));
+        }
+    }
+    Ok(())
+}
+
+fn undo_city_folder_moves(moves: &[CityFolderMove]) {
+    for folder_move in moves.iter().rev() {
+        let _ = move_folder(
+            &PathBuf::from(&folder_move.to),
+            &PathBuf::from(&folder_move.from),
+        );
+    }
+}
+
+/// Journal the move of every tracked folder before any folder moves, so a
+/// crash before `write_city_merge` commits is finished on the next start.
+/// Returns the journal entry ids.
+async fn journal_city_moves(
+    pool: &SqlitePool,
+    plan: &CityMergePlan,
+    rows: &[CityRow],
+) -> Result<Vec<i64>, String> {
+    let status_by_id: HashMap<i64, &str> = rows
+        .iter()
+        .map(|row| (row.id, row.status.as_str()))
+        .collect();
+    let mut journal_ids = Vec::new();
+    for folder_move in &plan.moves {
+        let Some(property_id) = folder_move.property_id else {
+            continue;
+        };
+        let status = status_by_id.get(&property_id).copied().unwrap_or_default();
+        let folder_name = PathBuf::from(&folder_move.to)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let change = StatusChange {
+            property_id,
+            old_status: status.to_string(),
+            new_status: status.to_string(),
+            folder_path: format!("{}/{folder_name}", plan.target),
+            folder_move: Some((
+                PathBuf::from(&folder_move.from),
+                PathBuf::from(&folder_move.to),
+            )),
+            source: CITY_MERGE_SOURCE.to_string(),
+        };
+        match journal_status_change(pool, &change).await {
+            Ok(id) => journal_ids.push(id),
+            Err(e) => {
+                discard_journal_entries(pool, &journal_ids).await;
+                return Err(e);
+            }
+        }
+    }
+    Ok(journal_ids)
+}
+
+/// Drop the journal entries of a merge whose folders were put back.
+async fn discard_journal_entries(pool: &SqlitePool, journal_ids: &[i64]) {
+    for &id in journal_ids {
+        // Left behind, startup recovery finds the source folder and drops it.
+        let _ = discard_journal_entry(pool, id).await;
+    }
+}
+
+/// Rewrite the rows and the `cities` table for a merge in one transaction,
+/// clearing its journal entries.
+async fn write_city_merge(
+    pool: &SqlitePool,
+    plan: &CityMergePlan,
+    rows: &[CityRow],
+    journal_ids: &[i64],
+) -> Result<(), String> {
+    let moves_by_property: HashMap<i64, &CityFolderMove> = plan
+        .moves
+        .iter()
+        .filter_map(|m| m.property_id.map(|id| (id, m)))
+        .collect();
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {e}"))?;
+
+    for row in rows {
+        let folder_name = row.folder_path.split('/').next_back().unwrap_or_default();
+        sqlx::query("UPDATE properties SET city = ?, folder_path = ?, updated_at = ? WHERE id = ?")
+            .bind(&plan.target)
+            .bind(format!("{}/{folder_name}", plan.target))
+            .bind(now)
+            .bind(row.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to update property {}: {e}", row.id))?;
+        record_property_event(
+            &mut tx,
+            row.id,
+            PropertyEventKind::CityChange,
+            Some(&row.city),
+            Some(&plan.target),
+            "merge_cities",
+        )
+        .await?;
+        if let Some(folder_move) = moves_by_property.get(&row.id) {
+            record_property_event(
+                &mut tx,
+                row.id,
+                PropertyEventKind::FolderMove,
+                Some(&folder_move.from),
+                Some(&folder_move.to),
+                "merge_cities",
+            )
+            .await?;
+        }
+    }
 
-    let rows = sqlx::query(
-        "SELECT * FROM cities WHERE name LIKE ? ORDER BY usage_count DESC, name ASC LIMIT 10",
+    sqlx::query(
+        "INSERT INTO cities (name, usage_count, created_at) VALUES (?, ?, ?)
+         ON CONFLICT(name) DO UPDATE SET usage_count = excluded.usage_count",
     )
-    .bind(&search_pattern)
-    .fetch_all(pool)
+    .bind(&plan.target)
+    .bind(plan.usage_count)
+    .bind(now)
+    .execute(&mut *tx)
     .await
-    .map_err(|e| format!("Failed to search cities: {}", e))?;
+    .map_err(|e| format!("Failed to update city: {e}"))?;
+    for source in &plan.sources {
+        sqlx::query("DELETE FROM cities WHERE name = ?")
+            .bind(source)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to remove city '{source}': {e}"))?;
+    }
+    for id in journal_ids {
+        sqlx::query("DELETE FROM pending_operations WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to clear journal entry: {e}"))?;
+    }
 
-    let mut cities = Vec::new();
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit city merge: {e}"))
+}
 
-    for row in rows {
-        let created_at_timestamp: i64 = row.get("created_at");
-        let created_at = chrono::DateTime::from_timestamp_millis(created_at_timestamp)
-            .unwrap_or_else(chrono::Utc::now);
-
-        let city = City {
-            id: Some(row.get("id")),
-            name: row.get("name"),
-            usage_count: row.get("usage_count"),
-            created_at,
-        };
+/// Why `city` cannot name a city folder, if it cannot: it must be a single
+/// path segment so it stays inside each status folder.
+fn city_name_error(city: &str) -> Option<String> {
+    if city.contains(['/', '\\']) {
+        Some(format!("City '{city}' cannot contain '/' or '\\'"))
+    } else if city == "." || city == ".." {
+        Some(format!("'{city}' is not a valid city name"))
+    } else {
+        None
+    }
+}
 
-        cities.push(city);
+/// Plan the merge of `sources` into `target` and, unless `dry_run` is set or
+/// a target folder already exists, carry it out: folders first, then the
+/// database in one transaction. If the database write fails the folders are
+/// moved back.
+async fn merge_cities_in(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    sources: Vec<String>,
+    target: &str,
+    dry_run: bool,
+) -> Result<CityMergePlan, String> {
+    let mut rows = Vec::new();
+    let mut usage_count = 0;
+    for city in sources.iter().map(String::as_str).chain([target]) {
+        let count: Option<i64> =
+            sqlx::query_scalar("SELECT usage_count FROM cities WHERE name = ?")
+                .bind(city)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| format!("Failed to load city '{city}': {e}"))?;
+        usage_count += count.unwrap_or(0);
+        if city == target {
+            continue;
+        }
+        let city_rows = sqlx::query(
+            "SELECT id, city, status, folder_path, deleted_at IS NOT NULL AS trashed
+             FROM properties WHERE city = ?",
+        )
+        .bind(city)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load properties in '{city}': {e}"))?;
+        rows.extend(city_rows.iter().map(|row| CityRow {
+            id: row.get("id"),
+            city: row.get("city"),
+            status: row.get("status"),
+            folder_path: row.get("folder_path"),
+            trashed: row.get("trashed"),
+        }));
     }
 
-    Ok(CommandResult {
-        success: true,
-        error: None,
-        data: Some(serde_json::to_value(cities).map_err(|e| e.to_string())?),
+    // Trashed folders live in the trash, so only live rows claim folders.
+    let tracked: HashMap<(String, String), i64> = rows
+        .iter()
+        .filter(|row| !row.trashed)
+        .map(|row| ((row.status.clone(), row.folder_path.clone()), row.id))
+        .collect();
+    let roots = status_roots(config);
+    let (task_roots, task_sources, task_target) =
+        (roots.clone(), sources.clone(), target.to_string());
+    let (moves, conflicts) = tokio::task::spawn_blocking(move || {
+        plan_city_folder_moves(&task_roots, &task_sources, &task_target, &tracked)
     })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    let plan = CityMergePlan {
+        target: target.to_string(),
+        sources,
+        properties: rows.len(),
+        usage_count,
+        moves,
+        conflicts,
+        dry_run,
+    };
+    if dry_run || !plan.conflicts.is_empty() {
+        return Ok(plan);
+    }
+
+    // As with status changes, the moves are journaled first and the journal
+    // is cleared in the same transaction as the rows.
+    let journal_ids = journal_city_moves(pool, &plan, &rows).await?;
+    let moves = plan.moves.clone();
+    let move_result = tokio::task::spawn_blocking(move || move_city_folders(&moves))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?;
+    if let Err(e) = move_result {
+        discard_journal_entries(pool, &journal_ids).await;
+        return Err(e);
+    }
+
+    if let Err(e) = write_city_merge(pool, &plan, &rows, &journal_ids).await {
+        let moves = plan.moves.clone();
+        let _ = tokio::task::spawn_blocking(move || undo_city_folder_moves(&moves)).await;
+        discard_journal_entries(pool, &journal_ids).await;
+        return Err(e);
+    }
+
+    // The source city folders are empty now unless they held loose files.
+    let sources = plan.sources.clone();
+    let _ = tokio::task::spawn_blocking(move || {
+        for (_, root) in &roots {
+            for source in &sources {
+                if let Ok(source_dir) = crate::paths::safe_join(root, source) {
+                    let _ = fs::remove_dir(source_dir);
+                }
+            }
+        }
+    })
+    .await;
+
+    Ok(plan)
+}
+
+/// Merge `sources` into `target`: property rows, their folders under every
+/// status folder, and the city usage counts. With `dry_run` only the plan is
+/// returned, listing every folder that would move. `data` is a
+/// `CityMergePlan`; it is also returned when target folders already exist,
+/// which fails the merge before anything is moved.
+#[tauri::command]
+pub async fn merge_cities(
+    app: tauri::AppHandle,
+    sources: Vec<String>,
+    target: String,
+    dry_run: Option<bool>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let config = crate::config::get_cached_config(&app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    let target = target.trim();
+    let mut seen = HashSet::new();
+    let sources: Vec<String> = sources
+        .iter()
+        .map(|city| city.trim().to_string())
+        .filter(|city| !city.is_empty() && city != target && seen.insert(city.clone()))
+        .collect();
+    let error = if target.is_empty() {
+        Some("City cannot be empty".to_string())
+    } else if sources.is_empty() {
+        Some("Choose at least one other city to merge".to_string())
+    } else {
+        std::iter::once(target)
+            .chain(sources.iter().map(String::as_str))
+            .find_map(city_name_error)
+    };
+    if let Some(error) = error {
+        return Ok(CommandResult {
+            success: false,
+            error: Some(error),
+            data: None,
+        });
+    }
+
+    match merge_cities_in(pool, &config, sources, target, dry_run.unwrap_or(false)).await {
+        Ok(plan) => {
+            let error = (!plan.dry_run && !plan.conflicts.is_empty()).then(|| {
+                format!(
+                    "{} folder(s) already exist in '{}'; nothing was moved",
+                    plan.conflicts.len(),
+                    plan.target
+                )
+            });
+            Ok(CommandResult {
+                success: error.is_none(),
+                error,
+                data: Some(serde_json::to_value(plan).map_err(|e| e.to_string())?),
+            })
+        }
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+/// Rename a city, moving its folders. Renaming onto an existing city merges
+/// the two; see `merge_cities`.
+#[tauri::command]
+pub async fn rename_city(
+    app: tauri::AppHandle,
+    from: String,
+    to: String,
+    dry_run: Option<bool>,
+) -> Result<CommandResult, String> {
+    merge_cities(app, vec![from], to, dry_run).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+
+    #[test]
+    fn search_key_ignores_accents_case_and_script() {
+        assert_eq!(city_search_key("Αθήνα", false), "αθηνα");
+        assert_eq!(city_search_key("ΑΘΗΝΑ", false), "αθηνα");
+        assert_eq!(city_search_key("Zürich", false), "zurich");
+        assert_eq!(
+            city_search_key("Αθήνα", true),
+            city_search_key("athina", true)
+        );
+        assert_eq!(
+            city_search_key("Ηράκλειο", true),
+            city_search_key("Irakleio", true)
+        );
+        assert_eq!(
+            city_search_key("Γλυφάδα", true),
+            city_search_key("Glifada", true)
+        );
+        assert_eq!(
+            city_search_key("Κηφισιά", true),
+            city_search_key("Kifissia", true)
+        );
+        assert_eq!(
+            city_search_key("Χαλάνδρι", true),
+            city_search_key("Halandri", true)
+        );
+        assert_eq!(city_search_key("  Nea   Smyrni-", true), "nea smirni");
+    }
+
+    #[tokio::test]
+    async fn search_ranks_by_match_quality_then_usage() {
+        let pool = setup_test_db().await;
+        for (name, usage) in [
+            ("Αθήνα", 5),
+            ("Nea Athina", 9),
+            ("Ηράκλειο", 2),
+            ("Athens", 1),
+        ] {
+            sqlx::query("INSERT INTO cities (name, usage_count, created_at) VALUES (?, ?, 0)")
+                .bind(name)
+                .bind(usage)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let names = |cities: Vec<City>| cities.into_iter().map(|c| c.name).collect::<Vec<_>>();
+
+        let found = search_cities_in(&pool, "athina", true).await.unwrap();
+        assert_eq!(names(found), vec!["Αθήνα", "Nea Athina"]);
+        let found = search_cities_in(&pool, "irakl", true).await.unwrap();
+        assert_eq!(names(found), vec!["Ηράκλειο"]);
+        let found = search_cities_in(&pool, "ath", true).await.unwrap();
+        assert_eq!(names(found), vec!["Αθήνα", "Athens", "Nea Athina"]);
+
+        // Without transliteration only accents and case are ignored.
+        let found = search_cities_in(&pool, "athina", false).await.unwrap();
+        assert_eq!(names(found), vec!["Nea Athina"]);
+        let found = search_cities_in(&pool, "ΑΘΗΝΑ", false).await.unwrap();
+        assert_eq!(names(found), vec!["Αθήνα"]);
+    }
+
+    fn config_for(root: &std::path::Path) -> AppConfig {
+        AppConfig {
+            new_folder_path: root.join("NEW").to_string_lossy().to_string(),
+            done_folder_path: root.join("DONE").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn merge_moves_folders_in_every_status_folder() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = config_for(tmp.path());
+        for dir in [
+            "NEW/Athína/Villa",
+            "DONE/Athína/Loft (AB1)",
+            "NEW/Athens/Flat",
+        ] {
+            fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+        add_property_to_database(&pool, "Villa", "Athína", "NEW", "Villa", None)
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Loft", "Athína", "DONE", "Loft (AB1)", Some("AB1"))
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Flat", "Athens", "NEW", "Flat", None)
+            .await
+            .unwrap();
+
+        let preview = merge_cities_in(&pool, &config, vec!["Athína".into()], "Athens", true)
+            .await
+            .unwrap();
+        assert_eq!(preview.properties, 2);
+        assert_eq!(preview.usage_count, 3);
+        assert_eq!(preview.moves.len(), 2);
+        assert!(preview.moves.iter().all(|m| m.property_id.is_some()));
+        assert!(tmp.path().join("NEW/Athína/Villa").is_dir());
+
+        let plan = merge_cities_in(&pool, &config, vec!["Athína".into()], "Athens", false)
+            .await
+            .unwrap();
+        assert!(plan.conflicts.is_empty());
+        assert!(tmp.path().join("NEW/Athens/Villa").is_dir());
+        assert!(tmp.path().join("DONE/Athens/Loft (AB1)").is_dir());
+        assert!(!tmp.path().join("NEW/Athína").exists());
+        assert!(!tmp.path().join("DONE/Athína").exists());
+
+        let paths: Vec<(String, String)> =
+            sqlx::query_as("SELECT city, folder_path FROM properties ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                ("Athens".into(), "Athens/Villa".into()),
+                ("Athens".into(), "Athens/Loft (AB1)".into()),
+                ("Athens".into(), "Athens/Flat".into()),
+            ]
+        );
+        let cities: Vec<(String, i64)> = sqlx::query_as("SELECT name, usage_count FROM cities")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(cities, vec![("Athens".into(), 3)]);
+        let events: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM property_events WHERE source = 'merge_cities'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(events, 4);
+        let pending: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pending_operations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(pending, 0);
+    }
+
+    #[tokio::test]
+    async fn existing_target_folder_blocks_the_merge() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = config_for(tmp.path());
+        for dir in ["NEW/Athína/Villa", "NEW/Athína/Flat", "NEW/Athens/Villa"] {
+            fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+        add_property_to_database(&pool, "Villa", "Athína", "NEW", "Villa", None)
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Flat", "Athína", "NEW", "Flat", None)
+            .await
+            .unwrap();
+
+        let plan = merge_cities_in(&pool, &config, vec!["Athína".into()], "Athens", false)
+            .await
+            .unwrap();
+        assert_eq!(plan.conflicts.len(), 1);
+        assert!(tmp.path().join("NEW/Athína/Flat").is_dir());
+        let cities: Vec<String> = sqlx::query_scalar("SELECT DISTINCT city FROM properties")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(cities, vec!["Athína".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn parent_dir_source_is_refused() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = config_for(tmp.path());
+        // ".." from NEW is the parent holding every status folder.
+        fs::create_dir_all(tmp.path().join("NEW/Athens")).unwrap();
+        fs::create_dir_all(tmp.path().join("DONE/Athens/Villa")).unwrap();
+
+        assert!(city_name_error("..").is_some());
+        assert!(city_name_error("Athens/Villa").is_some());
+        assert!(city_name_error("Athína").is_none());
+        let result = merge_cities_in(&pool, &config, vec!["..".into()], "Athens", false).await;
+        assert!(result.is_err());
+        assert!(tmp.path().join("NEW").is_dir());
+        assert!(tmp.path().join("DONE/Athens/Villa").is_dir());
+        assert!(!tmp.path().join("NEW/Athens/NEW").exists());
+    }
 }
@@ -0,0 +1,813 @@
+//! Listing codes. A code is a portal's id for a property, and a property
+//! can be listed on several portals, one row each in `property_codes`
+//! (migration 11). One of them is primary: it is mirrored into
+//! `properties.code` and ends up in the folder name as " (code)", so it has
+//! to be unique across properties (`idx_properties_code_unique`, migration
+//! 10) and has to survive the round trip through `parse_folder_name`.
+//! Every other code only has to be unique within its portal. Codes and
+//! portal names compare case-insensitively.
+
+use regex::Regex;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+use crate::config::AppConfig;
+use crate::database::types::{CommandResult, Property, PropertyCode};
+use crate::database::{
+    attach_tags, get_base_path_for_status, get_database_pool, parse_folder_name,
+    record_property_event, rename_folder_if_present, PropertyEventKind,
+};
+
+/// Portal for codes that predate per-portal codes, and for codes set with
+/// `set_property_code` on a property that has none yet.
+pub(super) const DEFAULT_PORTAL: &str = "website";
+
+/// A change to one property's codes, applied by `apply_code_change`.
+#[derive(Debug, Clone, Copy)]
+enum CodeChange<'a> {
+    /// Add or replace the code for `portal`, optionally making it primary.
+    Upsert {
+        portal: &'a str,
+        code: &'a str,
+        primary: bool,
+    },
+    Remove {
+        portal: &'a str,
+    },
+    MakePrimary {
+        portal: &'a str,
+    },
+}
+
+/// Check `code` against the configured pattern, which must match the whole
+/// code. Independently of the pattern, the code must read back unchanged
+/// from a folder suffix, or scans would import it differently.
+pub(super) fn validate_code_format(code: &str, pattern: &str) -> Result<(), String> {
+    if code.is_empty() {
+        return Err("Code cannot be empty".to_string());
+    }
+    let regex = Regex::new(&format!("^(?:{pattern})$"))
+        .map_err(|e| format!("Invalid code pattern in settings: {e}"))?;
+    if !regex.is_match(code) {
+        return Err(format!(
+            "Code '{code}' does not match the required format {pattern}"
+        ));
+    }
+
+    let folder_safe_code = code.replace('/', "-");
+    let (_, parsed) = parse_folder_name(&format!("Property ({folder_safe_code})"));
+    if parsed.as_deref() != Some(folder_safe_code.as_str()) {
+        return Err(format!(
+            "Code '{code}' cannot be stored in a folder name; use at most 20 letters, digits, '-' or '/'"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Fail with an error naming the property that already has `code`, if it
+/// is not `property_id` itself. Trashed properties keep their codes.
+pub(super) async fn ensure_code_available(
+    pool: &SqlitePool,
+    code: &str,
+    property_id: i64,
+) -> Result<(), String> {
+    let owner: Option<(i64, String, String, Option<i64>)> = sqlx::query_as(
+        "SELECT id, name, city, deleted_at FROM properties
+         WHERE code = ? COLLATE NOCASE AND id != ?",
+    )
+    .bind(code)
+    .bind(property_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to check code: {e}"))?;
+
+    match owner {
+        None => Ok(()),
+        Some((id, name, city, deleted_at)) => {
+            let trashed = if deleted_at.is_some() {
+                ", in the trash"
+            } else {
+                ""
+            };
+            Err(format!(
+                "Code '{code}' is already used by '{name}' in {city} (id {id}{trashed})"
+            ))
+        }
+    }
+}
+
+/// Fail with an error naming the property that already uses `code` on
+/// `portal`, if it is not `property_id` itself.
+async fn ensure_portal_code_available(
+    pool: &SqlitePool,
+    portal: &str,
+    code: &str,
+    property_id: i64,
+) -> Result<(), String> {
+    let owner: Option<(i64, String, String)> = sqlx::query_as(
+        "SELECT p.id, p.name, p.city FROM property_codes pc
+         JOIN properties p ON p.id = pc.property_id
+         WHERE pc.portal = ? AND pc.code = ? AND pc.property_id != ?",
+    )
+    .bind(portal)
+    .bind(code)
+    .bind(property_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to check code: {e}"))?;
+
+    match owner {
+        None => Ok(()),
+        Some((id, name, city)) => Err(format!(
+            "Code '{code}' on {portal} is already used by '{name}' in {city} (id {id})"
+        )),
+    }
+}
+
+/// All codes of one property, primary first, then in the order added.
+pub(super) async fn load_property_codes(
+    pool: &SqlitePool,
+    property_id: i64,
+) -> Result<Vec<PropertyCode>, String> {
+    let rows: Vec<(String, String, bool)> = sqlx::query_as(
+        "SELECT portal, code, is_primary FROM property_codes
+         WHERE property_id = ? ORDER BY is_primary DESC, created_at, id",
+    )
+    .bind(property_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load property codes: {e}"))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(portal, code, is_primary)| PropertyCode {
+            portal,
+            code,
+            is_primary,
+        })
+        .collect())
+}
+
+/// Apply `change` to the in-memory list of a property's codes. If no code
+/// is left primary the earliest remaining one takes over.
+fn update_code_list(codes: &mut Vec<PropertyCode>, change: CodeChange<'_>) -> Result<(), String> {
+    let is_portal = |code: &PropertyCode, portal: &str| code.portal.eq_ignore_ascii_case(portal);
+
+    match change {
+        CodeChange::Upsert {
+            portal,
+            code,
+            primary,
+        } => {
+            match codes.iter_mut().find(|c| is_portal(c, portal)) {
+                Some(existing) => code.clone_into(&mut existing.code),
+                None => codes.push(PropertyCode {
+                    portal: portal.to_string(),
+                    code: code.to_string(),
+                    is_primary: false,
+                }),
+            }
+            if primary {
+                for c in codes.iter_mut() {
+                    c.is_primary = is_portal(c, portal);
+                }
+            }
+        }
+        CodeChange::Remove { portal } => {
+            let before = codes.len();
+            codes.retain(|c| !is_portal(c, portal));
+            if codes.len() == before {
+                return Err(format!("No {portal} code to remove"));
+            }
+        }
+        CodeChange::MakePrimary { portal } => {
+            if !codes.iter().any(|c| is_portal(c, portal)) {
+                return Err(format!("No {portal} code to make primary"));
+            }
+            for c in codes.iter_mut() {
+                c.is_primary = is_portal(c, portal);
+            }
+        }
+    }
+    if !codes.iter().any(|c| c.is_primary) {
+        if let Some(first) = codes.first_mut() {
+            first.is_primary = true;
+        }
+    }
+    Ok(())
+}
+
+/// Write `change` to the `property_codes` rows and move the primary flag
+/// to `primary`.
+async fn store_code_rows(
+    conn: &mut SqliteConnection,
+    property_id: i64,
+    change: CodeChange<'_>,
+    primary: Option<&PropertyCode>,
+    now: i64,
+) -> Result<(), String> {
+    match change {
+        CodeChange::Upsert { portal, code, .. } => {
+            sqlx::query(
+                "INSERT INTO property_codes (property_id, portal, code, is_primary, created_at)
+                 VALUES (?, ?, ?, 0, ?)
+                 ON CONFLICT(property_id, portal) DO UPDATE SET code = excluded.code",
+            )
+            .bind(property_id)
+            .bind(portal)
+            .bind(code)
+            .bind(now)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        CodeChange::Remove { portal } => {
+            sqlx::query("DELETE FROM property_codes WHERE property_id = ? AND portal = ?")
+                .bind(property_id)
+                .bind(portal)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        CodeChange::MakePrimary { .. } => {}
+    }
+    // Two statements: the partial unique index allows one primary per
+    // property at every step.
+    sqlx::query("UPDATE property_codes SET is_primary = 0 WHERE property_id = ?")
+        .bind(property_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(primary) = primary {
+        sqlx::query(
+            "UPDATE property_codes SET is_primary = 1 WHERE property_id = ? AND portal = ?",
+        )
+        .bind(property_id)
+        .bind(&primary.portal)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Apply `change` to a property's codes. When the primary code changes
+/// the folder is renamed to the new suffix first, then the code rows,
+/// `properties.code` and `folder_path` are updated in one transaction. If
+/// that fails the folder is renamed back.
+/// Returns the new `folder_path` and primary code.
+async fn apply_code_change(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    property_id: i64,
+    change: CodeChange<'_>,
+    source: &str,
+) -> Result<(String, Option<String>), String> {
+    let (name, city, status, folder_path, old_code): (
+        String,
+        String,
+        String,
+        String,
+        Option<String>,
+    ) = sqlx::query_as("SELECT name, city, status, folder_path, code FROM properties WHERE id = ?")
+        .bind(property_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Property not found")?;
+
+    let mut codes = load_property_codes(pool, property_id).await?;
+    if let CodeChange::Upsert { portal, code, .. } = change {
+        ensure_portal_code_available(pool, portal, code, property_id).await?;
+    }
+    update_code_list(&mut codes, change)?;
+    let primary = codes.iter().find(|c| c.is_primary).cloned();
+    let new_code = primary.as_ref().map(|c| c.code.clone());
+
+    let primary_changed = new_code.as_deref() != old_code.as_deref();
+    if let Some(code) = new_code.as_deref().filter(|_| primary_changed) {
+        ensure_code_available(pool, code, property_id).await?;
+    }
+
+    // Setting the primary code explicitly always normalises the folder
+    // name, as set_property_code always has.
+    let reshape_folder =
+        primary_changed || matches!(change, CodeChange::Upsert { primary: true, .. });
+    let (new_folder_path, folder_move) = if reshape_folder {
+        // Use the real folder name on disk, not a reconstructed one
+        let old_folder_name = folder_path.split('/').next_back().unwrap_or(&name);
+        // "/" is not allowed in folder names, so "204905/44538" becomes "204905-44538"
+        let new_folder_name = new_code.as_ref().map_or_else(
+            || name.clone(),
+            |code| format!("{} ({})", name, code.replace('/', "-")),
+        );
+        let base_path = get_base_path_for_status(config, &status)?;
+        let folder_move = rename_folder_if_present(
+            base_path.join(&city).join(old_folder_name),
+            base_path.join(&city).join(&new_folder_name),
+        )
+        .await?;
+        (format!("{city}/{new_folder_name}"), folder_move)
+    } else {
+        (folder_path, None)
+    };
+
+    let written = async {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        store_code_rows(&mut tx, property_id, change, primary.as_ref(), now).await?;
+
+        sqlx::query("UPDATE properties SET code = ?, folder_path = ?, updated_at = ? WHERE id = ?")
+            .bind(&new_code)
+            .bind(&new_folder_path)
+            .bind(now)
+            .bind(property_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if primary_changed {
+            record_property_event(
+                &mut tx,
+                property_id,
+                PropertyEventKind::CodeAssigned,
+                old_code.as_deref(),
+                new_code.as_deref(),
+                source,
+            )
+            .await?;
+        }
+        if let Some((old_path, new_path)) = &folder_move {
+            record_property_event(
+                &mut tx,
+                property_id,
+                PropertyEventKind::FolderMove,
+                Some(&old_path.to_string_lossy()),
+                Some(&new_path.to_string_lossy()),
+                source,
+            )
+            .await?;
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())
+    }
+    .await;
+    if let Err(e) = written {
+        // Put the folder back so it matches the unchanged row.
+        if let Some((old_path, new_path)) = folder_move {
+            let _ = rename_folder_if_present(new_path, old_path).await;
+        }
+        return Err(e);
+    }
+    Ok((new_folder_path, new_code))
+}
+
+/// Make `code` the code of the property's primary portal, or of
+/// `DEFAULT_PORTAL` if it has none, renaming the folder to match.
+pub(super) async fn apply_primary_code(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    property_id: i64,
+    code: &str,
+    source: &str,
+) -> Result<(String, Option<String>), String> {
+    let portal = load_property_codes(pool, property_id)
+        .await?
+        .into_iter()
+        .find(|c| c.is_primary)
+        .map_or_else(|| DEFAULT_PORTAL.to_string(), |c| c.portal);
+    let change = CodeChange::Upsert {
+        portal: &portal,
+        code,
+        primary: true,
+    };
+    apply_code_change(pool, config, property_id, change, source).await
+}
+
+pub(super) async fn find_property_by_code(
+    pool: &SqlitePool,
+    code: &str,
+) -> Result<Option<Property>, String> {
+    let row = sqlx::query(
+        "SELECT * FROM properties WHERE code = ? COLLATE NOCASE AND deleted_at IS NULL",
+    )
+    .bind(code.trim())
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to look up code: {e}"))?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let created_at_timestamp: i64 = row.get("created_at");
+    let updated_at_timestamp: i64 = row.get("updated_at");
+    let property = Property {
+        id: Some(row.get("id")),
+        name: row.get("name"),
+        city: row.get("city"),
+        status: row.get("status"),
+        folder_path: row.get("folder_path"),
+        notes: row.get("notes"),
+        code: row.get("code"),
+        tags: Vec::new(),
+        created_at: chrono::DateTime::from_timestamp_millis(created_at_timestamp)
+            .unwrap_or_else(chrono::Utc::now),
+        updated_at: chrono::DateTime::from_timestamp_millis(updated_at_timestamp)
+            .unwrap_or_else(chrono::Utc::now),
+        completed: None,
+    };
+    let mut properties = [property];
+    attach_tags(pool, &mut properties).await?;
+    let [property] = properties;
+
+    Ok(Some(property))
+}
+
+/// Look up a property by its listing code.
+#[tauri::command]
+pub async fn get_property_by_code(
+    app: tauri::AppHandle,
+    code: String,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    match find_property_by_code(pool, &code).await? {
+        Some(property) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::to_value(property).map_err(|e| e.to_string())?),
+        }),
+        None => Ok(CommandResult {
+            success: false,
+            error: Some(format!("No property with code '{}'", code.trim())),
+            data: None,
+        }),
+    }
+}
+
+/// Set the primary code: replaces the code of the current primary portal,
+/// or adds a "website" code when the property has none.
+#[tauri::command]
+pub async fn set_property_code(
+    app: tauri::AppHandle,
+    property_id: i64,
+    code: String,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let code = code.trim();
+
+    let config = crate::config::get_cached_config(&app)
+        .await
+        .map_err(|e| format!("Failed to load config: {}", e))?
+        .ok_or("App configuration not found")?;
+
+    if let Err(e) = validate_code_format(code, &config.code_pattern) {
+        return Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        });
+    }
+
+    match apply_primary_code(pool, &config, property_id, code, "set_property_code").await {
+        Ok((new_folder_path, _)) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::json!({
+                "new_folder_path": new_folder_path,
+                "code": code
+            })),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(format!("Failed to update property code: {}", e)),
+            data: None,
+        }),
+    }
+}
+
+/// Shared tail of the per-portal commands: apply the change and return the
+/// property's codes afterwards.
+async fn change_codes_command(
+    app: &tauri::AppHandle,
+    property_id: i64,
+    change: CodeChange<'_>,
+    source: &str,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(app)?;
+    let config = crate::config::get_cached_config(app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    if let CodeChange::Upsert { portal, code, .. } = change {
+        let checked = if portal.is_empty() {
+            Err("Portal cannot be empty".to_string())
+        } else {
+            validate_code_format(code, &config.code_pattern)
+        };
+        if let Err(e) = checked {
+            return Ok(CommandResult {
+                success: false,
+                error: Some(e),
+                data: None,
+            });
+        }
+    }
+
+    match apply_code_change(pool, &config, property_id, change, source).await {
+        Ok(_) => {
+            let codes = load_property_codes(pool, property_id).await?;
+            Ok(CommandResult {
+                success: true,
+                error: None,
+                data: Some(serde_json::to_value(codes).map_err(|e| e.to_string())?),
+            })
+        }
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+/// Every portal code of a property, primary first.
+#[tauri::command]
+pub async fn get_property_codes(
+    app: tauri::AppHandle,
+    property_id: i64,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let codes = load_property_codes(pool, property_id).await?;
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(codes).map_err(|e| e.to_string())?),
+    })
+}
+
+/// Add or replace a property's code for one portal. The first code a
+/// property gets, or one added with `primary`, becomes the primary code.
+#[tauri::command]
+pub async fn add_property_code(
+    app: tauri::AppHandle,
+    property_id: i64,
+    portal: String,
+    code: String,
+    primary: Option<bool>,
+) -> Result<CommandResult, String> {
+    let change = CodeChange::Upsert {
+        portal: portal.trim(),
+        code: code.trim(),
+        primary: primary.unwrap_or(false),
+    };
+    change_codes_command(&app, property_id, change, "add_property_code").await
+}
+
+/// Remove a property's code for one portal. Removing the primary code
+/// promotes the earliest remaining one, or drops the folder suffix.
+#[tauri::command]
+pub async fn remove_property_code(
+    app: tauri::AppHandle,
+    property_id: i64,
+    portal: String,
+) -> Result<CommandResult, String> {
+    let change = CodeChange::Remove {
+        portal: portal.trim(),
+    };
+    change_codes_command(&app, property_id, change, "remove_property_code").await
+}
+
+/// Make a property's code for `portal` the primary one.
+#[tauri::command]
+pub async fn set_primary_property_code(
+    app: tauri::AppHandle,
+    property_id: i64,
+    portal: String,
+) -> Result<CommandResult, String> {
+    let change = CodeChange::MakePrimary {
+        portal: portal.trim(),
+    };
+    change_codes_command(&app, property_id, change, "set_primary_property_code").await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_CODE_PATTERN;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+
+    #[test]
+    fn default_pattern_accepts_portal_codes() {
+        for code in ["45164", "AB-123", "204905/44538"] {
+            assert!(
+                validate_code_format(code, DEFAULT_CODE_PATTERN).is_ok(),
+                "{code}"
+            );
+        }
+        for code in ["-12", "12 34", "ΑΒ123", "123456789012345678901"] {
+            assert!(
+                validate_code_format(code, DEFAULT_CODE_PATTERN).is_err(),
+                "{code}"
+            );
+        }
+    }
+
+    #[test]
+    fn custom_pattern_still_has_to_round_trip() {
+        assert!(validate_code_format("12345", r"\d{5}").is_ok());
+        assert!(validate_code_format("1234", r"\d{5}").is_err());
+        // The pattern allows it, but " (a b)" is not read back as a code.
+        assert!(validate_code_format("a b", r".+").is_err());
+        assert!(validate_code_format("1", "(").is_err());
+        assert!(validate_code_format("", ".*").is_err());
+    }
+
+    #[tokio::test]
+    async fn codes_are_unique_case_insensitively() {
+        let pool = setup_test_db().await;
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa (AB1)", Some("AB1"))
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Loft", "Rome", "NEW", "Loft", None)
+            .await
+            .unwrap();
+        let (villa_id, loft_id): (i64, i64) = sqlx::query_as(
+            "SELECT (SELECT id FROM properties WHERE name = 'Villa'),
+                    (SELECT id FROM properties WHERE name = 'Loft')",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let err = ensure_code_available(&pool, "ab1", loft_id)
+            .await
+            .unwrap_err();
+        assert!(err.contains("'Villa' in Athens"));
+        assert!(ensure_code_available(&pool, "AB1", villa_id).await.is_ok());
+
+        // The unique index backs up the check.
+        assert!(
+            sqlx::query("UPDATE properties SET code = 'ab1' WHERE id = ?")
+                .bind(loft_id)
+                .execute(&pool)
+                .await
+                .is_err()
+        );
+
+        let found = find_property_by_code(&pool, " ab1 ")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.name, "Villa");
+        assert!(find_property_by_code(&pool, "zz9").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn primary_code_follows_portal_changes_and_renames_folder() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = AppConfig {
+            new_folder_path: tmp.path().join("NEW").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        };
+        let folder = |name: &str| tmp.path().join("NEW").join("Athens").join(name);
+        std::fs::create_dir_all(folder("Villa (AB1)")).unwrap();
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa (AB1)", Some("AB1"))
+            .await
+            .unwrap();
+        let id: i64 = sqlx::query_scalar("SELECT id FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let code = |portal: &str, code: &str, is_primary: bool| PropertyCode {
+            portal: portal.to_string(),
+            code: code.to_string(),
+            is_primary,
+        };
+
+        // The insert trigger turned the existing code into a primary code.
+        assert_eq!(
+            load_property_codes(&pool, id).await.unwrap(),
+            vec![code("website", "AB1", true)]
+        );
+
+        let add = CodeChange::Upsert {
+            portal: "spitogatos",
+            code: "778",
+            primary: false,
+        };
+        let (folder_path, primary) = apply_code_change(&pool, &config, id, add, "test")
+            .await
+            .unwrap();
+        assert_eq!(folder_path, "Athens/Villa (AB1)");
+        assert_eq!(primary.as_deref(), Some("AB1"));
+
+        let switch = CodeChange::MakePrimary {
+            portal: "Spitogatos",
+        };
+        let (folder_path, _) = apply_code_change(&pool, &config, id, switch, "test")
+            .await
+            .unwrap();
+        assert_eq!(folder_path, "Athens/Villa (778)");
+        assert!(folder("Villa (778)").is_dir());
+        assert_eq!(
+            load_property_codes(&pool, id).await.unwrap(),
+            vec![
+                code("spitogatos", "778", true),
+                code("website", "AB1", false)
+            ]
+        );
+
+        // Removing the primary code promotes the remaining one.
+        let remove = CodeChange::Remove {
+            portal: "spitogatos",
+        };
+        let (folder_path, primary) = apply_code_change(&pool, &config, id, remove, "test")
+            .await
+            .unwrap();
+        assert_eq!(folder_path, "Athens/Villa (AB1)");
+        assert_eq!(primary.as_deref(), Some("AB1"));
+        assert!(folder("Villa (AB1)").is_dir());
+
+        let remove = CodeChange::Remove { portal: "website" };
+        let (folder_path, primary) = apply_code_change(&pool, &config, id, remove, "test")
+            .await
+            .unwrap();
+        assert_eq!(folder_path, "Athens/Villa");
+        assert!(primary.is_none());
+        let stored: Option<String> = sqlx::query_scalar("SELECT code FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(stored.is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_write_renames_the_folder_back() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = AppConfig {
+            new_folder_path: tmp.path().join("NEW").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        };
+        let folder = |name: &str| tmp.path().join("NEW").join("Athens").join(name);
+        std::fs::create_dir_all(folder("Villa")).unwrap();
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa", None)
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TRIGGER fail_update BEFORE UPDATE ON properties
+             BEGIN SELECT RAISE(ABORT, 'write failed'); END",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let result = apply_primary_code(&pool, &config, 1, "AB1", "test").await;
+        assert!(result.is_err());
+        assert!(folder("Villa").is_dir());
+        assert!(!folder("Villa (AB1)").exists());
+    }
+
+    #[tokio::test]
+    async fn portal_codes_are_unique_per_portal() {
+        let pool = setup_test_db().await;
+        // Only non-primary codes change below, so no folders are touched.
+        let config = AppConfig::default();
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa (AB1)", Some("AB1"))
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Loft", "Rome", "NEW", "Loft (CD2)", Some("CD2"))
+            .await
+            .unwrap();
+        let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM properties ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+
+        let first = CodeChange::Upsert {
+            portal: "xe",
+            code: "500",
+            primary: false,
+        };
+        apply_code_change(&pool, &config, ids[0], first, "test")
+            .await
+            .unwrap();
+        let err = apply_code_change(&pool, &config, ids[1], first, "test")
+            .await
+            .unwrap_err();
+        assert!(err.contains("'Villa' in Athens"), "{err}");
+
+        let other_portal = CodeChange::Upsert {
+            portal: "spitogatos",
+            code: "500",
+            primary: false,
+        };
+        apply_code_change(&pool, &config, ids[1], other_portal, "test")
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,184 @@
+//! Property audit log. Every command that changes a property's status,
+//! moves or renames its folder, or assigns a code appends a row to
+//! `property_events` with the old and new value and the command that made
+//! the change, so "who archived this and when" has an answer.
+
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+use crate::database::get_database_pool;
+use crate::database::types::{CommandResult, PropertyEvent};
+
+/// Kind of change recorded in `property_events.event_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PropertyEventKind {
+    /// `status` changed, e.g. DONE -> ARCHIVE.
+    StatusChange,
+    /// The folder moved on disk. Values are absolute paths.
+    FolderMove,
+    /// `name` changed.
+    Rename,
+    /// `city` changed.
+    CityChange,
+    /// `code` was set or replaced.
+    CodeAssigned,
+    /// `repair_property_statuses` corrected drift. Values are
+    /// `"{status}:{folder_path}"`.
+    RepairFix,
+    /// Moved to the trash. `new_value` is the folder's trash path, if it
+    /// was moved.
+    Trashed,
+    /// Restored from the trash. `old_value` is the trash path, if any.
+    Restored,
+}
+
+impl PropertyEventKind {
+    pub(super) const fn as_str(self) -> &'static str {
+        match self {
+            Self::StatusChange => "status_change",
+            Self::FolderMove => "folder_move",
+            Self::Rename => "rename",
+            Self::CityChange => "city_change",
+            Self::CodeAssigned => "code_assigned",
+            Self::RepairFix => "repair_fix",
+            Self::Trashed => "trashed",
+            Self::Restored => "restored",
+        }
+    }
+}
+
+/// Append one event. Takes a connection rather than the pool so callers can
+/// record the event in the same transaction as the change it describes.
+pub(super) async fn record_property_event(
+    conn: &mut SqliteConnection,
+    property_id: i64,
+    kind: PropertyEventKind,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    source: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO property_events (property_id, event_type, old_value, new_value, source, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(property_id)
+    .bind(kind.as_str())
+    .bind(old_value)
+    .bind(new_value)
+    .bind(source)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to record property event: {e}"))?;
+
+    Ok(())
+}
+
+/// All events for one property, oldest first.
+pub(super) async fn fetch_property_events(
+    pool: &SqlitePool,
+    property_id: i64,
+) -> Result<Vec<PropertyEvent>, String> {
+    let rows = sqlx::query(
+        "SELECT id, property_id, event_type, old_value, new_value, source, created_at
+         FROM property_events WHERE property_id = ? ORDER BY created_at ASC, id ASC",
+    )
+    .bind(property_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch property events: {e}"))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PropertyEvent {
+            id: row.get("id"),
+            property_id: row.get("property_id"),
+            event_type: row.get("event_type"),
+            old_value: row.get("old_value"),
+            new_value: row.get("new_value"),
+            source: row.get("source"),
+            created_at: chrono::DateTime::from_timestamp_millis(row.get("created_at"))
+                .unwrap_or_else(chrono::Utc::now),
+        })
+        .collect())
+}
+
+/// Timeline of every recorded change to a property, oldest first.
+#[tauri::command]
+pub async fn get_property_events(
+    app: tauri::AppHandle,
+    property_id: i64,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    let events = fetch_property_events(pool, property_id).await?;
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(events).map_err(|e| e.to_string())?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::setup_test_db;
+
+    #[tokio::test]
+    async fn events_round_trip_in_order() {
+        let pool = setup_test_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        record_property_event(
+            &mut conn,
+            7,
+            PropertyEventKind::StatusChange,
+            Some("NEW"),
+            Some("DONE"),
+            "update_property_status",
+        )
+        .await
+        .unwrap();
+        record_property_event(
+            &mut conn,
+            7,
+            PropertyEventKind::CodeAssigned,
+            None,
+            Some("45164"),
+            "set_property_code",
+        )
+        .await
+        .unwrap();
+        drop(conn);
+
+        let events = fetch_property_events(&pool, 7).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "status_change");
+        assert_eq!(events[0].old_value.as_deref(), Some("NEW"));
+        assert_eq!(events[0].new_value.as_deref(), Some("DONE"));
+        assert_eq!(events[1].event_type, "code_assigned");
+        assert_eq!(events[1].old_value, None);
+        assert_eq!(events[1].source, "set_property_code");
+    }
+
+    #[tokio::test]
+    async fn events_are_scoped_to_property() {
+        let pool = setup_test_db().await;
+        let mut conn = pool.acquire().await.unwrap();
+
+        record_property_event(
+            &mut conn,
+            1,
+            PropertyEventKind::Rename,
+            Some("Villa"),
+            Some("Villa Alpha"),
+            "update_property",
+        )
+        .await
+        .unwrap();
+        drop(conn);
+
+        assert_eq!(fetch_property_events(&pool, 1).await.unwrap().len(), 1);
+        assert!(fetch_property_events(&pool, 2).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,363 @@
+//! Per-image metadata. The `images` table (migration 13) holds one row per
+//! image in a property folder and its INTERNET, WATERMARK and AGGELIA
+//! subfolders: size, dimensions, mtime, SHA-256 and EXIF capture time.
+//!
+//! Rows are filled in lazily. `index_property_images` lists the folders,
+//! keeps the rows whose file still has the same size and mtime, reads the
+//! new and changed files, and drops rows whose file is gone, so callers
+//! always get an up-to-date list while only paying for hashing once per
+//! file version.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use sqlx::{Row, SqlitePool};
+
+use crate::config::AppConfig;
+use crate::database::transfer::sha256_file;
+use crate::database::types::{CommandResult, ImageRecord};
+use crate::database::{get_base_path_for_status, get_database_pool, list_image_filenames};
+use crate::folder_conventions::{AGGELIA, INTERNET, WATERMARK};
+use crate::paths::validate_relative_folder_path;
+
+/// Indexed folders relative to the property folder, in display order.
+pub(super) fn subfolders() -> [String; 5] {
+    [
+        String::new(),
+        INTERNET.to_string(),
+        format!("{INTERNET}/{AGGELIA}"),
+        WATERMARK.to_string(),
+        format!("{WATERMARK}/{AGGELIA}"),
+    ]
+}
+
+/// An image file on disk, before it is read.
+struct ImageFile {
+    subfolder: String,
+    filename: String,
+    path: PathBuf,
+    size: i64,
+    mtime: i64,
+}
+
+/// Stat every image in the indexed folders of `property_dir`. Blocking.
+fn list_image_files(property_dir: &Path) -> Result<Vec<ImageFile>, String> {
+    let mut files = Vec::new();
+    for subfolder in subfolders() {
+        let dir = subfolder
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .fold(property_dir.to_path_buf(), |dir, part| dir.join(part));
+        for filename in list_image_filenames(&dir)? {
+            let path = dir.join(&filename);
+            let metadata = fs::metadata(&path)
+                .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+            let mtime = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+                .and_then(|since| i64::try_from(since.as_millis()).ok())
+                .unwrap_or(0);
+            files.push(ImageFile {
+                subfolder: subfolder.clone(),
+                filename,
+                path,
+                size: i64::try_from(metadata.len()).unwrap_or(i64::MAX),
+                mtime,
+            });
+        }
+    }
+    Ok(files)
+}
+
+/// EXIF `DateTimeOriginal`, if the file has one. Blocking.
+fn read_capture_time(path: &Path) -> Option<String> {
+    use exif::{In, Reader, Tag, Value};
+
+    let file = fs::File::open(path).ok()?;
+    let exif_data = Reader::new()
+        .read_from_container(&mut std::io::BufReader::new(file))
+        .ok()?;
+    let field = exif_data.get_field(Tag::DateTimeOriginal, In::PRIMARY)?;
+    let Value::Ascii(ref values) = field.value else {
+        return None;
+    };
+    let dt = exif::DateTime::from_ascii(values.first()?).ok()?;
+    Some(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        dt.year, dt.month, dt.day, dt.hour, dt.minute, dt.second
+    ))
+}
+
+/// Hash and measure one file. Blocking.
+fn read_image(property_id: i64, file: &ImageFile) -> Result<ImageRecord, String> {
+    let sha256 = sha256_file(&file.path)
+        .map_err(|e| format!("Failed to hash {}: {e}", file.path.display()))?;
+    let dimensions = image::image_dimensions(&file.path).ok();
+    Ok(ImageRecord {
+        property_id,
+        subfolder: file.subfolder.clone(),
+        filename: file.filename.clone(),
+        size: file.size,
+        width: dimensions.map(|(width, _)| width),
+        height: dimensions.map(|(_, height)| height),
+        mtime: file.mtime,
+        sha256,
+        captured_at: read_capture_time(&file.path),
+    })
+}
+
+async fn load_image_rows(
+    pool: &SqlitePool,
+    property_id: i64,
+) -> Result<HashMap<(String, String), ImageRecord>, String> {
+    let rows = sqlx::query(
+        "SELECT subfolder, filename, size, width, height, mtime, sha256, captured_at
+         FROM images WHERE property_id = ?",
+    )
+    .bind(property_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load images: {e}"))?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let record = ImageRecord {
+                property_id,
+                subfolder: row.get("subfolder"),
+                filename: row.get("filename"),
+                size: row.get("size"),
+                width: row.get("width"),
+                height: row.get("height"),
+                mtime: row.get("mtime"),
+                sha256: row.get("sha256"),
+                captured_at: row.get("captured_at"),
+            };
+            ((record.subfolder.clone(), record.filename.clone()), record)
+        })
+        .collect())
+}
+
+/// Write `changed` and delete `removed` in one transaction.
+async fn store_image_rows(
+    pool: &SqlitePool,
+    property_id: i64,
+    changed: &[ImageRecord],
+    removed: &[(String, String)],
+) -> Result<(), String> {
+    if changed.is_empty() && removed.is_empty() {
+        return Ok(());
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {e}"))?;
+
+    for (subfolder, filename) in removed {
+        sqlx::query("DELETE FROM images WHERE property_id = ? AND subfolder = ? AND filename = ?")
+            .bind(property_id)
+            .bind(subfolder)
+            .bind(filename)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to remove image {filename}: {e}"))?;
+    }
+    for image in changed {
+        sqlx::query(
+            "INSERT INTO images
+                (property_id, subfolder, filename, size, width, height, mtime, sha256, captured_at, indexed_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(property_id, subfolder, filename) DO UPDATE SET
+                size = excluded.size, width = excluded.width, height = excluded.height,
+                mtime = excluded.mtime, sha256 = excluded.sha256,
+                captured_at = excluded.captured_at, indexed_at = excluded.indexed_at",
+        )
+        .bind(property_id)
+        .bind(&image.subfolder)
+        .bind(&image.filename)
+        .bind(image.size)
+        .bind(image.width)
+        .bind(image.height)
+        .bind(image.mtime)
+        .bind(&image.sha256)
+        .bind(&image.captured_at)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to store image {}: {e}", image.filename))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit images: {e}"))
+}
+
+/// Bring the `images` rows of one property in line with `property_dir` and
+/// return them, originals first, then by subfolder and filename. Files that
+/// cannot be read are left out and reported on stderr.
+pub(super) async fn index_property_images(
+    pool: &SqlitePool,
+    property_id: i64,
+    property_dir: PathBuf,
+) -> Result<Vec<ImageRecord>, String> {
+    let mut known = load_image_rows(pool, property_id).await?;
+
+    let files = tokio::task::spawn_blocking(move || list_image_files(&property_dir))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??;
+
+    let mut images = Vec::with_capacity(files.len());
+    let mut stale = Vec::new();
+    for file in files {
+        match known.remove(&(file.subfolder.clone(), file.filename.clone())) {
+            Some(record) if record.size == file.size && record.mtime == file.mtime => {
+                images.push(record);
+            }
+            _ => stale.push(file),
+        }
+    }
+    let removed: Vec<(String, String)> = known.into_keys().collect();
+
+    let changed: Vec<ImageRecord> = tokio::task::spawn_blocking(move || {
+        stale
+            .par_iter()
+            .filter_map(|file| {
+                read_image(property_id, file)
+                    .map_err(|e| eprintln!("Skipping image: {e}"))
+                    .ok()
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
+
+    store_image_rows(pool, property_id, &changed, &removed).await?;
+
+    images.extend(changed);
+    let order = subfolders();
+    images.sort_by(|a, b| {
+        let position = |image: &ImageRecord| order.iter().position(|s| *s == image.subfolder);
+        position(a)
+            .cmp(&position(b))
+            .then_with(|| a.filename.cmp(&b.filename))
+    });
+    Ok(images)
+}
+
+/// Absolute folder of a property that is not in the trash.
+pub(super) async fn property_dir(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    property_id: i64,
+) -> Result<PathBuf, String> {
+    let row = sqlx::query(
+        "SELECT status, folder_path FROM properties WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(property_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("Failed to load property {property_id}: {e}"))?
+    .ok_or("Property not found")?;
+
+    let status: String = row.get("status");
+    let folder_path: String = row.get("folder_path");
+    let relative = validate_relative_folder_path(&folder_path).map_err(|e| e.to_string())?;
+    Ok(get_base_path_for_status(config, &status)?.join(relative))
+}
+
+/// Metadata of a property's images, indexing any that are new or changed.
+/// `subfolder` limits the list to one folder, `""` being the originals.
+#[tauri::command]
+pub async fn get_property_images(
+    app: tauri::AppHandle,
+    property_id: i64,
+    subfolder: Option<String>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let config = crate::config::get_cached_config(&app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    let result = async {
+        let dir = property_dir(pool, &config, property_id).await?;
+        index_property_images(pool, property_id, dir).await
+    }
+    .await;
+
+    match result {
+        Ok(mut images) => {
+            if let Some(subfolder) = subfolder {
+                images.retain(|image| image.subfolder == subfolder);
+            }
+            Ok(CommandResult {
+                success: true,
+                error: None,
+                data: Some(serde_json::to_value(images).map_err(|e| e.to_string())?),
+            })
+        }
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+
+    #[tokio::test]
+    async fn index_tracks_new_changed_and_removed_images() {
+        let pool = setup_test_db().await;
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa", None)
+            .await
+            .unwrap();
+        let id: i64 = sqlx::query_scalar("SELECT id FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        fs::create_dir_all(dir.join("INTERNET/AGGELIA")).unwrap();
+        image::RgbImage::new(3, 2).save(dir.join("1.png")).unwrap();
+        fs::write(dir.join("INTERNET/AGGELIA/1.jpg"), b"not really a jpeg").unwrap();
+        fs::write(dir.join("notes.txt"), b"ignored").unwrap();
+
+        let images = index_property_images(&pool, id, dir.clone()).await.unwrap();
+        assert_eq!(images.len(), 2);
+        assert_eq!(images[0].subfolder, "");
+        assert_eq!((images[0].width, images[0].height), (Some(3), Some(2)));
+        assert_eq!(images[1].subfolder, "INTERNET/AGGELIA");
+        assert_eq!(images[1].width, None);
+        assert_eq!(images[1].sha256.len(), 64);
+
+        fs::write(dir.join("INTERNET/AGGELIA/1.jpg"), b"edited").unwrap();
+        fs::remove_file(dir.join("1.png")).unwrap();
+        let images = index_property_images(&pool, id, dir).await.unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].size, 6);
+
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM images")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 1);
+
+        sqlx::query("DELETE FROM properties WHERE id = ?")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM images")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 0);
+    }
+}
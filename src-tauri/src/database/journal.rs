@@ -0,0 +1,680 @@
+//! Write-ahead journal for folder moves that accompany a status update.
+//!
+//! A status change touches two systems that cannot share a transaction:
+//! the property folder moves between status roots on disk, and the
+//! `properties` row changes in the database. The intent is therefore written to
+//! `pending_operations` first, then the folder is moved, and the row update
+//! deletes the journal entry in the same transaction. If the app dies in
+//! between, `recover_pending_operations` (run from `init_database`) checks
+//! which end of the move exists on disk and either finishes the database
+//! update or drops the intent, so the DB and disk agree again on startup.
+//!
+//! `merge_cities` journals its folder moves the same way, as changes that
+//! keep the status. Their `folder_path` is under the target city, and
+//! replaying one also moves the row to that city.
+
+use std::path::PathBuf;
+
+use sqlx::{Row, SqliteConnection, SqlitePool};
+
+use crate::database::transfer::{remove_source, CopyProgress};
+use crate::database::{
+    move_folder, move_folder_with_progress, record_property_event, PropertyEventKind,
+};
+
+/// `source` of the journal entries written by `merge_cities`.
+pub(super) const CITY_MERGE_SOURCE: &str = "merge_cities";
+
+/// A status update for one property, optionally with the folder move that
+/// goes with it. Absolute paths are stored so recovery does not depend on
+/// the config being loaded.
+#[derive(Debug, Clone)]
+pub(super) struct StatusChange {
+    pub property_id: i64,
+    pub old_status: String,
+    pub new_status: String,
+    pub folder_path: String,
+    /// `(from, to)` absolute folder paths when the folder moves on disk.
+    pub folder_move: Option<(PathBuf, PathBuf)>,
+    /// Command that requested the change, recorded in the audit log.
+    pub source: String,
+}
+
+/// Update the `properties` row and append the matching audit events.
+/// Does not touch the filesystem.
+pub(super) async fn apply_status_change(
+    conn: &mut SqliteConnection,
+    change: &StatusChange,
+) -> Result<(), String> {
+    sqlx::query("UPDATE properties SET status = ?, folder_path = ?, updated_at = ? WHERE id = ?")
+        .bind(&change.new_status)
+        .bind(&change.folder_path)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(change.property_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to update property: {e}"))?;
+
+    if change.old_status != change.new_status {
+        record_property_event(
+            conn,
+            change.property_id,
+            PropertyEventKind::StatusChange,
+            Some(&change.old_status),
+            Some(&change.new_status),
+            &change.source,
+        )
+        .await?;
+    }
+    if let Some((from, to)) = &change.folder_move {
+        record_property_event(
+            conn,
+            change.property_id,
+            PropertyEventKind::FolderMove,
+            Some(&from.to_string_lossy()),
+            Some(&to.to_string_lossy()),
+            &change.source,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Apply a status change end to end. Without a folder move this is a single
+/// transaction; with one, the move is journaled before it happens and the
+/// journal entry is cleared in the same transaction as the row update.
+pub(super) async fn run_status_change(
+    pool: &SqlitePool,
+    change: &StatusChange,
+) -> Result<Option<String>, String> {
+    run_status_change_with_progress(pool, change, |_| {}).await
+}
+
+/// `run_status_change`, reporting copy progress when the folder has to be
+/// copied to another filesystem. Returns a warning when such a copy could
+/// not fully remove the original folder; the change itself is committed.
+pub(super) async fn run_status_change_with_progress<F>(
+    pool: &SqlitePool,
+    change: &StatusChange,
+    mut on_progress: F,
+) -> Result<Option<String>, String>
+where
+    F: FnMut(CopyProgress) + Send + 'static,
+{
+    let Some((from, to)) = change.folder_move.clone() else {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {e}"))?;
+        apply_status_change(&mut tx, change).await?;
+        return tx
+            .commit()
+            .await
+            .map(|()| None)
+            .map_err(|e| format!("Failed to commit status change: {e}"));
+    };
+
+    let journal_id = journal_status_change(pool, change).await?;
+
+    let move_result = tokio::task::spawn_blocking(move || {
+        move_folder_with_progress(&from, &to, &mut on_progress)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
+
+    let warning = match move_result {
+        Ok(warning) => warning,
+        Err(e) => {
+            // Nothing moved, so the intent can simply be dropped.
+            discard_journal_entry(pool, journal_id).await?;
+            return Err(e);
+        }
+    };
+
+    commit_journaled_change(pool, journal_id, change).await?;
+    Ok(warning)
+}
+
+/// Apply many status changes, moving their folders in parallel on the rayon
+/// pool. Every move is journaled up front and each row is committed as soon
+/// as the moves are done, so any single failure only affects its own
+/// property. `on_moved(done, total)` is called from the worker threads after
+/// each folder move, and `on_committed(done, total)` after each row has been
+/// written or rejected. Results are returned in the order of `changes`.
+pub(super) async fn run_status_changes<F, C>(
+    pool: &SqlitePool,
+    changes: &[StatusChange],
+    on_moved: F,
+    mut on_committed: C,
+) -> Vec<Result<(), String>>
+where
+    F: Fn(usize, usize) + Send + Sync + 'static,
+    C: FnMut(usize, usize),
+{
+    // Journal every move before touching the disk.
+    let mut journal_ids: Vec<Result<Option<i64>, String>> = Vec::with_capacity(changes.len());
+    for change in changes {
+        journal_ids.push(if change.folder_move.is_some() {
+            journal_status_change(pool, change).await.map(Some)
+        } else {
+            Ok(None)
+        });
+    }
+
+    // Move the journaled folders in parallel.
+    let moves: Vec<(usize, PathBuf, PathBuf)> = changes
+        .iter()
+        .zip(&journal_ids)
+        .enumerate()
+        .filter_map(
+            |(i, (change, journal_id))| match (&change.folder_move, journal_id) {
+                (Some((from, to)), Ok(Some(_))) => Some((i, from.clone(), to.clone())),
+                _ => None,
+            },
+        )
+        .collect();
+    let move_outcome = tokio::task::spawn_blocking(move || {
+        use rayon::prelude::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let total = moves.len();
+        let done = AtomicUsize::new(0);
+        moves
+            .into_par_iter()
+            .map(|(i, from, to)| {
+                let result = move_folder(&from, &to);
+                on_moved(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+                (i, result)
+            })
+            .collect::<Vec<_>>()
+    })
+    .await;
+    let mut move_results: Vec<Option<Result<(), String>>> = vec![None; changes.len()];
+    match move_outcome {
+        Ok(finished) => {
+            for (i, result) in finished {
+                move_results[i] = Some(result);
+            }
+        }
+        // The moves may or may not have happened; leave the journal entries
+        // for startup recovery to resolve against the disk.
+        Err(e) => {
+            let e = format!("Task join error: {e}");
+            return changes.iter().map(|_| Err(e.clone())).collect();
+        }
+    }
+
+    // Commit the rows whose folders moved; drop the intents that failed.
+    let mut results = Vec::with_capacity(changes.len());
+    for (i, (change, journal_id)) in changes.iter().zip(journal_ids).enumerate() {
+        let result = match (journal_id, move_results[i].take()) {
+            (Err(e), _) => Err(e),
+            (Ok(None), _) => run_status_change(pool, change).await.map(|_| ()),
+            (Ok(Some(id)), Some(Ok(()))) => commit_journaled_change(pool, id, change).await,
+            (Ok(Some(id)), move_result) => {
+                let e = move_result
+                    .and_then(Result::err)
+                    .unwrap_or_else(|| "Folder was not moved".to_string());
+                discard_journal_entry(pool, id).await.and(Err(e))
+            }
+        };
+        results.push(result);
+        on_committed(i + 1, changes.len());
+    }
+
+    results
+}
+
+pub(super) async fn journal_status_change(
+    pool: &SqlitePool,
+    change: &StatusChange,
+) -> Result<i64, String> {
+    let (from, to) = change
+        .folder_move
+        .as_ref()
+        .ok_or("Only status changes with a folder move are journaled")?;
+
+    sqlx::query(
+        "INSERT INTO pending_operations
+            (property_id, old_status, new_status, folder_path, source_path, dest_path, source, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(change.property_id)
+    .bind(&change.old_status)
+    .bind(&change.new_status)
+    .bind(&change.folder_path)
+    .bind(from.to_string_lossy())
+    .bind(to.to_string_lossy())
+    .bind(&change.source)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(pool)
+    .await
+    .map(|result| result.last_insert_rowid())
+    .map_err(|e| format!("Failed to journal folder move: {e}"))
+}
+
+async fn commit_journaled_change(
+    pool: &SqlitePool,
+    journal_id: i64,
+    change: &StatusChange,
+) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {e}"))?;
+
+    apply_status_change(&mut tx, change).await?;
+    if change.source == CITY_MERGE_SOURCE {
+        apply_merged_city(&mut tx, change).await?;
+    }
+
+    sqlx::query("DELETE FROM pending_operations WHERE id = ?")
+        .bind(journal_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to clear journal entry: {e}"))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit status change: {e}"))
+}
+
+/// Replay the city half of an interrupted `merge_cities`: the row takes the
+/// city of its journaled `folder_path`.
+async fn apply_merged_city(
+    conn: &mut SqliteConnection,
+    change: &StatusChange,
+) -> Result<(), String> {
+    let Some((city, _)) = change.folder_path.split_once('/') else {
+        return Err(format!("Invalid folder_path: {}", change.folder_path));
+    };
+    let old_city: String = sqlx::query_scalar("SELECT city FROM properties WHERE id = ?")
+        .bind(change.property_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to load property: {e}"))?;
+    if old_city == city {
+        return Ok(());
+    }
+
+    sqlx::query("UPDATE properties SET city = ? WHERE id = ?")
+        .bind(city)
+        .bind(change.property_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to update property: {e}"))?;
+    sqlx::query(
+        "INSERT INTO cities (name, usage_count, created_at) VALUES (?, 1, ?)
+         ON CONFLICT(name) DO UPDATE SET usage_count = usage_count + 1",
+    )
+    .bind(city)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to update city: {e}"))?;
+    record_property_event(
+        conn,
+        change.property_id,
+        PropertyEventKind::CityChange,
+        Some(&old_city),
+        Some(city),
+        &change.source,
+    )
+    .await
+}
+
+pub(super) async fn discard_journal_entry(
+    pool: &SqlitePool,
+    journal_id: i64,
+) -> Result<(), String> {
+    sqlx::query("DELETE FROM pending_operations WHERE id = ?")
+        .bind(journal_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to clear journal entry: {e}"))?;
+    Ok(())
+}
+
+/// Resolve every journal entry left behind by a crash. Called once from
+/// `init_database` after migrations.
+///
+/// - Only the destination exists: the move finished, so replay the row update.
+/// - Only the source exists: the move never happened, so drop the intent.
+/// - Both exist: a copy to another filesystem was interrupted while deleting
+///   the source. The destination only appears once every file is verified,
+///   so the move is committed and the rest of the source removed.
+/// - Neither exists: someone changed the folders by hand. The intent is
+///   dropped and `repair_property_statuses` is left to sort it out.
+pub(super) async fn recover_pending_operations(pool: &SqlitePool) -> Result<(), String> {
+    let rows = sqlx::query(
+        "SELECT id, property_id, old_status, new_status, folder_path, source_path, dest_path, source
+         FROM pending_operations ORDER BY id ASC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to read pending operations: {e}"))?;
+
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let pending: Vec<(i64, StatusChange)> = rows
+        .into_iter()
+        .map(|row| {
+            let source_path: String = row.get("source_path");
+            let dest_path: String = row.get("dest_path");
+            let change = StatusChange {
+                property_id: row.get("property_id"),
+                old_status: row.get("old_status"),
+                new_status: row.get("new_status"),
+                folder_path: row.get("folder_path"),
+                folder_move: Some((PathBuf::from(source_path), PathBuf::from(dest_path))),
+                source: row.get("source"),
+            };
+            (row.get("id"), change)
+        })
+        .collect();
+
+    let paths: Vec<(PathBuf, PathBuf)> = pending
+        .iter()
+        .filter_map(|(_, change)| change.folder_move.clone())
+        .collect();
+    let on_disk: Vec<(bool, bool)> = tokio::task::spawn_blocking(move || {
+        paths
+            .iter()
+            .map(|(from, to)| (from.exists(), to.exists()))
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
+
+    for ((journal_id, change), (source_exists, dest_exists)) in pending.iter().zip(on_disk) {
+        match (source_exists, dest_exists) {
+            (false, true) => {
+                commit_journaled_change(pool, *journal_id, change).await?;
+                println!(
+                    "Recovered interrupted move of property {} to {}",
+                    change.property_id, change.new_status
+                );
+            }
+            (true, false) => {
+                discard_journal_entry(pool, *journal_id).await?;
+                println!(
+                    "Rolled back interrupted move of property {} (folder never moved)",
+                    change.property_id
+                );
+            }
+            (true, true) => {
+                commit_journaled_change(pool, *journal_id, change).await?;
+                if let Some((from, _)) = change.folder_move.clone() {
+                    let removed = tokio::task::spawn_blocking(move || remove_source(&from))
+                        .await
+                        .map_err(|e| format!("Task join error: {e}"))?;
+                    if let Err(e) = removed {
+                        eprintln!(
+                            "Recovered interrupted copy of property {} but could not remove \
+                            the original: {e}",
+                            change.property_id
+                        );
+                    }
+                }
+                println!(
+                    "Recovered interrupted copy of property {} to {}",
+                    change.property_id, change.new_status
+                );
+            }
+            (false, false) => {
+                discard_journal_entry(pool, *journal_id).await?;
+                eprintln!(
+                    "Could not resolve interrupted move of property {} (neither folder exists). \
+                    Run repair to fix its status.",
+                    change.property_id
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+    use std::fs;
+
+    async fn villa_change(pool: &SqlitePool, from: PathBuf, to: PathBuf) -> StatusChange {
+        add_property_to_database(pool, "Villa", "Athens", "NEW", "Villa", None)
+            .await
+            .unwrap();
+        let property_id: i64 = sqlx::query_scalar("SELECT id FROM properties")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        StatusChange {
+            property_id,
+            old_status: "NEW".to_string(),
+            new_status: "DONE".to_string(),
+            folder_path: "Athens/Villa".to_string(),
+            folder_move: Some((from, to)),
+            source: "update_property_status".to_string(),
+        }
+    }
+
+    async fn status_of(pool: &SqlitePool, property_id: i64) -> String {
+        sqlx::query_scalar("SELECT status FROM properties WHERE id = ?")
+            .bind(property_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn pending_count(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM pending_operations")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn run_status_change_moves_folder_and_clears_journal() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("NEW").join("Athens").join("Villa");
+        let to = tmp.path().join("DONE").join("Athens").join("Villa");
+        fs::create_dir_all(&from).unwrap();
+
+        let change = villa_change(&pool, from.clone(), to.clone()).await;
+        run_status_change(&pool, &change).await.unwrap();
+
+        assert!(!from.exists());
+        assert!(to.exists());
+        assert_eq!(status_of(&pool, change.property_id).await, "DONE");
+        assert_eq!(pending_count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn failed_move_leaves_row_untouched() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        // Source folder does not exist, so the rename fails.
+        let from = tmp.path().join("NEW").join("Athens").join("Villa");
+        let to = tmp.path().join("DONE").join("Athens").join("Villa");
+
+        let change = villa_change(&pool, from, to).await;
+        assert!(run_status_change(&pool, &change).await.is_err());
+
+        assert_eq!(status_of(&pool, change.property_id).await, "NEW");
+        assert_eq!(pending_count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn run_status_changes_isolates_failures() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let mut changes = Vec::new();
+        for name in ["Villa A", "Villa B"] {
+            add_property_to_database(&pool, name, "Athens", "NEW", name, None)
+                .await
+                .unwrap();
+            let property_id: i64 = sqlx::query_scalar("SELECT id FROM properties WHERE name = ?")
+                .bind(name)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            changes.push(StatusChange {
+                property_id,
+                old_status: "NEW".to_string(),
+                new_status: "DONE".to_string(),
+                folder_path: format!("Athens/{name}"),
+                folder_move: Some((
+                    tmp.path().join("NEW").join("Athens").join(name),
+                    tmp.path().join("DONE").join("Athens").join(name),
+                )),
+                source: "bulk_update_property_status".to_string(),
+            });
+        }
+        // Only Villa A exists on disk, so Villa B's move fails.
+        fs::create_dir_all(tmp.path().join("NEW").join("Athens").join("Villa A")).unwrap();
+
+        let progress = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = progress.clone();
+        let mut committed = Vec::new();
+        let results = run_status_changes(
+            &pool,
+            &changes,
+            move |_, total| {
+                assert_eq!(total, 2);
+                counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            },
+            |done, total| committed.push((done, total)),
+        )
+        .await;
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert_eq!(progress.load(std::sync::atomic::Ordering::Relaxed), 2);
+        assert_eq!(committed, vec![(1, 2), (2, 2)]);
+        assert_eq!(status_of(&pool, changes[0].property_id).await, "DONE");
+        assert_eq!(status_of(&pool, changes[1].property_id).await, "NEW");
+        assert!(tmp
+            .path()
+            .join("DONE")
+            .join("Athens")
+            .join("Villa A")
+            .exists());
+        assert_eq!(pending_count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn recovery_replays_move_that_reached_disk() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("NEW").join("Athens").join("Villa");
+        let to = tmp.path().join("DONE").join("Athens").join("Villa");
+        fs::create_dir_all(&to).unwrap();
+
+        // Crash after the folder moved but before the row was updated.
+        let change = villa_change(&pool, from, to).await;
+        journal_status_change(&pool, &change).await.unwrap();
+
+        recover_pending_operations(&pool).await.unwrap();
+
+        assert_eq!(status_of(&pool, change.property_id).await, "DONE");
+        assert_eq!(pending_count(&pool).await, 0);
+        let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM property_events")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(events, 2);
+    }
+
+    #[tokio::test]
+    async fn recovery_replays_city_merge_move() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("NEW").join("Athína").join("Villa");
+        let to = tmp.path().join("NEW").join("Athens").join("Villa");
+        fs::create_dir_all(&to).unwrap();
+        add_property_to_database(&pool, "Villa", "Athína", "NEW", "Villa", None)
+            .await
+            .unwrap();
+        let property_id: i64 = sqlx::query_scalar("SELECT id FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        // Crash after the merge moved the folder but before the rows were written.
+        let change = StatusChange {
+            property_id,
+            old_status: "NEW".to_string(),
+            new_status: "NEW".to_string(),
+            folder_path: "Athens/Villa".to_string(),
+            folder_move: Some((from, to)),
+            source: CITY_MERGE_SOURCE.to_string(),
+        };
+        journal_status_change(&pool, &change).await.unwrap();
+
+        recover_pending_operations(&pool).await.unwrap();
+
+        let row: (String, String) =
+            sqlx::query_as("SELECT city, folder_path FROM properties WHERE id = ?")
+                .bind(property_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(row, ("Athens".into(), "Athens/Villa".into()));
+        assert_eq!(pending_count(&pool).await, 0);
+        let city_changes: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM property_events WHERE event_type = 'city_change'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(city_changes, 1);
+    }
+
+    #[tokio::test]
+    async fn recovery_drops_move_that_never_happened() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("NEW").join("Athens").join("Villa");
+        let to = tmp.path().join("DONE").join("Athens").join("Villa");
+        fs::create_dir_all(&from).unwrap();
+
+        // Crash after journaling but before the folder moved.
+        let change = villa_change(&pool, from, to).await;
+        journal_status_change(&pool, &change).await.unwrap();
+
+        recover_pending_operations(&pool).await.unwrap();
+
+        assert_eq!(status_of(&pool, change.property_id).await, "NEW");
+        assert_eq!(pending_count(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn recovery_finishes_copy_interrupted_while_removing_source() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("NEW").join("Athens").join("Villa");
+        let to = tmp.path().join("DONE").join("Athens").join("Villa");
+        fs::create_dir_all(&from).unwrap();
+        fs::write(from.join("2.jpg"), b"two").unwrap();
+        fs::create_dir_all(&to).unwrap();
+        fs::write(to.join("1.jpg"), b"one").unwrap();
+        fs::write(to.join("2.jpg"), b"two").unwrap();
+
+        // Crash after the verified copy was renamed into place, part-way
+        // through deleting the source.
+        let change = villa_change(&pool, from.clone(), to.clone()).await;
+        journal_status_change(&pool, &change).await.unwrap();
+
+        recover_pending_operations(&pool).await.unwrap();
+
+        assert_eq!(status_of(&pool, change.property_id).await, "DONE");
+        assert_eq!(pending_count(&pool).await, 0);
+        assert!(!from.exists());
+        assert!(to.join("2.jpg").exists());
+    }
+}
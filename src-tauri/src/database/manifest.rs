@@ -0,0 +1,302 @@
+//! Set manifests. Every set ZIP carries a `manifest.json` and a
+//! `manifest.csv` at its root listing each property with its portal codes
+//! and every image in its folder and subfolders, with the ZIP entry path,
+//! size and SHA-256, so whoever uploads the set can check it is complete
+//! without opening each folder.
+//!
+//! Image details come from the `images` table, which is brought up to date
+//! for each property while the manifest is built. That hashes every new or
+//! changed photo, so it reports progress and can be cancelled like the ZIP.
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::database::codes::load_property_codes;
+use crate::database::folder_path_to_pathbuf;
+use crate::database::images::index_property_images;
+use crate::database::set_zip::CANCELLED;
+use crate::database::types::{Property, PropertyCode, SetZipProgress};
+
+pub(super) const MANIFEST_JSON: &str = "manifest.json";
+pub(super) const MANIFEST_CSV: &str = "manifest.csv";
+
+const CSV_HEADERS: [&str; 10] = [
+    "property_id",
+    "name",
+    "city",
+    "code",
+    "codes",
+    "subfolder",
+    "filename",
+    "path",
+    "size",
+    "sha256",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct SetManifest {
+    pub set_name: String,
+    /// RFC 3339.
+    pub created_at: String,
+    pub properties: Vec<ManifestProperty>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ManifestProperty {
+    pub property_id: i64,
+    pub name: String,
+    pub city: String,
+    /// Primary code.
+    pub code: Option<String>,
+    pub codes: Vec<PropertyCode>,
+    /// ZIP path of the property folder, e.g. `Athens/Villa (123)/`.
+    pub folder: String,
+    pub images: Vec<ManifestImage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ManifestImage {
+    /// ZIP entry path.
+    pub path: String,
+    /// Folder relative to the property folder, `""` for the originals.
+    pub subfolder: String,
+    pub filename: String,
+    pub size: i64,
+    pub sha256: String,
+}
+
+/// Describe the properties going into a set, whose folders are under
+/// `base_path`. Properties whose folder is missing are left out, as they
+/// are from the ZIP. `cancel` is checked and progress reported before each
+/// property and once all are indexed.
+pub(super) async fn build_set_manifest(
+    pool: &SqlitePool,
+    set_name: &str,
+    base_path: &Path,
+    properties: &[Property],
+    cancel: &AtomicBool,
+    on_progress: &mut (dyn FnMut(SetZipProgress) + Send),
+) -> Result<SetManifest, String> {
+    let mut manifest = SetManifest {
+        set_name: set_name.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        properties: Vec::with_capacity(properties.len()),
+    };
+    let mut progress = SetZipProgress {
+        total_properties: properties.len(),
+        ..SetZipProgress::default()
+    };
+
+    for property in properties {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(CANCELLED.to_string());
+        }
+        on_progress(progress);
+        progress.properties_indexed += 1;
+        let Some(property_id) = property.id else {
+            continue;
+        };
+        let dir = base_path.join(folder_path_to_pathbuf(&property.folder_path));
+        let exists = {
+            let dir = dir.clone();
+            tokio::task::spawn_blocking(move || dir.is_dir())
+                .await
+                .map_err(|e| format!("Task join error: {e}"))?
+        };
+        if !exists {
+            continue;
+        }
+
+        let folder = format!("{}/", property.folder_path.trim_matches('/'));
+        let images = index_property_images(pool, property_id, dir)
+            .await?
+            .into_iter()
+            .map(|image| {
+                let path = if image.subfolder.is_empty() {
+                    format!("{folder}{}", image.filename)
+                } else {
+                    format!("{folder}{}/{}", image.subfolder, image.filename)
+                };
+                ManifestImage {
+                    path,
+                    subfolder: image.subfolder,
+                    filename: image.filename,
+                    size: image.size,
+                    sha256: image.sha256,
+                }
+            })
+            .collect();
+
+        manifest.properties.push(ManifestProperty {
+            property_id,
+            name: property.name.clone(),
+            city: property.city.clone(),
+            code: property.code.clone(),
+            codes: load_property_codes(pool, property_id).await?,
+            folder,
+            images,
+        });
+    }
+    on_progress(progress);
+
+    Ok(manifest)
+}
+
+pub(super) fn manifest_json(manifest: &SetManifest) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(manifest).map_err(|e| format!("Failed to write manifest: {e}"))
+}
+
+/// One row per image. A property without images still gets a row, with the
+/// image columns empty, so every property in the set appears.
+pub(super) fn manifest_csv(manifest: &SetManifest) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(CSV_HEADERS)
+        .map_err(|e| format!("Failed to write manifest: {e}"))?;
+
+    for property in &manifest.properties {
+        let codes = property
+            .codes
+            .iter()
+            .map(|code| format!("{}:{}", code.portal, code.code))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let property_fields = [
+            property.property_id.to_string(),
+            property.name.clone(),
+            property.city.clone(),
+            property.code.clone().unwrap_or_default(),
+            codes,
+        ];
+
+        let image_rows: Vec<[String; 5]> = if property.images.is_empty() {
+            vec![Default::default()]
+        } else {
+            property
+                .images
+                .iter()
+                .map(|image| {
+                    [
+                        image.subfolder.clone(),
+                        image.filename.clone(),
+                        image.path.clone(),
+                        image.size.to_string(),
+                        image.sha256.clone(),
+                    ]
+                })
+                .collect()
+        };
+        for image_fields in image_rows {
+            writer
+                .write_record(property_fields.iter().chain(&image_fields))
+                .map_err(|e| format!("Failed to write manifest: {e}"))?;
+        }
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write manifest: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+    use std::fs;
+
+    #[tokio::test]
+    async fn manifest_lists_images_and_codes() {
+        let pool = setup_test_db().await;
+        add_property_to_database(&pool, "Villa", "Athens", "DONE", "Villa (123)", Some("123"))
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Loft", "Rome", "DONE", "Loft (456)", Some("456"))
+            .await
+            .unwrap();
+        let tmp = tempfile::tempdir().unwrap();
+        let villa = tmp.path().join("Athens").join("Villa (123)");
+        fs::create_dir_all(villa.join("INTERNET")).unwrap();
+        fs::write(villa.join("1.jpg"), b"one").unwrap();
+        fs::write(villa.join("INTERNET").join("1.jpg"), b"internet").unwrap();
+        fs::create_dir_all(tmp.path().join("Rome").join("Loft (456)")).unwrap();
+
+        let properties: Vec<Property> =
+            sqlx::query_as::<_, (i64, String, String, String, Option<String>)>(
+                "SELECT id, name, city, folder_path, code FROM properties ORDER BY id",
+            )
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id, name, city, folder_path, code)| Property {
+                id: Some(id),
+                name,
+                city,
+                status: "DONE".to_string(),
+                folder_path,
+                notes: None,
+                code,
+                tags: Vec::new(),
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                completed: None,
+            })
+            .collect();
+
+        let cancel = AtomicBool::new(true);
+        let cancelled = build_set_manifest(
+            &pool,
+            "Done - test",
+            tmp.path(),
+            &properties,
+            &cancel,
+            &mut |_| {},
+        )
+        .await;
+        assert_eq!(cancelled.unwrap_err(), CANCELLED);
+
+        cancel.store(false, Ordering::Relaxed);
+        let mut reports = Vec::new();
+        let manifest = build_set_manifest(
+            &pool,
+            "Done - test",
+            tmp.path(),
+            &properties,
+            &cancel,
+            &mut |p| reports.push(p.properties_indexed),
+        )
+        .await
+        .unwrap();
+        assert_eq!(reports, vec![0, 1, 2]);
+        assert_eq!(manifest.properties.len(), 2);
+        let villa = &manifest.properties[0];
+        assert_eq!(villa.folder, "Athens/Villa (123)/");
+        assert_eq!(villa.codes[0].code, "123");
+        let paths: Vec<&str> = villa.images.iter().map(|i| i.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "Athens/Villa (123)/1.jpg",
+                "Athens/Villa (123)/INTERNET/1.jpg"
+            ]
+        );
+
+        let csv = String::from_utf8(manifest_csv(&manifest).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        // Header, two Villa images, and one empty row for Loft.
+        assert_eq!(lines.len(), 4);
+        assert!(lines[3].starts_with(&format!(
+            "{},Loft,Rome,456,website:456,,",
+            properties[1].id.unwrap()
+        )));
+
+        let json: SetManifest = serde_json::from_slice(&manifest_json(&manifest).unwrap()).unwrap();
+        assert_eq!(json.properties[0].images[1].size, 8);
+    }
+}
@@ -3,11 +3,20 @@
 //! `init_database` is called once on app startup from main.rs and returns
 //! the SQLx pool that the rest of the app stores in Tauri state.
 //!
+//! Migrations are numbered and recorded in the `schema_version` table.
+//! Each one runs inside its own transaction together with its
+//! `schema_version` row, so an upgrade is either fully applied or not at
+//! all. A database stamped with a version newer than [`SCHEMA_VERSION`]
+//! is refused rather than opened by an older build.
+//!
 //! Extracted from database.rs in the database-module split.
 
-use sqlx::{Row, SqlitePool};
+use sqlx::{Row, SqliteConnection, SqlitePool};
 use tauri::Manager;
 
+use super::search::greek_fold_sql;
+use super::{record_property_event, PropertyEventKind};
+
 // Database initialization
 pub async fn init_database(app: &tauri::AppHandle) -> Result<SqlitePool, String> {
     let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
@@ -53,10 +62,149 @@ pub async fn init_database(app: &tauri::AppHandle) -> Result<SqlitePool, String>
 
     println!("Database migrations completed successfully");
 
+    // Finish or roll back any folder move interrupted by a crash. A failure
+    // here is logged rather than fatal; repair_property_statuses can still
+    // reconcile whatever is left.
+    if let Err(e) = super::journal::recover_pending_operations(&pool).await {
+        eprintln!("Failed to recover pending operations: {e}");
+    }
+
     Ok(pool)
 }
 
+/// Ordered list of schema migrations as `(version, description)`.
+/// Append new entries at the end; never renumber or edit an entry that
+/// has shipped, since its version is already stamped into users' databases.
+const MIGRATIONS: &[(i64, &str)] = &[
+    (1, "create properties and cities tables"),
+    (2, "add properties.status and backfill from completed"),
+    (3, "add properties.code"),
+    (4, "create sets and set_properties tables"),
+    (5, "create property_events audit log"),
+    (6, "create pending_operations journal"),
+    (7, "create properties_fts full-text index"),
+    (8, "add properties.deleted_at and trash_path"),
+    (9, "create tags and property_tags tables"),
+    (10, "make properties.code unique"),
+    (11, "create property_codes table"),
+    (12, "create scan_state mtime cache"),
+    (13, "create images metadata table"),
+    (14, "add sets.reverted_at"),
+];
+
+/// Latest schema version this build knows how to run against.
+pub(super) const SCHEMA_VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].0;
+
 pub(super) async fn run_migrations(pool: &SqlitePool) -> Result<(), String> {
+    sqlx::query(
+        r"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        )
+        ",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create schema_version table: {e}"))?;
+
+    let current_version = get_schema_version(pool).await?;
+
+    // Refuse to touch a database written by a newer build. Running our
+    // (older) migrations against it could silently drop data it relies on.
+    if current_version > SCHEMA_VERSION {
+        return Err(format!(
+            "Database schema version {current_version} is newer than this version of \
+            Realtr supports ({SCHEMA_VERSION}). Please update the application."
+        ));
+    }
+
+    for &(version, description) in MIGRATIONS {
+        if version <= current_version {
+            continue;
+        }
+
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start migration {version}: {e}"))?;
+
+        apply_migration(&mut tx, version)
+            .await
+            .map_err(|e| format!("Migration {version} ({description}) failed: {e}"))?;
+
+        sqlx::query(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)",
+        )
+        .bind(version)
+        .bind(description)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to record migration {version}: {e}"))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit migration {version}: {e}"))?;
+
+        println!("Applied database migration {version}: {description}");
+    }
+
+    Ok(())
+}
+
+/// Highest migration version recorded in `schema_version`, or 0 for a
+/// fresh (or pre-versioning) database.
+pub(super) async fn get_schema_version(pool: &SqlitePool) -> Result<i64, String> {
+    sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await
+        .map(Option::unwrap_or_default)
+        .map_err(|e| format!("Failed to read schema version: {e}"))
+}
+
+async fn apply_migration(conn: &mut SqliteConnection, version: i64) -> Result<(), String> {
+    match version {
+        1 => migration_001_base_tables(conn).await,
+        2 => migration_002_status_column(conn).await,
+        3 => migration_003_code_column(conn).await,
+        4 => migration_004_sets_tables(conn).await,
+        5 => migration_005_property_events(conn).await,
+        6 => migration_006_pending_operations(conn).await,
+        7 => migration_007_properties_fts(conn).await,
+        8 => migration_008_soft_delete(conn).await,
+        9 => migration_009_tags(conn).await,
+        10 => migration_010_unique_codes(conn).await,
+        11 => migration_011_property_codes(conn).await,
+        12 => migration_012_scan_state(conn).await,
+        13 => migration_013_images(conn).await,
+        14 => migration_014_set_reverted_at(conn).await,
+        _ => Err(format!("Unknown migration version {version}")),
+    }
+}
+
+/// Check whether `table` already has `column`. Databases created before
+/// `schema_version` existed may already contain columns that a migration
+/// would add, so the column migrations use this to stay idempotent.
+async fn column_exists(
+    conn: &mut SqliteConnection,
+    table: &str,
+    column: &str,
+) -> Result<bool, String> {
+    let columns = sqlx::query(&format!("PRAGMA table_info({table})"))
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to check table info for {table}: {e}"))?;
+
+    Ok(columns.iter().any(|row| {
+        row.try_get::<String, _>("name")
+            .map(|name| name == column)
+            .unwrap_or(false)
+    }))
+}
+
+async fn migration_001_base_tables(conn: &mut SqliteConnection) -> Result<(), String> {
     // Create properties table with TIMESTAMP columns
     sqlx::query(
         r#"
@@ -72,7 +220,7 @@ pub(super) async fn run_migrations(pool: &SqlitePool) -> Result<(), String> {
         )
         "#,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| format!("Failed to create properties table: {}", e))?;
 
@@ -87,23 +235,23 @@ pub(super) async fn run_migrations(pool: &SqlitePool) -> Result<(), String> {
         )
         "#,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| format!("Failed to create cities table: {}", e))?;
 
     // Create indexes
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_completed ON properties(completed)")
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| format!("Failed to create completed index: {}", e))?;
 
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_city ON properties(city)")
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| format!("Failed to create city index: {}", e))?;
 
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_cities_name ON cities(name)")
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| format!("Failed to create cities name index: {}", e))?;
 
@@ -113,27 +261,21 @@ pub(super) async fn run_migrations(pool: &SqlitePool) -> Result<(), String> {
     sqlx::query(
         "CREATE INDEX IF NOT EXISTS idx_properties_created_at ON properties(created_at DESC)",
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| format!("Failed to create properties created_at index: {}", e))?;
 
-    // Migration: Add status column if it doesn't exist
-    // First check if the column exists
-    let column_check = sqlx::query("PRAGMA table_info(properties)")
-        .fetch_all(pool)
-        .await
-        .map_err(|e| format!("Failed to check table info: {}", e))?;
-
-    let has_status_column = column_check.iter().any(|row| {
-        row.try_get::<String, _>("name")
-            .map(|name| name == "status")
-            .unwrap_or(false)
-    });
+    Ok(())
+}
 
-    if !has_status_column {
+async fn migration_002_status_column(conn: &mut SqliteConnection) -> Result<(), String> {
+    // Only backfill when the column is actually added here. On a legacy
+    // database that already has `status`, re-running the backfill would flip
+    // properties moved back to NEW (but still `completed = 1`) to DONE.
+    if !column_exists(conn, "properties", "status").await? {
         // Add status column with default value 'NEW'
         sqlx::query("ALTER TABLE properties ADD COLUMN status TEXT DEFAULT 'NEW'")
-            .execute(pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| format!("Failed to add status column: {}", e))?;
 
@@ -148,37 +290,38 @@ pub(super) async fn run_migrations(pool: &SqlitePool) -> Result<(), String> {
             WHERE status IS NULL OR status = 'NEW'
             "#,
         )
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| format!("Failed to migrate completed to status: {}", e))?;
-
-        // Create index for status column
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_status ON properties(status)")
-            .execute(pool)
-            .await
-            .map_err(|e| format!("Failed to create status index: {}", e))?;
     }
 
-    // Migration: Add code column if it doesn't exist
-    let has_code_column = column_check.iter().any(|row| {
-        row.try_get::<String, _>("name")
-            .map(|name| name == "code")
-            .unwrap_or(false)
-    });
+    // Create index for status column
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_status ON properties(status)")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to create status index: {}", e))?;
+
+    Ok(())
+}
 
-    if !has_code_column {
+async fn migration_003_code_column(conn: &mut SqliteConnection) -> Result<(), String> {
+    if !column_exists(conn, "properties", "code").await? {
         sqlx::query("ALTER TABLE properties ADD COLUMN code TEXT")
-            .execute(pool)
+            .execute(&mut *conn)
             .await
             .map_err(|e| format!("Failed to add code column: {}", e))?;
-
-        // Create index for code column to enable fast searches
-        sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_code ON properties(code)")
-            .execute(pool)
-            .await
-            .map_err(|e| format!("Failed to create code index: {}", e))?;
     }
 
+    // Create index for code column to enable fast searches
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_code ON properties(code)")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to create code index: {}", e))?;
+
+    Ok(())
+}
+
+async fn migration_004_sets_tables(conn: &mut SqliteConnection) -> Result<(), String> {
     // Create sets table for tracking completed property sets
     sqlx::query(
         r"
@@ -191,7 +334,7 @@ pub(super) async fn run_migrations(pool: &SqlitePool) -> Result<(), String> {
         )
         ",
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| format!("Failed to create sets table: {}", e))?;
 
@@ -209,20 +352,402 @@ pub(super) async fn run_migrations(pool: &SqlitePool) -> Result<(), String> {
         )
         ",
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| format!("Failed to create set_properties table: {}", e))?;
 
     // Create indexes for sets tables
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_sets_created_at ON sets(created_at)")
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| format!("Failed to create sets created_at index: {}", e))?;
 
     sqlx::query("CREATE INDEX IF NOT EXISTS idx_set_properties_set_id ON set_properties(set_id)")
-        .execute(pool)
+        .execute(&mut *conn)
         .await
         .map_err(|e| format!("Failed to create set_properties set_id index: {}", e))?;
 
     Ok(())
 }
+
+async fn migration_005_property_events(conn: &mut SqliteConnection) -> Result<(), String> {
+    // Append-only audit log. No foreign key to properties on purpose: the
+    // history should outlive the row it describes.
+    sqlx::query(
+        r"
+        CREATE TABLE IF NOT EXISTS property_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            property_id INTEGER NOT NULL,
+            event_type TEXT NOT NULL,
+            old_value TEXT,
+            new_value TEXT,
+            source TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+        ",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to create property_events table: {e}"))?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_property_events_property \
+         ON property_events(property_id, created_at)",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to create property_events index: {e}"))?;
+
+    Ok(())
+}
+
+async fn migration_006_pending_operations(conn: &mut SqliteConnection) -> Result<(), String> {
+    // Write-ahead journal for folder moves (see database::journal). Rows only
+    // live between "about to move" and "row updated"; anything left at
+    // startup is an operation interrupted by a crash.
+    sqlx::query(
+        r"
+        CREATE TABLE IF NOT EXISTS pending_operations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            property_id INTEGER NOT NULL,
+            old_status TEXT NOT NULL,
+            new_status TEXT NOT NULL,
+            folder_path TEXT NOT NULL,
+            source_path TEXT NOT NULL,
+            dest_path TEXT NOT NULL,
+            source TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+        ",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to create pending_operations table: {e}"))?;
+
+    Ok(())
+}
+
+async fn migration_007_properties_fts(conn: &mut SqliteConnection) -> Result<(), String> {
+    // Full-text index for search_properties. rowid mirrors properties.id and
+    // the columns hold Greek-folded copies of the source text (see
+    // database::search); triggers keep it in step with every write.
+    sqlx::query(
+        r#"
+        CREATE VIRTUAL TABLE IF NOT EXISTS properties_fts USING fts5(
+            name, city, code, notes,
+            tokenize = "unicode61 remove_diacritics 2"
+        )
+        "#,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to create properties_fts table: {e}"))?;
+
+    let insert_row = |prefix: &str| {
+        format!(
+            "INSERT INTO properties_fts(rowid, name, city, code, notes) \
+             VALUES ({prefix}.id, {}, {}, {}, {});",
+            greek_fold_sql(&format!("{prefix}.name")),
+            greek_fold_sql(&format!("{prefix}.city")),
+            greek_fold_sql(&format!("{prefix}.code")),
+            greek_fold_sql(&format!("{prefix}.notes")),
+        )
+    };
+
+    let statements = [
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS properties_fts_insert AFTER INSERT ON properties BEGIN \
+             {} END",
+            insert_row("new")
+        ),
+        "CREATE TRIGGER IF NOT EXISTS properties_fts_delete AFTER DELETE ON properties BEGIN \
+         DELETE FROM properties_fts WHERE rowid = old.id; END"
+            .to_string(),
+        format!(
+            "CREATE TRIGGER IF NOT EXISTS properties_fts_update \
+             AFTER UPDATE OF name, city, code, notes ON properties BEGIN \
+             DELETE FROM properties_fts WHERE rowid = old.id; {} END",
+            insert_row("new")
+        ),
+        // Backfill whatever the table already holds.
+        "DELETE FROM properties_fts".to_string(),
+        format!(
+            "INSERT INTO properties_fts(rowid, name, city, code, notes) \
+             SELECT id, {}, {}, {}, {} FROM properties",
+            greek_fold_sql("name"),
+            greek_fold_sql("city"),
+            greek_fold_sql("code"),
+            greek_fold_sql("notes"),
+        ),
+    ];
+
+    for statement in &statements {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to set up properties_fts: {e}"))?;
+    }
+
+    Ok(())
+}
+
+async fn migration_008_soft_delete(conn: &mut SqliteConnection) -> Result<(), String> {
+    // deleted_at marks a property as trashed; trash_path records where its
+    // folder went so restore and purge can find it (see database::trash).
+    for (column, definition) in [("deleted_at", "INTEGER"), ("trash_path", "TEXT")] {
+        if !column_exists(conn, "properties", column).await? {
+            sqlx::query(&format!(
+                "ALTER TABLE properties ADD COLUMN {column} {definition}"
+            ))
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add properties.{column}: {e}"))?;
+        }
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_properties_deleted_at ON properties(deleted_at)")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to create idx_properties_deleted_at: {e}"))?;
+
+    Ok(())
+}
+
+async fn migration_009_tags(conn: &mut SqliteConnection) -> Result<(), String> {
+    // Free-form tags (see database::tags). usage_count is maintained by the
+    // property_tags triggers below, and links go away with their property.
+    let statements = [
+        r"
+        CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            usage_count INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        )
+        ",
+        r"
+        CREATE TABLE IF NOT EXISTS property_tags (
+            property_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (property_id, tag_id)
+        )
+        ",
+        "CREATE INDEX IF NOT EXISTS idx_property_tags_tag ON property_tags(tag_id)",
+        r"
+        CREATE TRIGGER IF NOT EXISTS property_tags_count_insert AFTER INSERT ON property_tags
+        BEGIN
+            UPDATE tags SET usage_count = usage_count + 1 WHERE id = new.tag_id;
+        END
+        ",
+        r"
+        CREATE TRIGGER IF NOT EXISTS property_tags_count_delete AFTER DELETE ON property_tags
+        BEGIN
+            UPDATE tags SET usage_count = usage_count - 1 WHERE id = old.tag_id;
+        END
+        ",
+        r"
+        CREATE TRIGGER IF NOT EXISTS properties_tags_delete AFTER DELETE ON properties
+        BEGIN
+            DELETE FROM property_tags WHERE property_id = old.id;
+        END
+        ",
+    ];
+
+    for statement in statements {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to create tags schema: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Earlier builds let several properties share a code. The earliest row
+/// keeps it; the others lose it, with an audit event so the user can see
+/// which codes to re-enter. Codes compare case-insensitively.
+async fn migration_010_unique_codes(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query("UPDATE properties SET code = NULL WHERE trim(code) = ''")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| format!("Failed to clear blank codes: {e}"))?;
+
+    let duplicates: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT id, code FROM properties p
+         WHERE code IS NOT NULL
+           AND EXISTS (SELECT 1 FROM properties o
+                       WHERE o.code = p.code COLLATE NOCASE AND o.id < p.id)",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to find duplicate codes: {e}"))?;
+
+    for (id, code) in duplicates {
+        sqlx::query("UPDATE properties SET code = NULL WHERE id = ?")
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to clear duplicate code: {e}"))?;
+        record_property_event(
+            conn,
+            id,
+            PropertyEventKind::CodeAssigned,
+            Some(&code),
+            None,
+            "migration_010_unique_codes",
+        )
+        .await?;
+    }
+
+    sqlx::query(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_properties_code_unique \
+         ON properties(code COLLATE NOCASE) WHERE code IS NOT NULL",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to create unique code index: {e}"))?;
+
+    Ok(())
+}
+
+/// One code per portal, at most one of them primary. `properties.code`
+/// stays as the primary code so folder names, search and sets keep working;
+/// rows inserted with a code (scans, imports) get it as their primary
+/// "website" code through a trigger. Set snapshots gain a JSON copy of all
+/// codes next to the primary one.
+async fn migration_011_property_codes(conn: &mut SqliteConnection) -> Result<(), String> {
+    let statements = [
+        r"
+        CREATE TABLE IF NOT EXISTS property_codes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            property_id INTEGER NOT NULL,
+            portal TEXT NOT NULL COLLATE NOCASE,
+            code TEXT NOT NULL COLLATE NOCASE,
+            is_primary INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            UNIQUE (property_id, portal),
+            UNIQUE (portal, code)
+        )
+        ",
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_property_codes_primary \
+         ON property_codes(property_id) WHERE is_primary = 1",
+        "CREATE INDEX IF NOT EXISTS idx_property_codes_code ON property_codes(code)",
+        r"
+        CREATE TRIGGER IF NOT EXISTS properties_codes_insert AFTER INSERT ON properties
+        WHEN new.code IS NOT NULL
+        BEGIN
+            INSERT OR IGNORE INTO property_codes (property_id, portal, code, is_primary, created_at)
+            VALUES (new.id, 'website', new.code, 1, new.created_at);
+        END
+        ",
+        r"
+        CREATE TRIGGER IF NOT EXISTS properties_codes_delete AFTER DELETE ON properties
+        BEGIN
+            DELETE FROM property_codes WHERE property_id = old.id;
+        END
+        ",
+        r"
+        INSERT OR IGNORE INTO property_codes (property_id, portal, code, is_primary, created_at)
+        SELECT id, 'website', code, 1, updated_at FROM properties WHERE code IS NOT NULL
+        ",
+    ];
+
+    for statement in statements {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to create property_codes schema: {e}"))?;
+    }
+
+    if !column_exists(conn, "set_properties", "property_codes").await? {
+        sqlx::query("ALTER TABLE set_properties ADD COLUMN property_codes TEXT")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add set_properties.property_codes: {e}"))?;
+    }
+    sqlx::query(
+        r"
+        UPDATE set_properties
+        SET property_codes = json_array(json_object(
+            'portal', 'website', 'code', property_code, 'isPrimary', json('true')))
+        WHERE property_code IS NOT NULL AND property_codes IS NULL
+        ",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to backfill set property codes: {e}"))?;
+
+    Ok(())
+}
+
+/// Modification time of each city folder when the scanner last read it in
+/// full, so an incremental scan can skip cities nothing was added to.
+async fn migration_012_scan_state(conn: &mut SqliteConnection) -> Result<(), String> {
+    sqlx::query(
+        r"
+        CREATE TABLE IF NOT EXISTS scan_state (
+            path TEXT PRIMARY KEY,
+            mtime INTEGER NOT NULL,
+            scanned_at INTEGER NOT NULL
+        )
+        ",
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to create scan_state table: {e}"))?;
+
+    Ok(())
+}
+
+/// One row per image file in a property folder or one of its INTERNET and
+/// WATERMARK subfolders, filled in lazily by `images::index_property_images`.
+/// `size` and `mtime` tell whether the file changed since it was hashed.
+async fn migration_013_images(conn: &mut SqliteConnection) -> Result<(), String> {
+    let statements = [
+        r"
+        CREATE TABLE IF NOT EXISTS images (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            property_id INTEGER NOT NULL,
+            subfolder TEXT NOT NULL,
+            filename TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            width INTEGER,
+            height INTEGER,
+            mtime INTEGER NOT NULL,
+            sha256 TEXT NOT NULL,
+            captured_at TEXT,
+            indexed_at INTEGER NOT NULL,
+            UNIQUE (property_id, subfolder, filename)
+        )
+        ",
+        "CREATE INDEX IF NOT EXISTS idx_images_sha256 ON images(sha256)",
+        r"
+        CREATE TRIGGER IF NOT EXISTS properties_images_delete AFTER DELETE ON properties
+        BEGIN
+            DELETE FROM images WHERE property_id = old.id;
+        END
+        ",
+    ];
+
+    for statement in statements {
+        sqlx::query(statement)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to create images schema: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// When `restore_set` put a set's properties back into the workflow. The
+/// row is kept so the set's history stays visible.
+async fn migration_014_set_reverted_at(conn: &mut SqliteConnection) -> Result<(), String> {
+    if !column_exists(conn, "sets", "reverted_at").await? {
+        sqlx::query("ALTER TABLE sets ADD COLUMN reverted_at INTEGER")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| format!("Failed to add sets.reverted_at: {e}"))?;
+    }
+    Ok(())
+}
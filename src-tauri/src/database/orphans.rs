@@ -0,0 +1,855 @@
+//! Orphan reconciliation: compares the database with the four status
+//! folders and reports what neither scan nor repair sorts out on its own —
+//! rows whose folder is gone, property folders present in several status
+//! folders at once, and city folders with nothing left in them.
+//!
+//! Every finding carries suggested `OrphanAction`s. The UI sends the chosen
+//! ones back to `apply_orphan_actions`, which re-checks the disk before it
+//! touches anything.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sqlx::SqlitePool;
+
+use crate::config::AppConfig;
+use crate::database::codes::apply_primary_code;
+use crate::database::scan::get_existing_properties_set;
+use crate::database::trash::trash_property;
+use crate::database::types::{
+    CommandResult, OrphanAction, OrphanActionResult, OrphanFinding, OrphanKind, OrphanReport,
+};
+use crate::database::{
+    find_actual_folder_location, get_base_path_for_status, get_database_pool, parse_folder_name,
+    record_property_event, PropertyEventKind,
+};
+use crate::paths::validate_relative_folder_path;
+
+const STATUSES: [&str; 4] = ["NEW", "DONE", "NOT_FOUND", "ARCHIVE"];
+
+/// Everything found under the status folders.
+#[derive(Debug, Default)]
+struct DiskListing {
+    /// `City/Folder` -> every status folder holding a copy.
+    folders: BTreeMap<String, Vec<String>>,
+    /// `(status, city)` for city folders with nothing in them. A city
+    /// holding only loose files is not empty.
+    empty_cities: Vec<(String, String)>,
+    errors: Vec<String>,
+}
+
+/// Configured status folders, in workflow order.
+pub(super) fn status_roots(config: &AppConfig) -> Vec<(String, PathBuf)> {
+    STATUSES
+        .iter()
+        .filter_map(|status| {
+            get_base_path_for_status(config, status)
+                .ok()
+                .map(|path| ((*status).to_string(), path))
+        })
+        .collect()
+}
+
+/// Walk `root/city/property` under every status folder. Blocking.
+fn list_status_roots(roots: &[(String, PathBuf)]) -> DiskListing {
+    let mut listing = DiskListing::default();
+
+    for (status, root) in roots {
+        if !root.is_dir() {
+            continue;
+        }
+        let cities = match fs::read_dir(root) {
+            Ok(entries) => entries,
+            Err(e) => {
+                listing
+                    .errors
+                    .push(format!("Failed to read {status} folder: {e}"));
+                continue;
+            }
+        };
+
+        for city_entry in cities.flatten() {
+            let city_path = city_entry.path();
+            if !city_path.is_dir() {
+                continue;
+            }
+            let Some(city) = city_path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let properties = match fs::read_dir(&city_path) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    listing
+                        .errors
+                        .push(format!("Failed to read city folder {city}: {e}"));
+                    continue;
+                }
+            };
+
+            let mut has_entries = false;
+            for property_entry in properties.flatten() {
+                has_entries = true;
+                let property_path = property_entry.path();
+                if !property_path.is_dir() {
+                    continue;
+                }
+                let Some(folder_name) = property_path.file_name().and_then(|n| n.to_str()) else {
+                    continue;
+                };
+                // Staging folder of a copy or restore in progress.
+                if folder_name.starts_with('.') {
+                    continue;
+                }
+                listing
+                    .folders
+                    .entry(format!("{city}/{folder_name}"))
+                    .or_default()
+                    .push(status.clone());
+            }
+
+            if !has_entries {
+                listing
+                    .empty_cities
+                    .push((status.clone(), city.to_string()));
+            }
+        }
+    }
+
+    listing
+}
+
+/// Turn the disk listing and the live rows `(id, name, status, folder_path)`
+/// into findings. `tracked` holds every `folder_path` in the database,
+/// trashed rows included, so their folders are never offered for relinking.
+fn build_findings(
+    rows: &[(i64, String, String, String)],
+    tracked: &HashSet<String>,
+    listing: &DiskListing,
+) -> Vec<OrphanFinding> {
+    let mut findings = Vec::new();
+
+    for (id, name, status, folder_path) in rows {
+        // A folder that only gained a code suffix is repair's job.
+        let suffixed = format!("{folder_path} (");
+        let on_disk = listing.folders.contains_key(folder_path)
+            || listing
+                .folders
+                .range(suffixed.clone()..)
+                .next()
+                .is_some_and(|(key, _)| key.starts_with(&suffixed));
+        if on_disk {
+            continue;
+        }
+
+        let wanted = name.to_lowercase();
+        let mut suggested_actions: Vec<OrphanAction> = listing
+            .folders
+            .iter()
+            .filter(|(key, _)| !tracked.contains(*key))
+            .filter(|(key, _)| {
+                key.split_once('/').is_some_and(|(_, folder_name)| {
+                    parse_folder_name(folder_name).0.to_lowercase() == wanted
+                })
+            })
+            .flat_map(|(key, statuses)| {
+                statuses
+                    .iter()
+                    .map(|candidate_status| OrphanAction::Relink {
+                        property_id: *id,
+                        status: candidate_status.clone(),
+                        folder_path: key.clone(),
+                    })
+            })
+            .collect();
+        suggested_actions.push(OrphanAction::DeleteRow { property_id: *id });
+
+        findings.push(OrphanFinding {
+            kind: OrphanKind::MissingFolder,
+            property_id: Some(*id),
+            folder_path: folder_path.clone(),
+            statuses: vec![status.clone()],
+            suggested_actions,
+        });
+    }
+
+    let row_by_path: HashMap<&str, (i64, &str)> = rows
+        .iter()
+        .map(|(id, _, status, folder_path)| (folder_path.as_str(), (*id, status.as_str())))
+        .collect();
+
+    for (folder_path, statuses) in &listing.folders {
+        if statuses.len() < 2 {
+            continue;
+        }
+        let row = row_by_path.get(folder_path.as_str());
+
+        // Suggest keeping the copy the database points at first.
+        let mut keep_order = statuses.clone();
+        if let Some(pos) = row.and_then(|(_, status)| keep_order.iter().position(|s| s == status)) {
+            let db_status = keep_order.remove(pos);
+            keep_order.insert(0, db_status);
+        }
+
+        findings.push(OrphanFinding {
+            kind: OrphanKind::DuplicateFolder,
+            property_id: row.map(|(id, _)| *id),
+            folder_path: folder_path.clone(),
+            statuses: statuses.clone(),
+            suggested_actions: keep_order
+                .into_iter()
+                .map(|keep_status| OrphanAction::Merge {
+                    folder_path: folder_path.clone(),
+                    keep_status,
+                })
+                .collect(),
+        });
+    }
+
+    for (status, city) in &listing.empty_cities {
+        findings.push(OrphanFinding {
+            kind: OrphanKind::EmptyCity,
+            property_id: None,
+            folder_path: city.clone(),
+            statuses: vec![status.clone()],
+            suggested_actions: vec![OrphanAction::RemoveCityFolder {
+                status: status.clone(),
+                city: city.clone(),
+            }],
+        });
+    }
+
+    findings
+}
+
+/// Move everything in `src` into `dst`, descending into folders that exist
+/// in both. Never overwrites: entries present on both sides stay in `src`
+/// and are returned as conflicts.
+fn merge_folder_into(src: &Path, dst: &Path, conflicts: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(src).map_err(|e| format!("Failed to read {}: {e}", src.display()))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let from = entry.path();
+        let to = dst.join(entry.file_name());
+
+        if !to.exists() {
+            fs::rename(&from, &to)
+                .map_err(|e| format!("Failed to move {}: {e}", from.display()))?;
+        } else if from.is_dir() && to.is_dir() {
+            merge_folder_into(&from, &to, conflicts)?;
+            remove_if_empty(&from)?;
+        } else {
+            conflicts.push(from);
+        }
+    }
+
+    Ok(())
+}
+
+fn remove_if_empty(dir: &Path) -> Result<(), String> {
+    let is_empty = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .next()
+        .is_none();
+    if is_empty {
+        fs::remove_dir(dir).map_err(|e| format!("Failed to remove {}: {e}", dir.display()))?;
+    }
+    Ok(())
+}
+
+/// Move the row of a property whose folder is gone to the trash, where it
+/// can still be restored.
+async fn delete_orphan_row(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    property_id: i64,
+) -> Result<(), String> {
+    let folder_path: String = sqlx::query_scalar("SELECT folder_path FROM properties WHERE id = ?")
+        .bind(property_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Property {property_id} not found"))?;
+
+    let config = config.clone();
+    let found = tokio::task::spawn_blocking(move || {
+        find_actual_folder_location(&config, &folder_path).is_some()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
+    if found {
+        return Err("its folder exists again; not deleting".to_string());
+    }
+
+    trash_property(pool, property_id, None, None, "apply_orphan_actions").await
+}
+
+/// Check a path sent back in an `OrphanAction` before it is joined onto a
+/// status root: `components` plain segments (2 for `City/Folder`, 1 for a
+/// city), so it cannot reach outside the root.
+fn action_relative_path(path: &str, components: usize) -> Result<PathBuf, String> {
+    let relative =
+        validate_relative_folder_path(path).map_err(|e| format!("Invalid path '{path}': {e}"))?;
+    if relative.components().count() != components {
+        return Err(format!("Invalid path '{path}'"));
+    }
+    Ok(relative)
+}
+
+/// Point a row at `status`/`folder_path`. The name and code are taken from
+/// the folder name, the code through `apply_primary_code` so its
+/// `property_codes` rows follow.
+async fn relink_property(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    property_id: i64,
+    status: &str,
+    folder_path: &str,
+) -> Result<(), String> {
+    let relative = action_relative_path(folder_path, 2)?;
+    let Some((city, folder_name)) = folder_path.split_once('/') else {
+        return Err(format!("Invalid folder_path: {folder_path}"));
+    };
+    let target = get_base_path_for_status(config, status)?.join(relative);
+    let exists = tokio::task::spawn_blocking(move || target.is_dir())
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?;
+    if !exists {
+        return Err(format!("{status}/{folder_path} no longer exists"));
+    }
+
+    let taken: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM properties WHERE folder_path = ? AND id != ?")
+            .bind(folder_path)
+            .bind(property_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if taken > 0 {
+        return Err(format!("{folder_path} already belongs to another property"));
+    }
+
+    let (old_status, old_folder_path, old_city, old_name, old_code): (
+        String,
+        String,
+        String,
+        String,
+        Option<String>,
+    ) = sqlx::query_as("SELECT status, folder_path, city, name, code FROM properties WHERE id = ?")
+        .bind(property_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Property {property_id} not found"))?;
+    let (name, code) = parse_folder_name(folder_name);
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        "UPDATE properties SET status = ?, folder_path = ?, city = ?, name = ?, updated_at = ?
+         WHERE id = ?",
+    )
+    .bind(status)
+    .bind(folder_path)
+    .bind(city)
+    .bind(&name)
+    .bind(now)
+    .bind(property_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    record_property_event(
+        &mut tx,
+        property_id,
+        PropertyEventKind::RepairFix,
+        Some(&format!("{old_status}:{old_folder_path}")),
+        Some(&format!("{status}:{folder_path}")),
+        "apply_orphan_actions",
+    )
+    .await?;
+    if old_name != name {
+        record_property_event(
+            &mut tx,
+            property_id,
+            PropertyEventKind::Rename,
+            Some(&old_name),
+            Some(&name),
+            "apply_orphan_actions",
+        )
+        .await?;
+    }
+    if old_city != city {
+        record_property_event(
+            &mut tx,
+            property_id,
+            PropertyEventKind::CityChange,
+            Some(&old_city),
+            Some(city),
+            "apply_orphan_actions",
+        )
+        .await?;
+        sqlx::query(
+            "INSERT INTO cities (name, usage_count, created_at) VALUES (?, 1, ?)
+             ON CONFLICT(name) DO UPDATE SET usage_count = usage_count + 1",
+        )
+        .bind(city)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    match code {
+        Some(code) if !old_code.is_some_and(|old| old.eq_ignore_ascii_case(&code)) => {
+            apply_folder_code(pool, config, property_id, &code).await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Make `code`, taken from a relinked folder's name, the property's
+/// primary code.
+async fn apply_folder_code(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    property_id: i64,
+    code: &str,
+) -> Result<(), String> {
+    apply_primary_code(pool, config, property_id, code, "apply_orphan_actions")
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("relinked, but code {code} was not applied: {e}"))
+}
+
+async fn merge_duplicate_folders(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    folder_path: &str,
+    keep_status: &str,
+) -> Result<(), String> {
+    let relative = action_relative_path(folder_path, 2)?;
+    let target = get_base_path_for_status(config, keep_status)?.join(&relative);
+    let copies: Vec<PathBuf> = status_roots(config)
+        .into_iter()
+        .filter(|(status, _)| status != keep_status)
+        .map(|(_, root)| root.join(&relative))
+        .collect();
+
+    let conflicts = tokio::task::spawn_blocking(move || {
+        if !target.is_dir() {
+            return Err(format!("{} no longer exists", target.display()));
+        }
+        let mut conflicts = Vec::new();
+        for copy in copies.iter().filter(|copy| copy.is_dir()) {
+            merge_folder_into(copy, &target, &mut conflicts)?;
+            remove_if_empty(copy)?;
+        }
+        Ok(conflicts)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    // The kept copy is now the property's folder, whatever was left behind.
+    let row: Option<(i64, String)> = sqlx::query_as(
+        "SELECT id, status FROM properties WHERE folder_path = ? AND deleted_at IS NULL",
+    )
+    .bind(folder_path)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Some((property_id, old_status)) = row.filter(|(_, status)| status != keep_status) {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        sqlx::query("UPDATE properties SET status = ?, updated_at = ? WHERE id = ?")
+            .bind(keep_status)
+            .bind(chrono::Utc::now().timestamp_millis())
+            .bind(property_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        record_property_event(
+            &mut tx,
+            property_id,
+            PropertyEventKind::StatusChange,
+            Some(&old_status),
+            Some(keep_status),
+            "apply_orphan_actions",
+        )
+        .await?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+    }
+
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        let names: Vec<String> = conflicts
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        Err(format!(
+            "{} entries exist in both copies and were left in place: {}",
+            conflicts.len(),
+            names.join(", ")
+        ))
+    }
+}
+
+async fn remove_city_folder(config: &AppConfig, status: &str, city: &str) -> Result<(), String> {
+    let city_path = get_base_path_for_status(config, status)?.join(action_relative_path(city, 1)?);
+    tokio::task::spawn_blocking(move || {
+        let mut entries = fs::read_dir(&city_path).map_err(|e| e.to_string())?;
+        if entries.next().is_some() {
+            return Err("the city folder is no longer empty".to_string());
+        }
+        fs::remove_dir(&city_path).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?
+}
+
+async fn apply_orphan_action(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    action: &OrphanAction,
+) -> Result<(), String> {
+    match action {
+        OrphanAction::DeleteRow { property_id } => {
+            delete_orphan_row(pool, config, *property_id).await
+        }
+        OrphanAction::Relink {
+            property_id,
+            status,
+            folder_path,
+        } => relink_property(pool, config, *property_id, status, folder_path).await,
+        OrphanAction::Merge {
+            folder_path,
+            keep_status,
+        } => merge_duplicate_folders(pool, config, folder_path, keep_status).await,
+        OrphanAction::RemoveCityFolder { status, city } => {
+            remove_city_folder(config, status, city).await
+        }
+    }
+}
+
+fn describe_action(action: &OrphanAction) -> String {
+    match action {
+        OrphanAction::DeleteRow { property_id } => {
+            format!("Move property {property_id} to the trash")
+        }
+        OrphanAction::Relink {
+            property_id,
+            status,
+            folder_path,
+        } => format!("Relink property {property_id} to {status}/{folder_path}"),
+        OrphanAction::Merge {
+            folder_path,
+            keep_status,
+        } => format!("Merge {folder_path} into {keep_status}"),
+        OrphanAction::RemoveCityFolder { status, city } => {
+            format!("Remove city folder {status}/{city}")
+        }
+    }
+}
+
+/// Report rows without folders, folders in several status folders and empty
+/// city folders, each with suggested fixes. Read-only.
+#[tauri::command]
+pub async fn find_orphans(app: tauri::AppHandle) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    let config = crate::config::get_cached_config(&app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    let rows: Vec<(i64, String, String, String)> = sqlx::query_as(
+        "SELECT id, name, status, folder_path FROM properties WHERE deleted_at IS NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch properties: {e}"))?;
+    let tracked = get_existing_properties_set(pool).await?;
+
+    let roots = status_roots(&config);
+    let listing = tokio::task::spawn_blocking(move || list_status_roots(&roots))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))?;
+
+    let report = OrphanReport {
+        properties_checked: rows.len(),
+        folders_checked: listing.folders.values().map(Vec::len).sum(),
+        findings: build_findings(&rows, &tracked, &listing),
+        errors: listing.errors,
+    };
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(report).map_err(|e| e.to_string())?),
+    })
+}
+
+/// Apply actions picked from a `find_orphans` report, in order. Each one is
+/// re-checked against the disk first; failures are reported and skipped.
+#[tauri::command]
+pub async fn apply_orphan_actions(
+    app: tauri::AppHandle,
+    actions: Vec<OrphanAction>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    let config = crate::config::get_cached_config(&app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    let mut result = OrphanActionResult {
+        applied: 0,
+        errors: Vec::new(),
+    };
+    for action in &actions {
+        match apply_orphan_action(pool, &config, action).await {
+            Ok(()) => result.applied += 1,
+            Err(e) => result
+                .errors
+                .push(format!("{}: {e}", describe_action(action))),
+        }
+    }
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(result).map_err(|e| e.to_string())?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::codes::load_property_codes;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+
+    fn test_config(root: &Path) -> AppConfig {
+        AppConfig {
+            new_folder_path: root.join("NEW").to_string_lossy().to_string(),
+            done_folder_path: root.join("DONE").to_string_lossy().to_string(),
+            not_found_folder_path: root.join("NOT_FOUND").to_string_lossy().to_string(),
+            archive_folder_path: root.join("ARCHIVE").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn findings_cover_missing_duplicate_and_empty_city() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("NEW/Athens/Villa")).unwrap();
+        fs::create_dir_all(tmp.path().join("DONE/Athens/Villa")).unwrap();
+        fs::create_dir_all(tmp.path().join("DONE/Rome/Loft (77)")).unwrap();
+        // A copy in progress is not offered for relinking.
+        fs::create_dir_all(tmp.path().join("DONE/Rome/.Loft (78).moving")).unwrap();
+        fs::create_dir_all(tmp.path().join("ARCHIVE/Sparta")).unwrap();
+        // Loose files make a city folder non-empty.
+        fs::create_dir_all(tmp.path().join("ARCHIVE/Corinth")).unwrap();
+        fs::write(tmp.path().join("ARCHIVE/Corinth/notes.txt"), b"x").unwrap();
+        fs::create_dir_all(tmp.path().join("NEW/Athens/Coded (12)")).unwrap();
+
+        let rows = vec![
+            (
+                1,
+                "Villa".to_string(),
+                "DONE".to_string(),
+                "Athens/Villa".to_string(),
+            ),
+            (
+                2,
+                "Loft".to_string(),
+                "NEW".to_string(),
+                "Athens/Loft".to_string(),
+            ),
+            (
+                3,
+                "Coded".to_string(),
+                "NEW".to_string(),
+                "Athens/Coded".to_string(),
+            ),
+        ];
+        let tracked: HashSet<String> = rows.iter().map(|r| r.3.clone()).collect();
+        let listing = list_status_roots(&status_roots(&test_config(tmp.path())));
+        let findings = build_findings(&rows, &tracked, &listing);
+
+        // Row 3 only gained a code suffix, which repair handles.
+        assert_eq!(findings.len(), 3);
+
+        let missing = &findings[0];
+        assert_eq!(missing.kind, OrphanKind::MissingFolder);
+        assert_eq!(missing.property_id, Some(2));
+        assert_eq!(
+            missing.suggested_actions,
+            vec![
+                OrphanAction::Relink {
+                    property_id: 2,
+                    status: "DONE".to_string(),
+                    folder_path: "Rome/Loft (77)".to_string(),
+                },
+                OrphanAction::DeleteRow { property_id: 2 },
+            ]
+        );
+
+        let duplicate = &findings[1];
+        assert_eq!(duplicate.kind, OrphanKind::DuplicateFolder);
+        assert_eq!(duplicate.statuses, vec!["NEW", "DONE"]);
+        assert_eq!(
+            duplicate.suggested_actions[0],
+            OrphanAction::Merge {
+                folder_path: "Athens/Villa".to_string(),
+                keep_status: "DONE".to_string(),
+            }
+        );
+
+        assert_eq!(findings[2].kind, OrphanKind::EmptyCity);
+        assert_eq!(findings[2].folder_path, "Sparta");
+    }
+
+    #[tokio::test]
+    async fn merge_moves_contents_and_keeps_conflicts() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = test_config(tmp.path());
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa", None)
+            .await
+            .unwrap();
+
+        let kept = tmp.path().join("DONE/Athens/Villa");
+        let stray = tmp.path().join("NEW/Athens/Villa");
+        fs::create_dir_all(kept.join("INTERNET")).unwrap();
+        fs::create_dir_all(stray.join("INTERNET")).unwrap();
+        fs::write(kept.join("cover.jpg"), b"kept").unwrap();
+        fs::write(stray.join("cover.jpg"), b"stray").unwrap();
+        fs::write(stray.join("INTERNET/1.jpg"), b"one").unwrap();
+
+        let action = OrphanAction::Merge {
+            folder_path: "Athens/Villa".to_string(),
+            keep_status: "DONE".to_string(),
+        };
+        let err = apply_orphan_action(&pool, &config, &action)
+            .await
+            .unwrap_err();
+        assert!(err.contains("1 entries"));
+
+        assert_eq!(fs::read(kept.join("INTERNET/1.jpg")).unwrap(), b"one");
+        assert_eq!(fs::read(kept.join("cover.jpg")).unwrap(), b"kept");
+        assert!(stray.join("cover.jpg").exists());
+        assert!(!stray.join("INTERNET").exists());
+
+        let status: String = sqlx::query_scalar("SELECT status FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, "DONE");
+    }
+
+    #[tokio::test]
+    async fn relink_and_delete_recheck_the_disk() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = test_config(tmp.path());
+        add_property_to_database(&pool, "Loft", "Athens", "NEW", "Loft", None)
+            .await
+            .unwrap();
+        let id: i64 = sqlx::query_scalar("SELECT id FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let relink = OrphanAction::Relink {
+            property_id: id,
+            status: "DONE".to_string(),
+            folder_path: "Rome/Loft (77)".to_string(),
+        };
+        assert!(apply_orphan_action(&pool, &config, &relink).await.is_err());
+
+        fs::create_dir_all(tmp.path().join("DONE/Rome/Loft (77)")).unwrap();
+        // The folder exists now, so the row must not be deleted.
+        let delete = OrphanAction::DeleteRow { property_id: id };
+        apply_orphan_action(&pool, &config, &relink).await.unwrap();
+        assert!(apply_orphan_action(&pool, &config, &delete).await.is_err());
+
+        let row: (String, String, String, String, Option<String>) = sqlx::query_as(
+            "SELECT status, folder_path, city, name, code FROM properties WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            row,
+            (
+                "DONE".to_string(),
+                "Rome/Loft (77)".to_string(),
+                "Rome".to_string(),
+                "Loft".to_string(),
+                Some("77".to_string())
+            )
+        );
+        let codes = load_property_codes(&pool, id).await.unwrap();
+        assert_eq!(codes.len(), 1);
+        assert_eq!(codes[0].code, "77");
+
+        fs::remove_dir(tmp.path().join("DONE/Rome/Loft (77)")).unwrap();
+        apply_orphan_action(&pool, &config, &delete).await.unwrap();
+        // The row goes to the trash rather than away.
+        let deleted_at: Option<i64> =
+            sqlx::query_scalar("SELECT deleted_at FROM properties WHERE id = ?")
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(deleted_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn actions_cannot_reach_outside_the_status_roots() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = test_config(tmp.path());
+        // "NEW/.." is the parent of every status folder.
+        fs::create_dir_all(tmp.path().join("NEW")).unwrap();
+        fs::create_dir_all(tmp.path().join("DONE/Athens/Villa")).unwrap();
+        add_property_to_database(&pool, "Loft", "Athens", "NEW", "Loft", None)
+            .await
+            .unwrap();
+
+        let actions = [
+            OrphanAction::Relink {
+                property_id: 1,
+                status: "NEW".to_string(),
+                folder_path: "../DONE".to_string(),
+            },
+            OrphanAction::Merge {
+                folder_path: "../DONE".to_string(),
+                keep_status: "NEW".to_string(),
+            },
+            OrphanAction::RemoveCityFolder {
+                status: "NEW".to_string(),
+                city: "..".to_string(),
+            },
+            OrphanAction::RemoveCityFolder {
+                status: "DONE".to_string(),
+                city: "Athens/Villa".to_string(),
+            },
+        ];
+        for action in &actions {
+            let error = apply_orphan_action(&pool, &config, action)
+                .await
+                .unwrap_err();
+            assert!(error.starts_with("Invalid path"), "{error}");
+        }
+        assert!(tmp.path().join("DONE/Athens/Villa").is_dir());
+        let folder_path: String = sqlx::query_scalar("SELECT folder_path FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(folder_path, "Athens/Loft");
+    }
+}
@@ -2,16 +2,55 @@
 //! its `folder_path` + `status` row matches what is actually on disk,
 //! correcting any drift. Used after manual filesystem changes.
 //!
+//! With `dry_run` the command only reports a `RepairDiff` per affected
+//! property; the user can then apply all fixes or pass the selected
+//! property ids. Fixes are always recomputed against the disk at apply
+//! time, so a stale preview never writes outdated paths.
+//!
 //! Extracted from database.rs in the database-module split.
 
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
 use ts_rs::TS;
 
+use crate::config::AppConfig;
 use crate::database::scan::find_folder_by_prefix;
 use crate::database::types::CommandResult;
-use crate::database::{folder_path_to_pathbuf, get_base_path_for_status, get_database_pool};
+use crate::database::{
+    folder_path_to_pathbuf, get_base_path_for_status, get_database_pool, record_property_event,
+    PropertyEventKind,
+};
+
+/// Why a property's row disagrees with what is on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub enum RepairReason {
+    /// The folder is in a different status folder than `status` says.
+    Moved,
+    /// The folder on disk gained a " (code)" suffix the row does not have.
+    CodeSuffixAdded,
+    /// The folder was not found in any status folder. Not fixable here.
+    Missing,
+}
+
+/// One property whose row does not match the disk.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct RepairDiff {
+    #[ts(type = "number")]
+    pub property_id: i64,
+    pub name: String,
+    pub current_status: String,
+    pub current_folder_path: String,
+    /// Where the folder was found; `None` when it is missing.
+    pub disk_status: Option<String>,
+    pub disk_folder_path: Option<String>,
+    pub reasons: Vec<RepairReason>,
+}
 
 /// Repair result structure
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -22,211 +61,268 @@ pub struct RepairResult {
     pub properties_checked: usize,
     #[ts(type = "number")]
     pub properties_fixed: usize,
+    pub dry_run: bool,
+    /// In a dry run every drift found; otherwise the fixes that were
+    /// attempted plus the missing properties.
+    pub diffs: Vec<RepairDiff>,
     pub errors: Vec<String>,
 }
 
+/// Compare every property row with the disk. Blocking; call from
+/// `spawn_blocking`. Returns the diffs plus warnings for rows that could not
+/// be checked at all.
+fn find_repair_diffs(
+    properties: Vec<(i64, String, String, String)>,
+    status_paths: &[(String, Option<PathBuf>)],
+) -> (Vec<RepairDiff>, Vec<String>) {
+    let mut diffs = Vec::new();
+    let mut errors = Vec::new();
+
+    for (id, folder_path, db_status, name) in properties {
+        let parts: Vec<&str> = folder_path.split('/').collect();
+        if parts.len() != 2 {
+            errors.push("Property has invalid folder_path format".to_string());
+            continue;
+        }
+        let city = parts[0];
+        let property_folder_name = parts[1];
+        let folder_path_buf = folder_path_to_pathbuf(&folder_path);
+
+        // Try exact match
+        let mut found_info: Option<(String, Option<String>)> = status_paths
+            .iter()
+            .find(|(_, base_path_opt)| {
+                base_path_opt
+                    .as_ref()
+                    .is_some_and(|base_path| base_path.join(&folder_path_buf).exists())
+            })
+            .map(|(status, _)| (status.clone(), None));
+
+        // Try prefix matching
+        if found_info.is_none() {
+            for (status, base_path_opt) in status_paths {
+                if let Some(base_path) = base_path_opt {
+                    let city_path = base_path.join(city);
+                    if let Some(actual_folder_name) =
+                        find_folder_by_prefix(&city_path, property_folder_name)
+                    {
+                        let renamed = (actual_folder_name != property_folder_name)
+                            .then_some(actual_folder_name);
+                        found_info = Some((status.clone(), renamed));
+                        break;
+                    }
+                }
+            }
+        }
+
+        let Some((found_status, new_folder_name)) = found_info else {
+            let checked_paths: Vec<String> = status_paths
+                .iter()
+                .filter_map(|(status, base_path_opt)| {
+                    base_path_opt
+                        .as_ref()
+                        .map(|bp| format!("{}: {}", status, bp.join(&folder_path_buf).display()))
+                })
+                .collect();
+            errors.push(format!(
+                "Property '{}' folder not found. DB folder_path='{}'. Checked: [{}]",
+                name,
+                folder_path,
+                checked_paths.join(", ")
+            ));
+            diffs.push(RepairDiff {
+                property_id: id,
+                name,
+                current_status: db_status,
+                current_folder_path: folder_path,
+                disk_status: None,
+                disk_folder_path: None,
+                reasons: vec![RepairReason::Missing],
+            });
+            continue;
+        };
+
+        let mut reasons = Vec::new();
+        if found_status != db_status {
+            reasons.push(RepairReason::Moved);
+        }
+        if new_folder_name.is_some() {
+            reasons.push(RepairReason::CodeSuffixAdded);
+        }
+        if reasons.is_empty() {
+            continue;
+        }
+
+        let disk_folder_path = new_folder_name.map_or_else(
+            || folder_path.clone(),
+            |new_name| format!("{city}/{new_name}"),
+        );
+        diffs.push(RepairDiff {
+            property_id: id,
+            name,
+            current_status: db_status,
+            current_folder_path: folder_path,
+            disk_status: Some(found_status),
+            disk_folder_path: Some(disk_folder_path),
+            reasons,
+        });
+    }
+
+    (diffs, errors)
+}
+
+/// Check every live property against the disk, or with `cities` only the
+/// properties in those city folders. Returns how many were checked, the
+/// diffs, and warnings for rows that could not be checked.
+pub(super) async fn load_repair_diffs(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    cities: Option<&[String]>,
+) -> Result<(usize, Vec<RepairDiff>, Vec<String>), String> {
+    // Get all properties from database
+    let mut properties: Vec<(i64, String, String, String)> = sqlx::query_as(
+        "SELECT id, folder_path, status, name FROM properties WHERE deleted_at IS NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch properties: {}", e))?;
+    if let Some(cities) = cities {
+        properties.retain(|(_, folder_path, _, _)| {
+            folder_path
+                .split_once('/')
+                .is_some_and(|(city, _)| cities.iter().any(|c| c == city))
+        });
+    }
+    let checked = properties.len();
+
+    // Get base paths for all statuses
+    let status_paths: Vec<(String, Option<PathBuf>)> = ["NEW", "DONE", "NOT_FOUND", "ARCHIVE"]
+        .into_iter()
+        .map(|status| {
+            (
+                status.to_string(),
+                get_base_path_for_status(config, status).ok(),
+            )
+        })
+        .collect();
+
+    // Check filesystem locations on a blocking thread.
+    let (diffs, errors) =
+        tokio::task::spawn_blocking(move || find_repair_diffs(properties, &status_paths))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))?;
+    Ok((checked, diffs, errors))
+}
+
+/// Write one fix and its audit event in a single transaction. `source` is
+/// the command recorded on the event.
+pub(super) async fn apply_repair_diff(
+    pool: &SqlitePool,
+    diff: &RepairDiff,
+    source: &str,
+) -> Result<(), String> {
+    let (Some(disk_status), Some(disk_folder_path)) = (&diff.disk_status, &diff.disk_folder_path)
+    else {
+        return Err("folder not found on disk".to_string());
+    };
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("UPDATE properties SET status = ?, folder_path = ?, updated_at = ? WHERE id = ?")
+        .bind(disk_status)
+        .bind(disk_folder_path)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(diff.property_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    record_property_event(
+        &mut tx,
+        diff.property_id,
+        PropertyEventKind::RepairFix,
+        Some(&format!(
+            "{}:{}",
+            diff.current_status, diff.current_folder_path
+        )),
+        Some(&format!("{disk_status}:{disk_folder_path}")),
+        source,
+    )
+    .await?;
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
 /// Repair property statuses by checking actual folder locations
 /// This fixes properties where the database status doesn't match where the folder actually exists
 /// Also handles folder name mismatches (e.g., when folder has code suffix but DB doesn't)
+///
+/// `dry_run` only reports the diffs. `property_ids` limits the fixes to
+/// those properties; without it every fixable diff is applied.
 #[tauri::command]
-pub async fn repair_property_statuses(app: tauri::AppHandle) -> Result<CommandResult, String> {
+pub async fn repair_property_statuses(
+    app: tauri::AppHandle,
+    dry_run: Option<bool>,
+    property_ids: Option<Vec<i64>>,
+) -> Result<CommandResult, String> {
     let pool = get_database_pool(&app)?;
+    let dry_run = dry_run.unwrap_or(false);
 
     let config = crate::config::get_cached_config(&app)
         .await
         .map_err(|e| e.to_string())?
         .ok_or("App configuration not found")?;
 
+    // Phase 1: Check filesystem locations.
+    let (properties_checked, diffs, errors) = load_repair_diffs(pool, &config, None).await?;
     let mut result = RepairResult {
-        properties_checked: 0,
+        properties_checked,
         properties_fixed: 0,
-        errors: Vec::new(),
+        dry_run,
+        diffs: Vec::new(),
+        errors,
     };
 
-    // Get all properties from database
-    let properties: Vec<(i64, String, String, String)> =
-        sqlx::query_as("SELECT id, folder_path, status, name FROM properties")
-            .fetch_all(pool)
-            .await
-            .map_err(|e| format!("Failed to fetch properties: {}", e))?;
-
-    // Get base paths for all statuses
-    let status_paths: Vec<(String, Option<PathBuf>)> = vec![
-        (
-            "NEW".to_string(),
-            get_base_path_for_status(&config, "NEW").ok(),
-        ),
-        (
-            "DONE".to_string(),
-            get_base_path_for_status(&config, "DONE").ok(),
-        ),
-        (
-            "NOT_FOUND".to_string(),
-            get_base_path_for_status(&config, "NOT_FOUND").ok(),
-        ),
-        (
-            "ARCHIVE".to_string(),
-            get_base_path_for_status(&config, "ARCHIVE").ok(),
-        ),
-    ];
-
-    // Phase 1: Check filesystem locations on a blocking thread.
-    // Each row: (id, folder_path, db_status, name, found_full_path,
-    // found_status, errors). Hoisted to a type alias to satisfy the
-    // clippy::type_complexity lint.
-    type ScanRow = (
-        i64,
-        String,
-        String,
-        String,
-        Option<String>,
-        Option<String>,
-        Vec<String>,
-    );
-    let properties_clone = properties.clone();
-    let status_paths_clone = status_paths.clone();
-    let scan_results: Vec<ScanRow> = tokio::task::spawn_blocking(move || {
-        let mut results = Vec::new();
-
-        for (id, folder_path, db_status, name) in properties_clone {
-            let parts: Vec<&str> = folder_path.split('/').collect();
-            if parts.len() != 2 {
-                results.push((
-                    id,
-                    folder_path,
-                    db_status,
-                    name,
-                    None,
-                    None,
-                    vec![format!("Property has invalid folder_path format")],
-                ));
-                continue;
-            }
-            let city = parts[0];
-            let property_folder_name = parts[1];
-            let folder_path_buf = folder_path_to_pathbuf(&folder_path);
-
-            let mut found_info: Option<(String, Option<String>)> = None;
-
-            // Try exact match
-            for (status, base_path_opt) in &status_paths_clone {
-                if let Some(base_path) = base_path_opt {
-                    let full_path = base_path.join(&folder_path_buf);
-                    if full_path.exists() {
-                        found_info = Some((status.clone(), None));
-                        break;
-                    }
-                }
-            }
-
-            // Try prefix matching
-            if found_info.is_none() {
-                for (status, base_path_opt) in &status_paths_clone {
-                    if let Some(base_path) = base_path_opt {
-                        let city_path = base_path.join(city);
-                        if let Some(actual_folder_name) =
-                            find_folder_by_prefix(&city_path, property_folder_name)
-                        {
-                            if actual_folder_name != property_folder_name {
-                                found_info = Some((status.clone(), Some(actual_folder_name)));
-                            } else {
-                                found_info = Some((status.clone(), None));
-                            }
-                            break;
-                        }
-                    }
-                }
-            }
+    if dry_run {
+        result.diffs = diffs;
+        return Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        });
+    }
 
-            let (found_status, new_folder_name) = match found_info {
-                Some((s, n)) => (Some(s), n),
-                None => (None, None),
-            };
-
-            let errors = if found_status.is_none() {
-                let checked_paths: Vec<String> = status_paths_clone
-                    .iter()
-                    .filter_map(|(status, base_path_opt)| {
-                        base_path_opt.as_ref().map(|bp| {
-                            format!("{}: {}", status, bp.join(&folder_path_buf).display())
-                        })
-                    })
-                    .collect();
-                vec![format!(
-                    "Property '{}' folder not found. DB folder_path='{}'. Checked: [{}]",
-                    name,
-                    folder_path,
-                    checked_paths.join(", ")
-                )]
-            } else {
-                vec![]
-            };
-
-            results.push((
-                id,
-                folder_path,
-                db_status,
-                name,
-                found_status,
-                new_folder_name,
-                errors,
-            ));
+    // Phase 2: Update database based on scan results
+    let selected = |diff: &RepairDiff| {
+        property_ids
+            .as_ref()
+            .map_or(true, |ids| ids.contains(&diff.property_id))
+    };
+    for diff in diffs.into_iter().filter(selected) {
+        if diff.reasons.contains(&RepairReason::Missing) {
+            result.diffs.push(diff);
+            continue;
         }
 
-        results
-    })
-    .await
-    .map_err(|e| format!("Task join error: {e}"))?;
-
-    // Phase 2: Update database based on scan results
-    for (id, folder_path, db_status, name, found_status, new_folder_name_opt, errors) in
-        scan_results
-    {
-        result.properties_checked += 1;
-        result.errors.extend(errors);
-
-        if let Some(found_status) = found_status {
-            let status_changed = found_status != db_status;
-            let folder_path_changed = new_folder_name_opt.is_some();
-
-            if status_changed || folder_path_changed {
-                let parts: Vec<&str> = folder_path.split('/').collect();
-                let city = parts[0];
-                let now_ts = chrono::Utc::now().timestamp_millis();
-                let new_folder_path = if let Some(ref new_name) = new_folder_name_opt {
-                    format!("{}/{}", city, new_name)
-                } else {
-                    folder_path.clone()
-                };
-
-                match sqlx::query("UPDATE properties SET status = ?, folder_path = ?, updated_at = ? WHERE id = ?")
-                    .bind(&found_status)
-                    .bind(&new_folder_path)
-                    .bind(now_ts)
-                    .bind(id)
-                    .execute(pool)
-                    .await
-                {
-                    Ok(_) => {
-                        result.properties_fixed += 1;
-                        if folder_path_changed {
-                            if let Some(new_name) = new_folder_name_opt {
-                                result.errors.push(format!(
-                                    "Fixed '{}': folder_path updated from '{}' to '{}/{}'",
-                                    name, folder_path, city, new_name
-                                ));
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        result.errors.push(format!(
-                            "Failed to update status for '{}': {}",
-                            name, e
-                        ));
-                    }
+        match apply_repair_diff(pool, &diff, "repair_property_statuses").await {
+            Ok(()) => {
+                result.properties_fixed += 1;
+                if diff.reasons.contains(&RepairReason::CodeSuffixAdded) {
+                    result.errors.push(format!(
+                        "Fixed '{}': folder_path updated from '{}' to '{}'",
+                        diff.name,
+                        diff.current_folder_path,
+                        diff.disk_folder_path.as_deref().unwrap_or_default()
+                    ));
                 }
             }
+            Err(e) => {
+                result.errors.push(format!(
+                    "Failed to update status for '{}': {}",
+                    diff.name, e
+                ));
+            }
         }
+        result.diffs.push(diff);
     }
 
     Ok(CommandResult {
@@ -235,3 +331,121 @@ pub async fn repair_property_statuses(app: tauri::AppHandle) -> Result<CommandRe
         data: Some(serde_json::to_value(result).map_err(|e| e.to_string())?),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+    use std::fs;
+
+    fn status_paths(root: &std::path::Path) -> Vec<(String, Option<PathBuf>)> {
+        ["NEW", "DONE", "NOT_FOUND", "ARCHIVE"]
+            .into_iter()
+            .map(|status| (status.to_string(), Some(root.join(status))))
+            .collect()
+    }
+
+    #[test]
+    fn diffs_report_moved_suffixed_and_missing_folders() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("NEW/Athens/In Place")).unwrap();
+        fs::create_dir_all(tmp.path().join("DONE/Athens/Moved")).unwrap();
+        fs::create_dir_all(tmp.path().join("ARCHIVE/Athens/Coded (123)")).unwrap();
+
+        let properties = vec![
+            (
+                1,
+                "Athens/In Place".to_string(),
+                "NEW".to_string(),
+                "In Place".to_string(),
+            ),
+            (
+                2,
+                "Athens/Moved".to_string(),
+                "NEW".to_string(),
+                "Moved".to_string(),
+            ),
+            (
+                3,
+                "Athens/Coded".to_string(),
+                "ARCHIVE".to_string(),
+                "Coded".to_string(),
+            ),
+            (
+                4,
+                "Athens/Gone".to_string(),
+                "NEW".to_string(),
+                "Gone".to_string(),
+            ),
+        ];
+        let (diffs, errors) = find_repair_diffs(properties, &status_paths(tmp.path()));
+
+        assert_eq!(diffs.len(), 3);
+        assert_eq!(diffs[0].property_id, 2);
+        assert_eq!(diffs[0].reasons, vec![RepairReason::Moved]);
+        assert_eq!(diffs[0].disk_status.as_deref(), Some("DONE"));
+        assert_eq!(diffs[1].property_id, 3);
+        assert_eq!(diffs[1].reasons, vec![RepairReason::CodeSuffixAdded]);
+        assert_eq!(
+            diffs[1].disk_folder_path.as_deref(),
+            Some("Athens/Coded (123)")
+        );
+        assert_eq!(diffs[2].property_id, 4);
+        assert_eq!(diffs[2].reasons, vec![RepairReason::Missing]);
+        assert!(diffs[2].disk_status.is_none());
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn applying_a_diff_updates_row_and_audit_log() {
+        let pool = setup_test_db().await;
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa", None)
+            .await
+            .unwrap();
+        let id: i64 = sqlx::query_scalar("SELECT id FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let diff = RepairDiff {
+            property_id: id,
+            name: "Villa".to_string(),
+            current_status: "NEW".to_string(),
+            current_folder_path: "Athens/Villa".to_string(),
+            disk_status: Some("DONE".to_string()),
+            disk_folder_path: Some("Athens/Villa (42)".to_string()),
+            reasons: vec![RepairReason::Moved, RepairReason::CodeSuffixAdded],
+        };
+
+        apply_repair_diff(&pool, &diff, "repair_property_statuses")
+            .await
+            .unwrap();
+
+        let (status, folder_path): (String, String) =
+            sqlx::query_as("SELECT status, folder_path FROM properties WHERE id = ?")
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "DONE");
+        assert_eq!(folder_path, "Athens/Villa (42)");
+        let new_value: String = sqlx::query_scalar(
+            "SELECT new_value FROM property_events WHERE event_type = 'repair_fix'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(new_value, "DONE:Athens/Villa (42)");
+
+        let missing = RepairDiff {
+            disk_status: None,
+            disk_folder_path: None,
+            reasons: vec![RepairReason::Missing],
+            ..diff
+        };
+        assert!(
+            apply_repair_diff(&pool, &missing, "repair_property_statuses")
+                .await
+                .is_err()
+        );
+    }
+}
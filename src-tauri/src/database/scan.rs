@@ -3,22 +3,40 @@
 //! tracked in the database. Owns the find_folder_by_prefix helper that
 //! repair_property_statuses also relies on.
 //!
+//! Scans are incremental: the modification time of each city folder read
+//! in full is kept in `scan_state`, and a city whose mtime has not changed
+//! since is skipped, since adding, removing or renaming a property folder
+//! changes it. The four roots are read in parallel.
+//!
 //! Extracted from database.rs in the database-module split.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, UNIX_EPOCH};
 
 use sqlx::{Row, SqlitePool};
 
-use crate::database::types::{CommandResult, ScanResult};
+use crate::config::AppConfig;
+use crate::database::types::{CommandResult, RootScanTiming, ScanResult};
 use crate::database::{
     get_database_pool, get_relative_folder_path, is_valid_property_folder, parse_folder_name,
 };
 
+/// Only cache a city mtime at least this much older than the scan. Network
+/// shares may keep mtimes to the second or coarser, so a folder added just
+/// after the city was read could otherwise leave its mtime unchanged.
+const MTIME_SETTLE_MS: i64 = 2_000;
+
 // Scan and import properties function
+//
+// `full` ignores the cached city mtimes and reads every city folder.
 #[tauri::command]
-pub async fn scan_and_import_properties(app: tauri::AppHandle) -> Result<CommandResult, String> {
+pub async fn scan_and_import_properties(
+    app: tauri::AppHandle,
+    full: Option<bool>,
+) -> Result<CommandResult, String> {
     let pool = get_database_pool(&app)?;
 
     let config_result = crate::config::get_cached_config(&app).await;
@@ -42,23 +60,33 @@ pub async fn scan_and_import_properties(app: tauri::AppHandle) -> Result<Command
         }
     };
 
-    let mut scan_result = ScanResult {
-        found_properties: 0,
-        new_properties: 0,
-        existing_properties: 0,
-        errors: Vec::new(),
-    };
+    match scan_roots(pool, &config, full.unwrap_or(false)).await {
+        Ok(scan_result) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::to_value(scan_result).map_err(|e| e.to_string())?),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
 
-    let existing_properties = match get_existing_properties_set(pool).await {
-        Ok(props) => props,
-        Err(e) => {
-            return Ok(CommandResult {
-                success: false,
-                error: Some(e),
-                data: None,
-            });
-        }
-    };
+/// Scan all four status folders and import what is new. Unless `full`,
+/// cities unchanged since the last scan are skipped.
+pub(super) async fn scan_roots(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    full: bool,
+) -> Result<ScanResult, String> {
+    let existing = Arc::new(get_existing_properties_set(pool).await?);
+    let cached = Arc::new(if full {
+        HashMap::new()
+    } else {
+        load_city_mtimes(pool).await?
+    });
 
     // Scan all 4 status folders
     let folders_to_scan = [
@@ -68,6 +96,9 @@ pub async fn scan_and_import_properties(app: tauri::AppHandle) -> Result<Command
         (&config.archive_folder_path, "ARCHIVE"),
     ];
 
+    // Read every root at once: on a NAS the listings mostly wait on the
+    // network, so they overlap well. Imports then run one root at a time.
+    let mut walks = Vec::new();
     for (folder_path_str, status) in folders_to_scan {
         if folder_path_str.is_empty() {
             continue; // Skip if folder path not configured
@@ -79,12 +110,38 @@ pub async fn scan_and_import_properties(app: tauri::AppHandle) -> Result<Command
             continue; // Skip if folder doesn't exist
         }
 
-        match scan_folder_for_properties(&folder_path, status, &existing_properties, pool).await {
-            Ok(folder_result) => {
-                scan_result.found_properties += folder_result.found_properties;
-                scan_result.new_properties += folder_result.new_properties;
-                scan_result.existing_properties += folder_result.existing_properties;
-                scan_result.errors.extend(folder_result.errors);
+        let existing = Arc::clone(&existing);
+        let cached = Arc::clone(&cached);
+        let walk = tokio::task::spawn_blocking(move || {
+            let started = Instant::now();
+            let walk = walk_root(&folder_path, status, &existing, &cached);
+            (walk, started.elapsed())
+        });
+        walks.push((status, walk));
+    }
+
+    let mut scan_result = ScanResult::default();
+    let mut mtimes = Vec::new();
+    for (status, walk) in walks {
+        let (walk, walk_time) = walk.await.map_err(|e| format!("Task join error: {e}"))?;
+        let import_started = Instant::now();
+        let mut timing = RootScanTiming {
+            status: status.to_string(),
+            duration_ms: 0,
+            cities_scanned: 0,
+            cities_skipped: 0,
+        };
+
+        match walk {
+            Ok(walk) => {
+                timing.cities_scanned = walk.cities_scanned;
+                timing.cities_skipped = walk.cities_skipped;
+                match import_walk(pool, walk, &mut scan_result).await {
+                    Ok(settled) => mtimes.extend(settled),
+                    Err(e) => scan_result
+                        .errors
+                        .push(format!("Error scanning {} folder: {}", status, e)),
+                }
             }
             Err(e) => {
                 scan_result
@@ -92,13 +149,17 @@ pub async fn scan_and_import_properties(app: tauri::AppHandle) -> Result<Command
                     .push(format!("Error scanning {} folder: {}", status, e));
             }
         }
+
+        timing.duration_ms = duration_millis(walk_time + import_started.elapsed());
+        scan_result.root_timings.push(timing);
     }
 
-    Ok(CommandResult {
-        success: true,
-        error: None,
-        data: Some(serde_json::to_value(scan_result).map_err(|e| e.to_string())?),
-    })
+    save_city_mtimes(pool, &mtimes).await?;
+    Ok(scan_result)
+}
+
+fn duration_millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
 }
 
 /// Helper function to find a folder by prefix match within a city directory
@@ -142,197 +203,343 @@ pub(super) async fn get_existing_properties_set(
     Ok(existing)
 }
 
-pub(super) async fn scan_folder_for_properties(
-    folder_path: &Path,
+/// A property folder that is not in the database yet.
+struct NewProperty {
+    name: String,
+    city: String,
+    status: String,
+    folder_name: String,
+    code: Option<String>,
+    /// `city/folder`, the `folder_path` it is stored with.
+    key: String,
+    /// Absolute path of its city folder, the `scan_state` key.
+    city_path: String,
+}
+
+/// What reading one status root found.
+#[derive(Default)]
+struct RootWalk {
+    new_properties: Vec<NewProperty>,
+    found: usize,
+    existing: usize,
+    errors: Vec<String>,
+    cities_scanned: usize,
+    cities_skipped: usize,
+    /// `(city path, mtime)` of the cities read without errors.
+    city_mtimes: Vec<(String, i64)>,
+}
+
+fn modified_millis(metadata: &fs::Metadata) -> Option<i64> {
+    let modified = metadata.modified().ok()?;
+    let millis = modified.duration_since(UNIX_EPOCH).ok()?.as_millis();
+    i64::try_from(millis).ok()
+}
+
+/// Read the city folders under `root` for property folders not in
+/// `existing`, skipping cities whose mtime matches `cached`. Blocking.
+fn walk_root(
+    root: &Path,
     status: &str,
-    existing_properties: &HashSet<String>,
-    pool: &SqlitePool,
-) -> Result<ScanResult, String> {
-    let mut result = ScanResult {
-        found_properties: 0,
-        new_properties: 0,
-        existing_properties: 0,
-        errors: Vec::new(),
+    existing: &HashSet<String>,
+    cached: &HashMap<String, i64>,
+) -> Result<RootWalk, String> {
+    let mut walk = RootWalk::default();
+    let settled_before = chrono::Utc::now().timestamp_millis() - MTIME_SETTLE_MS;
+
+    let entries = match fs::read_dir(root) {
+        Ok(e) => e,
+        Err(e) => return Err(format!("Failed to read directory: {}", e)),
     };
 
-    // Filesystem scan runs on a blocking thread to avoid stalling the Tokio runtime
-    let folder_path_clone = folder_path.to_path_buf();
-    let status_clone = status.to_string();
-    let existing_clone = existing_properties.clone();
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                walk.errors
+                    .push(format!("Error reading directory entry: {}", e));
+                continue;
+            }
+        };
+
+        let city_path = entry.path();
+        let Ok(metadata) = fs::metadata(&city_path) else {
+            continue;
+        };
+        if !metadata.is_dir() {
+            continue;
+        }
 
-    let (new_properties_to_insert, scan_found, scan_existing, scan_errors) =
-        tokio::task::spawn_blocking(move || {
-            let mut new_props: Vec<(String, String, String, String, Option<String>, String)> =
-                Vec::new();
-            let mut found = 0usize;
-            let mut existing = 0usize;
-            let mut errors = Vec::new();
-
-            let entries = match fs::read_dir(&folder_path_clone) {
-                Ok(e) => e,
-                Err(e) => return Err(format!("Failed to read directory: {}", e)),
-            };
-
-            for entry in entries {
-                let entry = match entry {
-                    Ok(entry) => entry,
-                    Err(e) => {
-                        errors.push(format!("Error reading directory entry: {}", e));
-                        continue;
-                    }
-                };
-
-                let city_path = entry.path();
-                if !city_path.is_dir() {
-                    continue;
-                }
+        let city_name = match city_path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name.to_string(),
+            None => {
+                walk.errors
+                    .push(format!("Invalid city folder name: {:?}", city_path));
+                continue;
+            }
+        };
 
-                let city_name = match city_path.file_name().and_then(|n| n.to_str()) {
-                    Some(name) => name.to_string(),
-                    None => {
-                        errors.push(format!("Invalid city folder name: {:?}", city_path));
-                        continue;
-                    }
-                };
-
-                let city_entries = match fs::read_dir(&city_path) {
-                    Ok(entries) => entries,
-                    Err(e) => {
-                        errors.push(format!("Failed to read city folder {}: {}", city_name, e));
-                        continue;
-                    }
-                };
-
-                for property_entry in city_entries {
-                    let property_entry = match property_entry {
-                        Ok(entry) => entry,
-                        Err(e) => {
-                            errors.push(format!(
-                                "Error reading property entry in {}: {}",
-                                city_name, e
-                            ));
-                            continue;
-                        }
-                    };
-
-                    let property_path = property_entry.path();
-                    if !property_path.is_dir() {
-                        continue;
-                    }
-
-                    let folder_name = match property_path.file_name().and_then(|n| n.to_str()) {
-                        Some(name) => name.to_string(),
-                        None => {
-                            errors
-                                .push(format!("Invalid property folder name: {:?}", property_path));
-                            continue;
-                        }
-                    };
-
-                    let (property_name, code) = parse_folder_name(&folder_name);
-
-                    found += 1;
-
-                    let property_key = format!("{}/{}", city_name, folder_name);
-
-                    if existing_clone.contains(&property_key) {
-                        existing += 1;
-                        continue;
-                    }
-
-                    if !is_valid_property_folder(&property_path) {
-                        errors.push(format!("Invalid property structure: {}", property_key));
-                        continue;
-                    }
-
-                    new_props.push((
-                        property_name,
-                        city_name.clone(),
-                        status_clone.clone(),
-                        folder_name,
-                        code,
-                        property_key,
-                    ));
-                }
+        let city_key = city_path.to_string_lossy().to_string();
+        let mtime = modified_millis(&metadata);
+        if mtime.is_some() && cached.get(&city_key) == mtime.as_ref() {
+            walk.cities_skipped += 1;
+            continue;
+        }
+
+        walk.cities_scanned += 1;
+        let errors_before = walk.errors.len();
+        walk_city(&city_path, &city_name, status, existing, &mut walk);
+        if walk.errors.len() == errors_before {
+            if let Some(mtime) = mtime.filter(|mtime| *mtime < settled_before) {
+                walk.city_mtimes.push((city_key, mtime));
             }
+        }
+    }
 
-            Ok((new_props, found, existing, errors))
-        })
-        .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+    Ok(walk)
+}
+
+/// Collect the property folders of one city into `walk`. Blocking.
+fn walk_city(
+    city_path: &Path,
+    city_name: &str,
+    status: &str,
+    existing: &HashSet<String>,
+    walk: &mut RootWalk,
+) {
+    let city_entries = match fs::read_dir(city_path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            walk.errors
+                .push(format!("Failed to read city folder {}: {}", city_name, e));
+            return;
+        }
+    };
+
+    for property_entry in city_entries {
+        let property_entry = match property_entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                walk.errors.push(format!(
+                    "Error reading property entry in {}: {}",
+                    city_name, e
+                ));
+                continue;
+            }
+        };
+
+        let property_path = property_entry.path();
+        if !property_path.is_dir() {
+            continue;
+        }
+
+        let folder_name = match property_path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name.to_string(),
+            None => {
+                walk.errors
+                    .push(format!("Invalid property folder name: {:?}", property_path));
+                continue;
+            }
+        };
+
+        // Hidden staging folders of a copy or restore in progress, such as
+        // `.Villa.moving`, are not properties.
+        if folder_name.starts_with('.') {
+            continue;
+        }
+
+        let (property_name, code) = parse_folder_name(&folder_name);
+
+        walk.found += 1;
+
+        let property_key = format!("{}/{}", city_name, folder_name);
+
+        if existing.contains(&property_key) {
+            walk.existing += 1;
+            continue;
+        }
+
+        if !is_valid_property_folder(&property_path) {
+            walk.errors
+                .push(format!("Invalid property structure: {}", property_key));
+            continue;
+        }
+
+        walk.new_properties.push(NewProperty {
+            name: property_name,
+            city: city_name.to_string(),
+            status: status.to_string(),
+            folder_name,
+            code,
+            key: property_key,
+            city_path: city_path.to_string_lossy().to_string(),
+        });
+    }
+}
+
+/// Insert what `walk` found and add its counts to `result`. Returns the city
+/// mtimes that are safe to cache: those of cities read without errors whose
+/// new properties were all imported.
+async fn import_walk(
+    pool: &SqlitePool,
+    walk: RootWalk,
+    result: &mut ScanResult,
+) -> Result<Vec<(String, i64)>, String> {
+    result.found_properties += walk.found;
+    result.existing_properties += walk.existing;
+    result.skipped_cities += walk.cities_skipped;
+    result.errors.extend(walk.errors);
+
+    let failed_cities = insert_new_properties(pool, &walk.new_properties, result).await?;
+    Ok(walk
+        .city_mtimes
+        .into_iter()
+        .filter(|(city_path, _)| !failed_cities.contains(city_path))
+        .collect())
+}
 
-    result.found_properties = scan_found;
-    result.existing_properties = scan_existing;
-    result.errors = scan_errors;
+/// Insert new properties in one transaction. Returns the city paths of the
+/// ones that failed.
+async fn insert_new_properties(
+    pool: &SqlitePool,
+    new_properties: &[NewProperty],
+    result: &mut ScanResult,
+) -> Result<HashSet<String>, String> {
+    let mut failed_cities = HashSet::new();
+    if new_properties.is_empty() {
+        return Ok(failed_cities);
+    }
 
     // Batch-insert all new properties in a single transaction.
     // SQLite is ~100x faster with batched transactions vs one-per-insert.
-    if !new_properties_to_insert.is_empty() {
-        let now = chrono::Utc::now().timestamp_millis();
+    let now = chrono::Utc::now().timestamp_millis();
 
-        let mut tx = pool
-            .begin()
-            .await
-            .map_err(|e| format!("Failed to start batch transaction: {}", e))?;
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start batch transaction: {}", e))?;
+
+    for property in new_properties {
+        let folder_path = get_relative_folder_path(&property.city, &property.folder_name);
+
+        // Upsert city
+        if let Err(e) = sqlx::query(
+            r#"
+            INSERT INTO cities (name, usage_count, created_at)
+            VALUES (?, 1, ?)
+            ON CONFLICT(name) DO UPDATE SET usage_count = usage_count + 1
+            "#,
+        )
+        .bind(&property.city)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        {
+            result
+                .errors
+                .push(format!("Failed to update city for {}: {}", property.key, e));
+            failed_cities.insert(property.city_path.clone());
+            continue;
+        }
 
-        for (property_name, city_name, status_str, folder_name, code, property_key) in
-            &new_properties_to_insert
+        // Insert property
+        match sqlx::query(
+            r#"
+            INSERT INTO properties (name, city, status, folder_path, notes, code, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&property.name)
+        .bind(&property.city)
+        .bind(&property.status)
+        .bind(&folder_path)
+        .bind("Imported from existing folder")
+        .bind(property.code.as_deref())
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
         {
-            let folder_path = get_relative_folder_path(city_name, folder_name);
-
-            // Upsert city
-            if let Err(e) = sqlx::query(
-                r#"
-                INSERT INTO cities (name, usage_count, created_at)
-                VALUES (?, 1, ?)
-                ON CONFLICT(name) DO UPDATE SET usage_count = usage_count + 1
-                "#,
-            )
-            .bind(city_name)
-            .bind(now)
-            .execute(&mut *tx)
-            .await
-            {
+            Ok(_) => {
+                result.new_properties += 1;
+            }
+            Err(e) => {
                 result
                     .errors
-                    .push(format!("Failed to update city for {}: {}", property_key, e));
-                continue;
-            }
-
-            // Insert property
-            match sqlx::query(
-                r#"
-                INSERT INTO properties (name, city, status, folder_path, notes, code, created_at, updated_at)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-                "#,
-            )
-            .bind(property_name)
-            .bind(city_name)
-            .bind(status_str)
-            .bind(&folder_path)
-            .bind("Imported from existing folder")
-            .bind(code.as_deref())
-            .bind(now)
-            .bind(now)
-            .execute(&mut *tx)
-            .await
-            {
-                Ok(_) => {
-                    result.new_properties += 1;
-                }
-                Err(e) => {
-                    result
-                        .errors
-                        .push(format!("Failed to add property {}: {}", property_key, e));
-                }
+                    .push(format!("Failed to add property {}: {}", property.key, e));
+                failed_cities.insert(property.city_path.clone());
             }
         }
+    }
 
-        tx.commit()
-            .await
-            .map_err(|e| format!("Failed to commit batch transaction: {}", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit batch transaction: {}", e))?;
+
+    Ok(failed_cities)
+}
+
+async fn load_city_mtimes(pool: &SqlitePool) -> Result<HashMap<String, i64>, String> {
+    let rows = sqlx::query("SELECT path, mtime FROM scan_state")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load scan state: {e}"))?;
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("path"), row.get("mtime")))
+        .collect())
+}
+
+async fn save_city_mtimes(pool: &SqlitePool, mtimes: &[(String, i64)]) -> Result<(), String> {
+    if mtimes.is_empty() {
+        return Ok(());
     }
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to save scan state: {e}"))?;
+    for (path, mtime) in mtimes {
+        sqlx::query(
+            "INSERT INTO scan_state (path, mtime, scanned_at) VALUES (?, ?, ?)
+             ON CONFLICT(path) DO UPDATE SET mtime = excluded.mtime, scanned_at = excluded.scanned_at",
+        )
+        .bind(path)
+        .bind(mtime)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to save scan state: {e}"))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to save scan state: {e}"))
+}
 
+/// Scan one status folder in full, without the mtime cache, and import
+/// what is new.
+pub(super) async fn scan_folder_for_properties(
+    folder_path: &Path,
+    status: &str,
+    existing_properties: &HashSet<String>,
+    pool: &SqlitePool,
+) -> Result<ScanResult, String> {
+    // Filesystem scan runs on a blocking thread to avoid stalling the Tokio runtime
+    let folder_path = folder_path.to_path_buf();
+    let status_clone = status.to_string();
+    let existing_clone = existing_properties.clone();
+    let walk = tokio::task::spawn_blocking(move || {
+        walk_root(
+            &folder_path,
+            &status_clone,
+            &existing_clone,
+            &HashMap::new(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    let mut result = ScanResult::default();
+    import_walk(pool, walk, &mut result).await?;
     Ok(result)
 }
 
@@ -446,6 +653,27 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn scan_skips_staging_folders() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+
+        let city_dir = tmp.path().join("CityA");
+        fs::create_dir_all(city_dir.join("Villa")).unwrap();
+        fs::create_dir_all(city_dir.join(".Loft.moving")).unwrap();
+        fs::create_dir_all(city_dir.join(".Flat.extracting")).unwrap();
+
+        let result =
+            scan_folder_for_properties(&tmp.path().to_path_buf(), "NEW", &HashSet::new(), &pool)
+                .await
+                .unwrap();
+
+        assert_eq!(result.found_properties, 1);
+        assert_eq!(result.new_properties, 1);
+        let set = get_existing_properties_set(&pool).await.unwrap();
+        assert_eq!(set, HashSet::from(["CityA/Villa".to_string()]));
+    }
+
     #[tokio::test]
     async fn scan_skips_non_directory_entries() {
         let pool = setup_test_db().await;
@@ -507,4 +735,68 @@ mod tests {
             .unwrap();
         assert_eq!(row.get::<i32, _>("usage_count"), 2);
     }
+
+    fn config_for(root: &Path) -> AppConfig {
+        AppConfig {
+            new_folder_path: root.join("NEW").to_string_lossy().to_string(),
+            done_folder_path: root.join("DONE").to_string_lossy().to_string(),
+            not_found_folder_path: root.join("NOT_FOUND").to_string_lossy().to_string(),
+            archive_folder_path: root.join("ARCHIVE").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn incremental_scan_skips_unchanged_cities() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = config_for(tmp.path());
+        let athens = tmp.path().join("NEW").join("Athens");
+        fs::create_dir_all(athens.join("Villa A")).unwrap();
+        fs::create_dir_all(tmp.path().join("DONE").join("Rome").join("Villa B")).unwrap();
+
+        // Backdate Athens so its mtime is settled and gets cached.
+        let old = std::time::SystemTime::now() - Duration::from_secs(60);
+        fs::File::open(&athens).unwrap().set_modified(old).unwrap();
+
+        let first = scan_roots(&pool, &config, false).await.unwrap();
+        assert_eq!(first.new_properties, 2);
+        assert_eq!(first.skipped_cities, 0);
+        let statuses: Vec<&str> = first
+            .root_timings
+            .iter()
+            .map(|timing| timing.status.as_str())
+            .collect();
+        assert_eq!(statuses, vec!["NEW", "DONE"]);
+
+        // Rome was modified just now, so only Athens is skipped.
+        let second = scan_roots(&pool, &config, false).await.unwrap();
+        assert_eq!(second.skipped_cities, 1);
+        assert_eq!(second.root_timings[0].cities_skipped, 1);
+        assert_eq!(second.root_timings[0].cities_scanned, 0);
+        assert_eq!(second.found_properties, 1);
+
+        // A full scan reads every city again.
+        let full = scan_roots(&pool, &config, true).await.unwrap();
+        assert_eq!(full.skipped_cities, 0);
+        assert_eq!(full.found_properties, 2);
+        assert_eq!(full.existing_properties, 2);
+    }
+
+    #[tokio::test]
+    async fn changed_city_is_rescanned() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = config_for(tmp.path());
+        let athens = tmp.path().join("NEW").join("Athens");
+        fs::create_dir_all(athens.join("Villa A")).unwrap();
+        let old = std::time::SystemTime::now() - Duration::from_secs(60);
+        fs::File::open(&athens).unwrap().set_modified(old).unwrap();
+        scan_roots(&pool, &config, false).await.unwrap();
+
+        fs::create_dir_all(athens.join("Villa C")).unwrap();
+        let result = scan_roots(&pool, &config, false).await.unwrap();
+        assert_eq!(result.skipped_cities, 0);
+        assert_eq!(result.new_properties, 1);
+    }
 }
@@ -0,0 +1,312 @@
+//! Full-text property search backed by the `properties_fts` FTS5 table.
+//!
+//! The index is maintained by triggers on `properties` (see migration 7),
+//! so every write path keeps it current without calling into this module.
+//! FTS5's `unicode61` tokenizer folds case and strips Latin diacritics but
+//! leaves Greek tonos and dialytika alone, so both the indexed text and the
+//! query go through the same Greek fold first: the triggers via the SQL
+//! expression from `greek_fold_sql`, queries via `fold_search_text`.
+
+use sqlx::{Row, SqlitePool};
+
+use crate::database::types::{CommandResult, Property};
+use crate::database::{attach_tags, get_database_pool};
+
+/// Accented Greek letters (both cases) and final sigma mapped to their plain
+/// lowercase form, plus combining marks that decomposed (NFD) names carry.
+/// `None` drops the character.
+const GREEK_FOLDS: &[(char, Option<char>)] = &[
+    ('ά', Some('α')),
+    ('έ', Some('ε')),
+    ('ή', Some('η')),
+    ('ί', Some('ι')),
+    ('ό', Some('ο')),
+    ('ύ', Some('υ')),
+    ('ώ', Some('ω')),
+    ('ϊ', Some('ι')),
+    ('ϋ', Some('υ')),
+    ('ΐ', Some('ι')),
+    ('ΰ', Some('υ')),
+    ('Ά', Some('α')),
+    ('Έ', Some('ε')),
+    ('Ή', Some('η')),
+    ('Ί', Some('ι')),
+    ('Ό', Some('ο')),
+    ('Ύ', Some('υ')),
+    ('Ώ', Some('ω')),
+    ('Ϊ', Some('ι')),
+    ('Ϋ', Some('υ')),
+    ('ς', Some('σ')),
+    ('\u{0301}', None),
+    ('\u{0308}', None),
+];
+
+const VALID_STATUSES: &[&str] = &["NEW", "DONE", "NOT_FOUND", "ARCHIVE"];
+
+/// Default and upper bound for the number of results returned.
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+
+/// Fold Greek accents and case so "Γλυφάδα", "ΓΛΥΦΑΔΑ" and "γλυφαδα" all
+/// compare equal. Latin accents are left to the tokenizer.
+pub(super) fn fold_search_text(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| {
+            GREEK_FOLDS
+                .iter()
+                .find(|(from, _)| *from == c)
+                .map_or(Some(c), |(_, to)| *to)
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// SQL expression applying the `GREEK_FOLDS` table to `expr` with nested
+/// `replace()` calls. Used by the FTS triggers, where Rust cannot run.
+pub(super) fn greek_fold_sql(expr: &str) -> String {
+    GREEK_FOLDS
+        .iter()
+        .fold(expr.to_string(), |inner, (from, to)| {
+            let to = to.map(String::from).unwrap_or_default();
+            format!("replace({inner}, '{from}', '{to}')")
+        })
+}
+
+/// Turn free text into an FTS5 MATCH expression: every word must match,
+/// and the last characters typed may be the start of a longer word.
+/// Returns `None` when the query has no searchable words.
+fn build_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = fold_search_text(query)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{term}\"*"))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+pub(super) async fn search_properties_in_pool(
+    pool: &SqlitePool,
+    query: &str,
+    statuses: &[String],
+    limit: i64,
+) -> Result<Vec<Property>, String> {
+    let Some(match_expression) = build_match_expression(query) else {
+        return Ok(Vec::new());
+    };
+
+    if let Some(invalid) = statuses
+        .iter()
+        .find(|s| !VALID_STATUSES.contains(&s.as_str()))
+    {
+        return Err(format!("Invalid status: {invalid}"));
+    }
+
+    let status_filter = if statuses.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " AND p.status IN ({})",
+            vec!["?"; statuses.len()].join(", ")
+        )
+    };
+    let mut sql = format!(
+        "SELECT p.* FROM properties_fts
+         JOIN properties p ON p.id = properties_fts.rowid
+         WHERE properties_fts MATCH ? AND p.deleted_at IS NULL{status_filter}"
+    );
+    // bm25 weights follow the column order (name, city, code, notes): name
+    // and code hits outrank city hits, which outrank notes.
+    sql.push_str(" ORDER BY bm25(properties_fts, 10.0, 4.0, 8.0, 1.0), p.updated_at DESC LIMIT ?");
+
+    let mut db_query = sqlx::query(&sql).bind(match_expression);
+    for status in statuses {
+        db_query = db_query.bind(status);
+    }
+    let rows = db_query
+        .bind(limit.clamp(1, MAX_LIMIT))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to search properties: {e}"))?;
+
+    let mut properties: Vec<Property> = rows
+        .into_iter()
+        .map(|row| {
+            let created_at_timestamp: i64 = row.get("created_at");
+            let updated_at_timestamp: i64 = row.get("updated_at");
+
+            Property {
+                id: Some(row.get("id")),
+                name: row.get("name"),
+                city: row.get("city"),
+                status: row.get("status"),
+                folder_path: row.get("folder_path"),
+                notes: row.get("notes"),
+                code: row.get("code"),
+                tags: Vec::new(),
+                created_at: chrono::DateTime::from_timestamp_millis(created_at_timestamp)
+                    .unwrap_or_else(chrono::Utc::now),
+                updated_at: chrono::DateTime::from_timestamp_millis(updated_at_timestamp)
+                    .unwrap_or_else(chrono::Utc::now),
+                completed: None,
+            }
+        })
+        .collect();
+
+    attach_tags(pool, &mut properties).await?;
+
+    Ok(properties)
+}
+
+/// Search properties by name, city, code and notes, best matches first.
+/// `statuses` narrows the results to those workflow folders when non-empty.
+#[tauri::command]
+pub async fn search_properties(
+    app: tauri::AppHandle,
+    query: String,
+    statuses: Option<Vec<String>>,
+    limit: Option<i64>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    match search_properties_in_pool(
+        pool,
+        &query,
+        &statuses.unwrap_or_default(),
+        limit.unwrap_or(DEFAULT_LIMIT),
+    )
+    .await
+    {
+        Ok(properties) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::to_value(properties).map_err(|e| e.to_string())?),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+
+    fn names(properties: &[Property]) -> Vec<&str> {
+        properties.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn fold_strips_greek_accents_and_case() {
+        assert_eq!(fold_search_text("Γλυφάδα"), "γλυφαδα");
+        assert_eq!(fold_search_text("ΆΓΙΟΣ Νικόλαος"), "αγιοσ νικολαοσ");
+        assert_eq!(fold_search_text("Villa Μαρία"), "villa μαρια");
+    }
+
+    #[test]
+    fn match_expression_quotes_terms_as_prefixes() {
+        assert_eq!(
+            build_match_expression("villa \"ath").as_deref(),
+            Some("\"villa\"* \"ath\"*")
+        );
+        assert_eq!(build_match_expression("  -*- "), None);
+    }
+
+    #[tokio::test]
+    async fn search_matches_prefixes_and_greek_without_accents() {
+        let pool = setup_test_db().await;
+        add_property_to_database(&pool, "Βίλα Γλυφάδα", "Αθήνα", "NEW", "Βίλα Γλυφάδα", None)
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Villa Alpha", "Athens", "DONE", "Villa Alpha", None)
+            .await
+            .unwrap();
+
+        let hits = search_properties_in_pool(&pool, "γλυφαδα", &[], 50)
+            .await
+            .unwrap();
+        assert_eq!(names(&hits), vec!["Βίλα Γλυφάδα"]);
+
+        let hits = search_properties_in_pool(&pool, "ΑΘΗ", &[], 50)
+            .await
+            .unwrap();
+        assert_eq!(names(&hits), vec!["Βίλα Γλυφάδα"]);
+
+        let hits = search_properties_in_pool(&pool, "vil alp", &[], 50)
+            .await
+            .unwrap();
+        assert_eq!(names(&hits), vec!["Villa Alpha"]);
+    }
+
+    #[tokio::test]
+    async fn search_filters_by_status_and_ranks_name_over_notes() {
+        let pool = setup_test_db().await;
+        add_property_to_database(&pool, "Sea View", "Athens", "NEW", "Sea View", None)
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "Loft", "Athens", "DONE", "Loft", None)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE properties SET notes = 'great sea view' WHERE name = 'Loft'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let hits = search_properties_in_pool(&pool, "sea", &[], 50)
+            .await
+            .unwrap();
+        assert_eq!(names(&hits), vec!["Sea View", "Loft"]);
+
+        let hits = search_properties_in_pool(&pool, "sea", &["DONE".to_string()], 50)
+            .await
+            .unwrap();
+        assert_eq!(names(&hits), vec!["Loft"]);
+
+        assert!(
+            search_properties_in_pool(&pool, "sea", &["BOGUS".to_string()], 50)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn index_follows_updates_and_deletes() {
+        let pool = setup_test_db().await;
+        add_property_to_database(&pool, "Old Name", "Athens", "NEW", "Old Name", None)
+            .await
+            .unwrap();
+
+        sqlx::query("UPDATE properties SET name = 'New Name', code = 'AB123'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search_properties_in_pool(&pool, "old", &[], 50)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            names(
+                &search_properties_in_pool(&pool, "ab12", &[], 50)
+                    .await
+                    .unwrap()
+            ),
+            vec!["New Name"]
+        );
+
+        sqlx::query("DELETE FROM properties")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(search_properties_in_pool(&pool, "new", &[], 50)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
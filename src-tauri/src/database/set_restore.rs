@@ -0,0 +1,432 @@
+//! `restore_set`: undo `complete_set` for one set.
+//!
+//! Each property the set archived is moved from ARCHIVE back to DONE or NEW
+//! through the same journaled status change every other move uses. When a
+//! property's archive folder is gone, its folder can instead be rebuilt from
+//! the set ZIP. The `sets` row is kept and stamped with `reverted_at`, so the
+//! set's history and ZIP stay visible.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sqlx::{Row, SqlitePool};
+
+use crate::database::transfer::{emit_move_progress, CopyProgress};
+use crate::database::types::{CommandResult, RestoreSetResult};
+use crate::database::{
+    folder_path_to_pathbuf, get_base_path_for_status, get_database_pool, plan_folder_move,
+    run_status_change_with_progress, StatusChange,
+};
+
+/// A property of the set that is still in ARCHIVE.
+struct ArchivedProperty {
+    id: i64,
+    name: String,
+    folder_path: String,
+}
+
+/// Extract the entries under `folder_path/` from the set ZIP into `target`.
+/// Entries go into a hidden staging folder first, which is renamed into
+/// place only once all of them are written. Returns the number of files.
+/// Blocking.
+fn extract_property_folder(
+    zip_path: &Path,
+    folder_path: &str,
+    target: &Path,
+) -> Result<usize, String> {
+    let name = target
+        .file_name()
+        .ok_or_else(|| format!("Invalid destination: {}", target.display()))?;
+    let staging = target.with_file_name(format!(".{}.extracting", name.to_string_lossy()));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to clear {}: {e}", staging.display()))?;
+    }
+
+    let extracted = extract_entries(zip_path, &folder_path_to_pathbuf(folder_path), &staging)
+        .and_then(|files| {
+            fs::rename(&staging, target)
+                .map_err(|e| format!("Failed to move extracted folder: {e}"))?;
+            Ok(files)
+        });
+    if extracted.is_err() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    extracted
+}
+
+fn extract_entries(zip_path: &Path, folder: &Path, staging: &Path) -> Result<usize, String> {
+    let file = fs::File::open(zip_path).map_err(|e| format!("Failed to open ZIP: {e}"))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP: {e}"))?;
+
+    fs::create_dir_all(staging)
+        .map_err(|e| format!("Failed to create {}: {e}", staging.display()))?;
+    let mut files = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read ZIP entry #{index}: {e}"))?;
+        // Names that would escape the folder have no enclosed name.
+        let Some(relative) = entry
+            .enclosed_name()
+            .and_then(|path| path.strip_prefix(folder).ok().map(Path::to_path_buf))
+        else {
+            continue;
+        };
+        let out = staging.join(&relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&out)
+                .map_err(|e| format!("Failed to create {}: {e}", out.display()))?;
+            continue;
+        }
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        let mut writer = fs::File::create(&out)
+            .map_err(|e| format!("Failed to create {}: {e}", out.display()))?;
+        io::copy(&mut entry, &mut writer)
+            .map_err(|e| format!("Failed to extract {}: {e}", entry.name()))?;
+        files += 1;
+    }
+
+    if files == 0 {
+        return Err("Property folder is not in the ZIP".to_string());
+    }
+    Ok(files)
+}
+
+/// The set's properties that can be restored, and a reason for each one
+/// that cannot.
+async fn archived_properties(
+    pool: &SqlitePool,
+    set_id: i64,
+) -> Result<(Vec<ArchivedProperty>, Vec<String>), String> {
+    let rows = sqlx::query(
+        "SELECT sp.property_id, sp.property_name, p.name, p.status, p.folder_path, p.deleted_at
+         FROM set_properties sp
+         LEFT JOIN properties p ON p.id = sp.property_id
+         WHERE sp.set_id = ?
+         ORDER BY sp.id",
+    )
+    .bind(set_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch set properties: {e}"))?;
+
+    let mut archived = Vec::new();
+    let mut skipped = Vec::new();
+    for row in rows {
+        let set_name: String = row.get("property_name");
+        let Some(id) = row.get::<Option<i64>, _>("property_id") else {
+            skipped.push(format!("{set_name}: no longer in the database"));
+            continue;
+        };
+        let Some(name) = row.get::<Option<String>, _>("name") else {
+            skipped.push(format!("{set_name}: no longer in the database"));
+            continue;
+        };
+        if row.get::<Option<i64>, _>("deleted_at").is_some() {
+            skipped.push(format!("{name}: in the trash"));
+            continue;
+        }
+        let status: String = row.get("status");
+        if status != "ARCHIVE" {
+            skipped.push(format!("{name}: status is {status}, not ARCHIVE"));
+            continue;
+        }
+        archived.push(ArchivedProperty {
+            id,
+            name,
+            folder_path: row.get("folder_path"),
+        });
+    }
+    Ok((archived, skipped))
+}
+
+/// Move the set's archived properties back to `target_status` (DONE or
+/// NEW). Properties whose archive folder is missing are re-extracted from
+/// the set ZIP when `extract_missing` is set, and skipped otherwise. Only
+/// properties still in ARCHIVE are moved, so the restore can be run again
+/// to bring back what an earlier run skipped. `reverted_at` records the
+/// last run that restored anything.
+pub(super) async fn restore_set_in<P, F>(
+    pool: &SqlitePool,
+    config: &crate::config::AppConfig,
+    set_id: i64,
+    target_status: &str,
+    extract_missing: bool,
+    progress: P,
+) -> Result<RestoreSetResult, String>
+where
+    P: Fn(i64) -> F,
+    F: FnMut(CopyProgress) + Send + 'static,
+{
+    if !matches!(target_status, "DONE" | "NEW") {
+        return Err(format!(
+            "Invalid target status: {target_status}. Sets can be restored to DONE or NEW"
+        ));
+    }
+
+    let zip_path: String = sqlx::query_scalar("SELECT zip_path FROM sets WHERE id = ?")
+        .bind(set_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch set: {e}"))?
+        .ok_or("Set not found")?;
+
+    let (archived, skipped) = archived_properties(pool, set_id).await?;
+    let mut result = RestoreSetResult {
+        set_id,
+        status: target_status.to_string(),
+        skipped,
+        ..RestoreSetResult::default()
+    };
+    let target_base = get_base_path_for_status(config, target_status)?;
+
+    for property in archived {
+        let target = target_base.join(folder_path_to_pathbuf(&property.folder_path));
+        let folder_move =
+            plan_folder_move(config, &property.folder_path, "ARCHIVE", target_status)?;
+        let target_exists = {
+            let target = target.clone();
+            tokio::task::spawn_blocking(move || target.exists())
+                .await
+                .map_err(|e| format!("Task join error: {e}"))?
+        };
+        if target_exists {
+            result.skipped.push(format!(
+                "{}: a folder already exists in {target_status}",
+                property.name
+            ));
+            continue;
+        }
+
+        let extracted = if folder_move.is_some() {
+            false
+        } else if extract_missing {
+            let zip_path = PathBuf::from(&zip_path);
+            let folder_path = property.folder_path.clone();
+            let target = target.clone();
+            let extraction = tokio::task::spawn_blocking(move || {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create parent directories: {e}"))?;
+                }
+                extract_property_folder(&zip_path, &folder_path, &target)
+            })
+            .await
+            .map_err(|e| format!("Task join error: {e}"))?;
+            if let Err(e) = extraction {
+                result.skipped.push(format!("{}: {e}", property.name));
+                continue;
+            }
+            true
+        } else {
+            result
+                .skipped
+                .push(format!("{}: archive folder is missing", property.name));
+            continue;
+        };
+
+        let change = StatusChange {
+            property_id: property.id,
+            old_status: "ARCHIVE".to_string(),
+            new_status: target_status.to_string(),
+            folder_path: property.folder_path.clone(),
+            folder_move,
+            source: "restore_set".to_string(),
+        };
+        match run_status_change_with_progress(pool, &change, progress(property.id)).await {
+            Ok(warning) => {
+                result.warnings.extend(warning);
+                result.restored += 1;
+                if extracted {
+                    result.extracted += 1;
+                }
+            }
+            Err(e) => result.skipped.push(format!("{}: {e}", property.name)),
+        }
+    }
+
+    if result.restored > 0 {
+        sqlx::query("UPDATE sets SET reverted_at = ? WHERE id = ?")
+            .bind(chrono::Utc::now().timestamp_millis())
+            .bind(set_id)
+            .execute(pool)
+            .await
+            .map_err(|e| format!("Failed to mark set as reverted: {e}"))?;
+    }
+
+    Ok(result)
+}
+
+/// Move a completed set's properties from ARCHIVE back to `target_status`
+/// (DONE or NEW), optionally rebuilding missing folders from the set ZIP.
+/// `data` is a `RestoreSetResult`.
+#[tauri::command]
+pub async fn restore_set(
+    app: tauri::AppHandle,
+    set_id: i64,
+    target_status: String,
+    extract_missing: Option<bool>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let config = crate::config::get_cached_config(&app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    match restore_set_in(
+        pool,
+        &config,
+        set_id,
+        &target_status,
+        extract_missing.unwrap_or(false),
+        |property_id| emit_move_progress(&app, property_id),
+    )
+    .await
+    {
+        Ok(result) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::to_value(result).map_err(|e| e.to_string())?),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+    use std::io::Write;
+
+    fn config(root: &Path) -> AppConfig {
+        AppConfig {
+            new_folder_path: root.join("NEW").to_string_lossy().to_string(),
+            done_folder_path: root.join("DONE").to_string_lossy().to_string(),
+            not_found_folder_path: root.join("NF").to_string_lossy().to_string(),
+            archive_folder_path: root.join("ARCHIVE").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        }
+    }
+
+    /// An archived Villa in a set whose ZIP holds its two images.
+    async fn archived_set(pool: &SqlitePool, root: &Path) -> (i64, i64) {
+        add_property_to_database(pool, "Villa", "Athens", "ARCHIVE", "Villa", Some("1"))
+            .await
+            .unwrap();
+        let property_id: i64 = sqlx::query_scalar("SELECT id FROM properties WHERE name = 'Villa'")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let zip_path = root.join("set.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        for (name, contents) in [
+            ("Athens/Villa/1.jpg", &b"one"[..]),
+            ("Athens/Villa/INTERNET/1.jpg", b"internet"),
+            ("Athens/Other/1.jpg", b"other"),
+        ] {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let set_id = sqlx::query(
+            "INSERT INTO sets (name, zip_path, property_count, created_at) VALUES ('s', ?, 1, 0)",
+        )
+        .bind(zip_path.to_string_lossy())
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        sqlx::query(
+            "INSERT INTO set_properties (set_id, property_id, property_name, property_city)
+             VALUES (?, ?, 'Villa', 'Athens')",
+        )
+        .bind(set_id)
+        .bind(property_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        (set_id, property_id)
+    }
+
+    async fn status_of(pool: &SqlitePool, property_id: i64) -> String {
+        sqlx::query_scalar("SELECT status FROM properties WHERE id = ?")
+            .bind(property_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn restore_moves_folder_back_and_marks_set() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = config(tmp.path());
+        let (set_id, property_id) = archived_set(&pool, tmp.path()).await;
+        let archived = tmp.path().join("ARCHIVE/Athens/Villa");
+        fs::create_dir_all(&archived).unwrap();
+        fs::write(archived.join("1.jpg"), b"one").unwrap();
+
+        let result = restore_set_in(&pool, &config, set_id, "DONE", false, |_| |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!((result.restored, result.extracted), (1, 0));
+        assert!(result.skipped.is_empty());
+        assert!(!archived.exists());
+        assert!(tmp.path().join("DONE/Athens/Villa/1.jpg").exists());
+        assert_eq!(status_of(&pool, property_id).await, "DONE");
+        let reverted_at: Option<i64> =
+            sqlx::query_scalar("SELECT reverted_at FROM sets WHERE id = ?")
+                .bind(set_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(reverted_at.is_some());
+
+        // A second run finds nothing left in ARCHIVE.
+        let again = restore_set_in(&pool, &config, set_id, "DONE", false, |_| |_| {})
+            .await
+            .unwrap();
+        assert_eq!(again.restored, 0);
+        assert_eq!(again.skipped, vec!["Villa: status is DONE, not ARCHIVE"]);
+        assert_eq!(status_of(&pool, property_id).await, "DONE");
+    }
+
+    #[tokio::test]
+    async fn missing_archive_folder_is_extracted_from_zip() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let config = config(tmp.path());
+        let (set_id, property_id) = archived_set(&pool, tmp.path()).await;
+
+        // Without extraction nothing happens and the set can be retried.
+        let result = restore_set_in(&pool, &config, set_id, "NEW", false, |_| |_| {})
+            .await
+            .unwrap();
+        assert_eq!(result.restored, 0);
+        assert_eq!(result.skipped.len(), 1);
+        assert_eq!(status_of(&pool, property_id).await, "ARCHIVE");
+
+        let result = restore_set_in(&pool, &config, set_id, "NEW", true, |_| |_| {})
+            .await
+            .unwrap();
+        assert_eq!((result.restored, result.extracted), (1, 1));
+        let villa = tmp.path().join("NEW/Athens/Villa");
+        assert_eq!(fs::read(villa.join("INTERNET/1.jpg")).unwrap(), b"internet");
+        assert!(!tmp.path().join("NEW/Athens/Other").exists());
+        assert!(!tmp.path().join("NEW/Athens/.Villa.extracting").exists());
+        assert_eq!(status_of(&pool, property_id).await, "NEW");
+    }
+}
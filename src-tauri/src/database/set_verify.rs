@@ -0,0 +1,467 @@
+//! `verify_set`: check a set ZIP on the NAS is still whole.
+//!
+//! Every entry is read to the end, which makes the zip reader check its
+//! CRC, and hashed on the way. The entries are then compared against the
+//! set's `manifest.json`, when it has one, and against its
+//! `set_properties` rows, so the report names what is missing, what is
+//! there that should not be, and what is damaged.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool};
+
+use crate::database::images::subfolders;
+use crate::database::manifest::{SetManifest, MANIFEST_CSV, MANIFEST_JSON};
+use crate::database::transfer::to_hex;
+use crate::database::types::{CommandResult, SetEntryIssue, SetVerificationReport};
+use crate::database::{get_database_pool, is_image_extension};
+
+/// A `set_properties` row, as far as finding its folder in the ZIP goes.
+struct ExpectedProperty {
+    property_id: Option<i64>,
+    name: String,
+    city: String,
+}
+
+/// A file entry that read back with a valid CRC.
+struct ZipFileEntry {
+    size: u64,
+    sha256: String,
+}
+
+/// What reading every entry of a ZIP found.
+#[derive(Default)]
+struct ZipContents {
+    files: HashMap<String, ZipFileEntry>,
+    dirs: Vec<String>,
+    corrupt: Vec<SetEntryIssue>,
+    manifest: Option<Vec<u8>>,
+    entries: usize,
+}
+
+/// Passes written bytes into a SHA-256 hasher.
+struct HashWriter(Sha256);
+
+impl Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Read every entry of the ZIP at `zip_path`. Blocking.
+fn read_zip(zip_path: &Path) -> Result<ZipContents, String> {
+    let file = std::fs::File::open(zip_path).map_err(|e| format!("Failed to open ZIP: {e}"))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP: {e}"))?;
+    let mut contents = ZipContents::default();
+
+    for index in 0..archive.len() {
+        contents.entries += 1;
+        let mut entry = match archive.by_index(index) {
+            Ok(entry) => entry,
+            Err(e) => {
+                contents.corrupt.push(SetEntryIssue {
+                    path: format!("entry #{index}"),
+                    reason: e.to_string(),
+                });
+                continue;
+            }
+        };
+        let name = entry.name().to_string();
+        if entry.is_dir() {
+            contents.dirs.push(name);
+            continue;
+        }
+
+        let read = if name == MANIFEST_JSON {
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).map(|size| {
+                let sha256 = to_hex(&Sha256::digest(&bytes));
+                contents.manifest = Some(bytes);
+                (size as u64, sha256)
+            })
+        } else {
+            let mut hasher = HashWriter(Sha256::new());
+            io::copy(&mut entry, &mut hasher).map(|size| (size, to_hex(&hasher.0.finalize())))
+        };
+        match read {
+            Ok((size, sha256)) => {
+                contents.files.insert(name, ZipFileEntry { size, sha256 });
+            }
+            Err(e) => contents.corrupt.push(SetEntryIssue {
+                path: name,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    Ok(contents)
+}
+
+/// Folder of `property` in the ZIP: the manifest's, or for older sets the
+/// first entry under `city/name/` or `city/name (code)/`.
+fn property_folder(
+    property: &ExpectedProperty,
+    manifest: Option<&SetManifest>,
+    contents: &ZipContents,
+) -> Option<String> {
+    if let Some(manifest) = manifest {
+        if let Some(listed) = manifest
+            .properties
+            .iter()
+            .find(|listed| Some(listed.property_id) == property.property_id)
+        {
+            return Some(listed.folder.clone());
+        }
+    }
+    let plain = format!("{}/{}/", property.city, property.name);
+    let coded = format!("{}/{} (", property.city, property.name);
+    contents
+        .dirs
+        .iter()
+        .chain(contents.files.keys())
+        .find_map(|path| {
+            if path.starts_with(&plain) {
+                Some(plain.clone())
+            } else if path.starts_with(&coded) {
+                path.find(')')
+                    .map(|end| path[..=end].to_string() + "/")
+                    .filter(|folder| path.starts_with(folder.as_str()))
+            } else {
+                None
+            }
+        })
+}
+
+/// Compare what the ZIP holds against the manifest and the set's rows.
+fn compare(
+    contents: &ZipContents,
+    manifest: Option<&SetManifest>,
+    expected: &[ExpectedProperty],
+    report: &mut SetVerificationReport,
+) {
+    let mut folders = Vec::new();
+    for property in expected {
+        match property_folder(property, manifest, contents) {
+            Some(folder) => folders.push(folder),
+            None => report
+                .missing_properties
+                .push(format!("{}/{}", property.city, property.name)),
+        }
+    }
+
+    let mut listed = HashSet::new();
+    for image in manifest
+        .iter()
+        .flat_map(|m| &m.properties)
+        .flat_map(|p| &p.images)
+    {
+        listed.insert(image.path.as_str());
+        match contents.files.get(&image.path) {
+            None if contents
+                .corrupt
+                .iter()
+                .any(|issue| issue.path == image.path) => {}
+            None => report.missing_entries.push(image.path.clone()),
+            Some(entry) if u64::try_from(image.size).ok() != Some(entry.size) => {
+                report.corrupt_entries.push(SetEntryIssue {
+                    path: image.path.clone(),
+                    reason: format!("Size is {} bytes, manifest says {}", entry.size, image.size),
+                });
+            }
+            Some(entry) if entry.sha256 != image.sha256 => {
+                report.corrupt_entries.push(SetEntryIssue {
+                    path: image.path.clone(),
+                    reason: "Checksum differs from manifest".to_string(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    // The manifest only lists images in the indexed subfolders, while the
+    // ZIP holds the whole property folder.
+    let indexed = subfolders();
+    for path in contents.files.keys() {
+        if path == MANIFEST_JSON || path == MANIFEST_CSV || listed.contains(path.as_str()) {
+            continue;
+        }
+        let Some(in_folder) = folders
+            .iter()
+            .find_map(|folder| path.strip_prefix(folder.as_str()))
+        else {
+            report.extra_entries.push(path.clone());
+            continue;
+        };
+        let subfolder = in_folder.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let unlisted_image = manifest.is_some()
+            && indexed.iter().any(|indexed| indexed == subfolder)
+            && Path::new(path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(is_image_extension);
+        if unlisted_image {
+            report.extra_entries.push(path.clone());
+        }
+    }
+}
+
+/// Verify the ZIP at `zip_path` against the set's rows. Blocking.
+fn verify_zip(set_id: i64, zip_path: &str, expected: &[ExpectedProperty]) -> SetVerificationReport {
+    let mut report = SetVerificationReport {
+        set_id,
+        zip_path: zip_path.to_string(),
+        ..SetVerificationReport::default()
+    };
+
+    let contents = match read_zip(Path::new(zip_path)) {
+        Ok(contents) => contents,
+        Err(e) => {
+            report.open_error = Some(e);
+            return report;
+        }
+    };
+
+    let manifest = contents.manifest.as_ref().and_then(|bytes| {
+        serde_json::from_slice::<SetManifest>(bytes)
+            .map_err(|e| {
+                report.corrupt_entries.push(SetEntryIssue {
+                    path: MANIFEST_JSON.to_string(),
+                    reason: format!("Unreadable manifest: {e}"),
+                });
+            })
+            .ok()
+    });
+    report.has_manifest = manifest.is_some();
+    report.entries_checked = contents.entries;
+    report
+        .corrupt_entries
+        .extend(contents.corrupt.iter().cloned());
+
+    compare(&contents, manifest.as_ref(), expected, &mut report);
+
+    report.missing_entries.sort();
+    report.extra_entries.sort();
+    report.missing_properties.sort();
+    report.ok = report.corrupt_entries.is_empty()
+        && report.missing_entries.is_empty()
+        && report.extra_entries.is_empty()
+        && report.missing_properties.is_empty();
+    report
+}
+
+pub(super) async fn verify_set_in(
+    pool: &SqlitePool,
+    set_id: i64,
+) -> Result<SetVerificationReport, String> {
+    let zip_path: String = sqlx::query_scalar("SELECT zip_path FROM sets WHERE id = ?")
+        .bind(set_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch set: {e}"))?
+        .ok_or("Set not found")?;
+
+    let expected: Vec<ExpectedProperty> = sqlx::query(
+        "SELECT property_id, property_name, property_city FROM set_properties WHERE set_id = ?",
+    )
+    .bind(set_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch set properties: {e}"))?
+    .iter()
+    .map(|row| ExpectedProperty {
+        property_id: row.get("property_id"),
+        name: row.get("property_name"),
+        city: row.get("property_city"),
+    })
+    .collect();
+
+    tokio::task::spawn_blocking(move || verify_zip(set_id, &zip_path, &expected))
+        .await
+        .map_err(|e| format!("Task join error: {e}"))
+}
+
+/// Check a set's ZIP for truncation, corruption and missing or unexpected
+/// entries. `data` is a `SetVerificationReport`.
+#[tauri::command]
+pub async fn verify_set(app: tauri::AppHandle, set_id: i64) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    match verify_set_in(pool, set_id).await {
+        Ok(report) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::to_value(report).map_err(|e| e.to_string())?),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::manifest::{ManifestImage, ManifestProperty};
+    use crate::database::test_support::setup_test_db;
+
+    fn image(path: &str, contents: &[u8]) -> ManifestImage {
+        ManifestImage {
+            path: path.to_string(),
+            subfolder: String::new(),
+            filename: path.rsplit('/').next().unwrap().to_string(),
+            size: i64::try_from(contents.len()).unwrap(),
+            sha256: to_hex(&Sha256::digest(contents)),
+        }
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        for (name, contents) in files {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    async fn insert_set(pool: &SqlitePool, zip_path: &Path, properties: &[(i64, &str)]) -> i64 {
+        let set_id = sqlx::query(
+            "INSERT INTO sets (name, zip_path, property_count, created_at) VALUES ('s', ?, ?, 0)",
+        )
+        .bind(zip_path.to_string_lossy())
+        .bind(i64::try_from(properties.len()).unwrap())
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        for (id, name) in properties {
+            sqlx::query(
+                "INSERT INTO set_properties (set_id, property_id, property_name, property_city)
+                 VALUES (?, ?, ?, 'Athens')",
+            )
+            .bind(set_id)
+            .bind(id)
+            .bind(name)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+        set_id
+    }
+
+    #[tokio::test]
+    async fn intact_set_verifies() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let zip_path = tmp.path().join("set.zip");
+        let manifest = SetManifest {
+            set_name: "s".to_string(),
+            created_at: String::new(),
+            properties: vec![ManifestProperty {
+                property_id: 1,
+                name: "Villa".to_string(),
+                city: "Athens".to_string(),
+                code: Some("12".to_string()),
+                codes: Vec::new(),
+                folder: "Athens/Villa (12)/".to_string(),
+                images: vec![image("Athens/Villa (12)/1.jpg", b"one")],
+            }],
+        };
+        let json = serde_json::to_vec(&manifest).unwrap();
+        write_zip(
+            &zip_path,
+            &[
+                ("Athens/Villa (12)/1.jpg", b"one"),
+                ("Athens/Villa (12)/notes.txt", b"n"),
+                // Not an indexed subfolder, so not in the manifest.
+                ("Athens/Villa (12)/RAW/1.jpg", b"raw"),
+                (MANIFEST_JSON, &json),
+            ],
+        );
+        let set_id = insert_set(&pool, &zip_path, &[(1, "Villa")]).await;
+
+        let report = verify_set_in(&pool, set_id).await.unwrap();
+        assert!(report.ok, "{report:?}");
+        assert!(report.has_manifest);
+        assert_eq!(report.entries_checked, 4);
+    }
+
+    #[tokio::test]
+    async fn reports_missing_extra_and_corrupt_entries() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let zip_path = tmp.path().join("set.zip");
+        let manifest = SetManifest {
+            set_name: "s".to_string(),
+            created_at: String::new(),
+            properties: vec![ManifestProperty {
+                property_id: 1,
+                name: "Villa".to_string(),
+                city: "Athens".to_string(),
+                code: None,
+                codes: Vec::new(),
+                folder: "Athens/Villa/".to_string(),
+                images: vec![
+                    image("Athens/Villa/1.jpg", b"original bytes"),
+                    image("Athens/Villa/2.jpg", b"two"),
+                ],
+            }],
+        };
+        let json = serde_json::to_vec(&manifest).unwrap();
+        write_zip(
+            &zip_path,
+            &[
+                ("Athens/Villa/1.jpg", b"original bytes"),
+                ("Athens/Villa/3.jpg", b"unlisted"),
+                ("stray.txt", b"stray"),
+                (MANIFEST_JSON, &json),
+            ],
+        );
+        // Damage the stored bytes of 1.jpg so its CRC no longer matches.
+        let mut bytes = std::fs::read(&zip_path).unwrap();
+        let at = bytes
+            .windows(14)
+            .position(|window| window == b"original bytes")
+            .unwrap();
+        bytes[at] = b'O';
+        std::fs::write(&zip_path, bytes).unwrap();
+        let set_id = insert_set(&pool, &zip_path, &[(1, "Villa"), (2, "Loft")]).await;
+
+        let report = verify_set_in(&pool, set_id).await.unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.missing_entries, vec!["Athens/Villa/2.jpg"]);
+        assert_eq!(
+            report.extra_entries,
+            vec!["Athens/Villa/3.jpg", "stray.txt"]
+        );
+        assert_eq!(report.corrupt_entries.len(), 1);
+        assert_eq!(report.corrupt_entries[0].path, "Athens/Villa/1.jpg");
+        assert_eq!(report.missing_properties, vec!["Athens/Loft"]);
+    }
+
+    #[tokio::test]
+    async fn truncated_zip_cannot_be_opened() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let zip_path = tmp.path().join("set.zip");
+        write_zip(&zip_path, &[("Athens/Villa/1.jpg", b"one")]);
+        let bytes = std::fs::read(&zip_path).unwrap();
+        std::fs::write(&zip_path, &bytes[..bytes.len() / 2]).unwrap();
+        let set_id = insert_set(&pool, &zip_path, &[(1, "Villa")]).await;
+
+        let report = verify_set_in(&pool, set_id).await.unwrap();
+        assert!(!report.ok);
+        assert!(report.open_error.is_some());
+    }
+}
@@ -1,93 +1,33 @@
 //! Set commands — completing a property batch into a zip + DB rows,
 //! and the read/list/delete/open helpers used by the Sets page.
-//! Also owns the `add_directory_to_zip` helper since it's only used here.
+//! The ZIP itself is written by `set_zip`.
 //!
 //! Extracted from database.rs in the database-module split.
 
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 use sqlx::Row;
-
-use crate::database::types::{CommandResult, CompleteSetResult, Property, Set, SetProperty};
-use crate::database::{folder_path_to_pathbuf, get_base_path_for_status, get_database_pool};
-
-/// Recursively add a directory to a ZIP file. Used by `complete_set`.
-fn add_directory_to_zip<W: std::io::Write + std::io::Seek>(
-    zip: &mut zip::ZipWriter<W>,
-    dir_path: &std::path::Path,
-    base_path: &std::path::Path,
-    options: zip::write::SimpleFileOptions,
-) -> Result<(), String> {
-    use walkdir::WalkDir;
-
-    for entry in WalkDir::new(dir_path) {
-        let entry = entry.map_err(|e| format!("Failed to walk directory: {}", e))?;
-        let path = entry.path();
-
-        let relative_path = path
-            .strip_prefix(base_path)
-            .map_err(|e| format!("Failed to strip prefix: {}", e))?;
-
-        let relative_path_str = relative_path
-            .to_str()
-            .ok_or("Invalid path encoding")?
-            .replace('\\', "/");
-
-        if path.is_dir() {
-            if !relative_path_str.is_empty() {
-                let dir_name = format!("{}/", relative_path_str);
-                zip.add_directory(&dir_name, options)
-                    .map_err(|e| format!("Failed to add directory to ZIP: {}", e))?;
-            }
-        } else {
-            zip.start_file(&relative_path_str, options)
-                .map_err(|e| format!("Failed to start file in ZIP: {}", e))?;
-
-            let file_content = std::fs::read(path)
-                .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
-
-            use std::io::Write;
-            zip.write_all(&file_content)
-                .map_err(|e| format!("Failed to write file to ZIP: {}", e))?;
-        }
-    }
-
-    Ok(())
-}
-
-/// Complete a set: ZIP all DONE properties with codes, move to ARCHIVE,
-/// move properties without codes to NOT_FOUND
-#[tauri::command]
-pub async fn complete_set(app: tauri::AppHandle) -> Result<CompleteSetResult, String> {
-    let pool = get_database_pool(&app)?;
-
-    // Load config
-    let config = crate::config::get_cached_config(&app)
-        .await
-        .map_err(|e| e.to_string())?
-        .ok_or("App configuration not found")?;
-
-    // Validate sets folder path is configured
-    if config.sets_folder_path.is_empty() {
-        return Err(
-            "Sets folder path is not configured. Please configure it in Settings.".to_string(),
-        );
-    }
-
-    let sets_folder = PathBuf::from(&config.sets_folder_path);
-    let sets_folder_clone = sets_folder.clone();
-    tokio::task::spawn_blocking(move || {
-        if !sets_folder_clone.exists() {
-            std::fs::create_dir_all(&sets_folder_clone)
-                .map_err(|e| format!("Failed to create sets folder: {}", e))?;
-        }
-        Ok::<_, String>(())
-    })
-    .await
-    .map_err(|e| format!("Task join error: {e}"))??;
-
-    // Get all DONE properties
-    let done_properties: Vec<Property> = sqlx::query_as::<
+use tauri::Manager;
+
+use crate::database::codes::load_property_codes;
+use crate::database::manifest::{
+    build_set_manifest, manifest_csv, manifest_json, MANIFEST_CSV, MANIFEST_JSON,
+};
+use crate::database::set_zip::{emit_zip_progress, write_set_zip, SetZipState};
+use crate::database::transfer::emit_move_progress;
+use crate::database::types::{
+    CommandResult, CompleteSetResult, Property, Set, SetCompletionOptions, SetCompletionPreview,
+    SetPreviewProperty, SetProperty,
+};
+use crate::database::{
+    folder_path_to_pathbuf, get_base_path_for_status, get_database_pool,
+    run_status_change_with_progress, StatusChange,
+};
+
+// Every DONE property that is not in the trash.
+async fn load_done_properties(pool: &sqlx::SqlitePool) -> Result<Vec<Property>, String> {
+    Ok(sqlx::query_as::<
         _,
         (
             i64,
@@ -102,7 +42,7 @@ pub async fn complete_set(app: tauri::AppHandle) -> Result<CompleteSetResult, St
         ),
     >(
         "SELECT id, name, city, status, folder_path, notes, code, created_at, updated_at
-         FROM properties WHERE status = 'DONE'",
+         FROM properties WHERE status = 'DONE' AND deleted_at IS NULL",
     )
     .fetch_all(pool)
     .await
@@ -117,6 +57,7 @@ pub async fn complete_set(app: tauri::AppHandle) -> Result<CompleteSetResult, St
             folder_path,
             notes,
             code,
+            tags: Vec::new(),
             created_at: chrono::DateTime::from_timestamp_millis(created_at)
                 .unwrap_or_else(chrono::Utc::now),
             updated_at: chrono::DateTime::from_timestamp_millis(updated_at)
@@ -124,12 +65,186 @@ pub async fn complete_set(app: tauri::AppHandle) -> Result<CompleteSetResult, St
             completed: None,
         },
     )
-    .collect();
+    .collect())
+}
 
-    // Separate properties by whether they have a code
-    let (with_code, without_code): (Vec<_>, Vec<_>) = done_properties
+/// DONE properties split the way `complete_set` handles them.
+#[derive(Default)]
+struct SetSelection {
+    with_code: Vec<Property>,
+    to_not_found: Vec<Property>,
+    left_in_done: Vec<Property>,
+    not_eligible: Vec<String>,
+}
+
+// Narrow the DONE properties down to the requested ones and split them by
+// whether they have a code.
+fn select_properties(done: Vec<Property>, options: &SetCompletionOptions) -> SetSelection {
+    let mut selection = SetSelection::default();
+    let done = match &options.property_ids {
+        Some(ids) => {
+            let requested: HashSet<i64> = ids.iter().copied().collect();
+            let found: HashSet<i64> = done.iter().filter_map(|p| p.id).collect();
+            selection.not_eligible = ids
+                .iter()
+                .filter(|id| !found.contains(id))
+                .map(|id| format!("Property {id} is not a DONE property"))
+                .collect();
+            done.into_iter()
+                .filter(|p| p.id.is_some_and(|id| requested.contains(&id)))
+                .collect()
+        }
+        None => done,
+    };
+
+    let (with_code, without_code): (Vec<_>, Vec<_>) = done
         .into_iter()
         .partition(|p| p.code.as_ref().is_some_and(|c| !c.is_empty()));
+    selection.with_code = with_code;
+    if options.keep_codeless_in_done {
+        selection.left_in_done = without_code;
+    } else {
+        selection.to_not_found = without_code;
+    }
+    selection
+}
+
+// File count and size of each property folder under `base_path`, or
+// `None` when the folder is missing. Blocking.
+fn folder_stats(base_path: &Path, folder_path: &str) -> Option<(usize, u64)> {
+    let dir = base_path.join(folder_path_to_pathbuf(folder_path));
+    if !dir.is_dir() {
+        return None;
+    }
+    Some(
+        walkdir::WalkDir::new(dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .fold((0, 0), |(count, bytes), entry| {
+                let len = entry.metadata().map_or(0, |m| m.len());
+                (count + 1, bytes + len)
+            }),
+    )
+}
+
+pub(super) async fn preview_set_completion_in(
+    pool: &sqlx::SqlitePool,
+    config: &crate::config::AppConfig,
+    options: &SetCompletionOptions,
+) -> Result<SetCompletionPreview, String> {
+    let selection = select_properties(load_done_properties(pool).await?, options);
+    let done_base_path = get_base_path_for_status(config, "DONE")?;
+
+    tokio::task::spawn_blocking(move || {
+        let describe = |properties: &[Property]| -> Vec<SetPreviewProperty> {
+            properties
+                .iter()
+                .filter_map(|p| {
+                    let stats = folder_stats(&done_base_path, &p.folder_path);
+                    let (file_count, total_bytes) = stats.unwrap_or_default();
+                    Some(SetPreviewProperty {
+                        property_id: p.id?,
+                        name: p.name.clone(),
+                        city: p.city.clone(),
+                        code: p.code.clone(),
+                        folder_path: p.folder_path.clone(),
+                        folder_exists: stats.is_some(),
+                        file_count,
+                        total_bytes,
+                    })
+                })
+                .collect()
+        };
+        let to_archive = describe(&selection.with_code);
+        SetCompletionPreview {
+            zip_file_count: to_archive.iter().map(|p| p.file_count).sum(),
+            zip_bytes: to_archive.iter().map(|p| p.total_bytes).sum(),
+            to_archive,
+            to_not_found: describe(&selection.to_not_found),
+            left_in_done: describe(&selection.left_in_done),
+            not_eligible: selection.not_eligible,
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))
+}
+
+/// Show what `complete_set` would zip, archive and move with `options`,
+/// without touching anything. `data` is a `SetCompletionPreview`.
+#[tauri::command]
+pub async fn preview_set_completion(
+    app: tauri::AppHandle,
+    options: Option<SetCompletionOptions>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let config = crate::config::get_cached_config(&app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    match preview_set_completion_in(pool, &config, &options.unwrap_or_default()).await {
+        Ok(preview) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::to_value(preview).map_err(|e| e.to_string())?),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+/// Complete a set: ZIP the DONE properties with codes and move them to
+/// ARCHIVE, and move those without codes to `NOT_FOUND`. `options` narrows
+/// this to chosen properties and can keep code-less ones in DONE; without
+/// it every DONE property is taken.
+#[tauri::command]
+pub async fn complete_set(
+    app: tauri::AppHandle,
+    options: Option<SetCompletionOptions>,
+) -> Result<CompleteSetResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    // Load config
+    let config = crate::config::get_cached_config(&app)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("App configuration not found")?;
+
+    // Validate sets folder path is configured
+    if config.sets_folder_path.is_empty() {
+        return Err(
+            "Sets folder path is not configured. Please configure it in Settings.".to_string(),
+        );
+    }
+
+    let sets_folder = PathBuf::from(&config.sets_folder_path);
+    let sets_folder_clone = sets_folder.clone();
+    tokio::task::spawn_blocking(move || {
+        if !sets_folder_clone.exists() {
+            std::fs::create_dir_all(&sets_folder_clone)
+                .map_err(|e| format!("Failed to create sets folder: {}", e))?;
+        }
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))??;
+
+    let options = options.unwrap_or_default();
+    if options.property_ids.as_ref().is_some_and(Vec::is_empty) {
+        return Err("No properties selected for the set.".to_string());
+    }
+    let SetSelection {
+        with_code,
+        to_not_found: without_code,
+        left_in_done,
+        not_eligible,
+    } = select_properties(load_done_properties(pool).await?, &options);
+    if !not_eligible.is_empty() {
+        return Err(format!("Cannot complete set: {}", not_eligible.join("; ")));
+    }
 
     if with_code.is_empty() {
         return Err("No DONE properties with codes found to create a set.".to_string());
@@ -143,160 +258,191 @@ pub async fn complete_set(app: tauri::AppHandle) -> Result<CompleteSetResult, St
 
     let done_base_path = get_base_path_for_status(&config, "DONE")?;
     {
+        // Cancellable until the ZIP is finished; nothing has been written to
+        // the database or moved yet, so a cancelled set leaves no trace.
+        let zip_state = app.state::<SetZipState>();
+        let run = zip_state.begin()?;
+        let cancel = run.token();
+        let manifest = build_set_manifest(
+            pool,
+            &set_name,
+            &done_base_path,
+            &with_code,
+            &cancel,
+            &mut emit_zip_progress(&app),
+        )
+        .await?;
+        let manifest_files = [
+            (MANIFEST_JSON, manifest_json(&manifest)?),
+            (MANIFEST_CSV, manifest_csv(&manifest)?),
+        ];
         let zip_path = zip_path.clone();
         let done_base_path = done_base_path.clone();
-        let with_code_ref: Vec<_> = with_code
+        let folders: Vec<_> = with_code
             .iter()
             .map(|p| (p.folder_path.clone(), p.city.clone()))
             .collect();
+        let mut on_progress = emit_zip_progress(&app);
         tokio::task::spawn_blocking(move || {
-            let file = std::fs::File::create(&zip_path)
-                .map_err(|e| format!("Failed to create ZIP file: {}", e))?;
-
-            let mut zip = zip::ZipWriter::new(file);
-            // Use Stored (no compression) instead of Deflated for speed
-            // Photos are already compressed (JPEG/PNG), so deflate provides minimal benefit
-            // but takes much longer. Stored mode is ~10x faster with minimal size increase.
-            let options = zip::write::SimpleFileOptions::default()
-                .compression_method(zip::CompressionMethod::Stored);
+            write_set_zip(
+                &zip_path,
+                &done_base_path,
+                &folders,
+                &manifest_files,
+                &cancel,
+                &mut on_progress,
+            )
+        })
+        .await
+        .map_err(|e| format!("Task join error: {e}"))??;
+    }
 
-            // Add each property to the ZIP
-            for (folder_path, city) in &with_code_ref {
-                let property_path = done_base_path.join(folder_path_to_pathbuf(folder_path));
+    let zip_path_str = zip_path.to_string_lossy().to_string();
+    let set_id = insert_set_records(pool, &set_name, &zip_path_str, &with_code).await?;
 
-                if property_path.exists() {
-                    let city_folder = format!("{}/", city);
-                    let _ = zip.add_directory(&city_folder, options);
+    // Properties with code -> ARCHIVE, without code -> NOT_FOUND. Each
+    // property's folder move is journaled and its row updated right after
+    // the move, so a failure or crash part-way leaves every property either
+    // fully moved or untouched. A failed move is reported and the rest carry
+    // on; the property stays in DONE.
+    let archive_base_path = get_base_path_for_status(&config, "ARCHIVE")?;
+    let not_found_base_path = get_base_path_for_status(&config, "NOT_FOUND")?;
 
-                    add_directory_to_zip(&mut zip, &property_path, &done_base_path, options)?;
-                }
-            }
+    let targets: Vec<(&Property, &str, PathBuf, PathBuf)> = with_code
+        .iter()
+        .map(|p| (p, "ARCHIVE", &archive_base_path))
+        .chain(
+            without_code
+                .iter()
+                .map(|p| (p, "NOT_FOUND", &not_found_base_path)),
+        )
+        .map(|(p, status, base)| {
+            let folder_path_buf = folder_path_to_pathbuf(&p.folder_path);
+            (
+                p,
+                status,
+                done_base_path.join(&folder_path_buf),
+                base.join(&folder_path_buf),
+            )
+        })
+        .collect();
 
-            zip.finish()
-                .map_err(|e| format!("Failed to finish ZIP file: {}", e))?;
-            Ok::<_, String>(())
+    let needs_move: Vec<bool> = {
+        let paths: Vec<_> = targets
+            .iter()
+            .map(|(_, _, old, new)| (old.clone(), new.clone()))
+            .collect();
+        tokio::task::spawn_blocking(move || {
+            paths
+                .iter()
+                .map(|(old, new)| old.exists() && old != new)
+                .collect()
         })
         .await
-        .map_err(|e| format!("Task join error: {e}"))??;
+        .map_err(|e| format!("Task join error: {e}"))?
+    };
+
+    let mut properties_archived = 0;
+    let mut properties_moved_to_not_found = 0;
+    let mut warnings = Vec::new();
+    let mut errors = Vec::new();
+    for ((property, new_status, old_path, new_path), needs_move) in
+        targets.into_iter().zip(needs_move)
+    {
+        let Some(property_id) = property.id else {
+            continue;
+        };
+        let change = StatusChange {
+            property_id,
+            old_status: property.status.clone(),
+            new_status: new_status.to_string(),
+            folder_path: property.folder_path.clone(),
+            folder_move: needs_move.then_some((old_path, new_path)),
+            source: "complete_set".to_string(),
+        };
+        match run_status_change_with_progress(pool, &change, emit_move_progress(&app, property_id))
+            .await
+        {
+            Ok(warning) => {
+                if new_status == "ARCHIVE" {
+                    properties_archived += 1;
+                } else {
+                    properties_moved_to_not_found += 1;
+                }
+                warnings.extend(warning);
+            }
+            Err(e) => errors.push(format!(
+                "Failed to move '{}' to {new_status}: {e}",
+                property.name
+            )),
+        }
     }
 
-    // Insert set record into database
-    let now_timestamp = chrono::Utc::now().timestamp_millis();
-    let zip_path_str = zip_path.to_string_lossy().to_string();
+    Ok(CompleteSetResult {
+        set_id,
+        set_name,
+        zip_path: zip_path_str,
+        properties_archived,
+        properties_moved_to_not_found,
+        properties_left_in_done: left_in_done.len(),
+        warnings,
+        errors,
+    })
+}
 
+/// Insert the `sets` row and a `set_properties` row per property, with a
+/// snapshot of every portal code, in one transaction. Returns the set id.
+async fn insert_set_records(
+    pool: &sqlx::SqlitePool,
+    set_name: &str,
+    zip_path: &str,
+    properties: &[Property],
+) -> Result<i64, String> {
+    let mut codes = Vec::with_capacity(properties.len());
+    for property in properties {
+        let property_codes = match property.id {
+            Some(id) => load_property_codes(pool, id).await?,
+            None => Vec::new(),
+        };
+        codes.push(serde_json::to_string(&property_codes).map_err(|e| e.to_string())?);
+    }
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {e}"))?;
     let set_id = sqlx::query(
         "INSERT INTO sets (name, zip_path, property_count, created_at) VALUES (?, ?, ?, ?)",
     )
-    .bind(&set_name)
-    .bind(&zip_path_str)
-    .bind(with_code.len() as i64)
-    .bind(now_timestamp)
-    .execute(pool)
+    .bind(set_name)
+    .bind(zip_path)
+    .bind(properties.len() as i64)
+    .bind(chrono::Utc::now().timestamp_millis())
+    .execute(&mut *tx)
     .await
     .map_err(|e| format!("Failed to insert set record: {}", e))?
     .last_insert_rowid();
 
-    // Insert set_properties records
-    for property in &with_code {
+    for (property, codes) in properties.iter().zip(codes) {
         sqlx::query(
-            "INSERT INTO set_properties (set_id, property_id, property_name, property_city, property_code)
-             VALUES (?, ?, ?, ?, ?)"
+            "INSERT INTO set_properties (set_id, property_id, property_name, property_city, property_code, property_codes)
+             VALUES (?, ?, ?, ?, ?, ?)"
         )
         .bind(set_id)
         .bind(property.id)
         .bind(&property.name)
         .bind(&property.city)
         .bind(&property.code)
-        .execute(pool)
+        .bind(codes)
+        .execute(&mut *tx)
         .await
         .map_err(|e| format!("Failed to insert set_property record: {}", e))?;
     }
 
-    // Update DB statuses for properties with code -> ARCHIVE
-    let archive_base_path = get_base_path_for_status(&config, "ARCHIVE")?;
-    let properties_archived = with_code.len();
-    for property in &with_code {
-        if let Some(property_id) = property.id {
-            let now_ts = chrono::Utc::now().timestamp_millis();
-            sqlx::query("UPDATE properties SET status = 'ARCHIVE', updated_at = ? WHERE id = ?")
-                .bind(now_ts)
-                .bind(property_id)
-                .execute(pool)
-                .await
-                .map_err(|e| format!("Failed to update property status: {}", e))?;
-        }
-    }
-
-    // Update DB statuses for properties without code -> NOT_FOUND
-    let not_found_base_path = get_base_path_for_status(&config, "NOT_FOUND")?;
-    let properties_moved_to_not_found = without_code.len();
-    for property in &without_code {
-        if let Some(property_id) = property.id {
-            let now_ts = chrono::Utc::now().timestamp_millis();
-            sqlx::query("UPDATE properties SET status = 'NOT_FOUND', updated_at = ? WHERE id = ?")
-                .bind(now_ts)
-                .bind(property_id)
-                .execute(pool)
-                .await
-                .map_err(|e| format!("Failed to update property status: {}", e))?;
-        }
-    }
-
-    // Move folders on disk (blocking I/O on dedicated thread)
-    {
-        let with_code_paths: Vec<_> = with_code.iter().map(|p| p.folder_path.clone()).collect();
-        let without_code_paths: Vec<_> =
-            without_code.iter().map(|p| p.folder_path.clone()).collect();
-        let done_base = done_base_path.clone();
-        let archive_base = archive_base_path.clone();
-        let not_found_base = not_found_base_path.clone();
-
-        tokio::task::spawn_blocking(move || {
-            // Move properties with code to ARCHIVE
-            for fp in &with_code_paths {
-                let folder_path_buf = folder_path_to_pathbuf(fp);
-                let old_path = done_base.join(&folder_path_buf);
-                let new_path = archive_base.join(&folder_path_buf);
-
-                if old_path.exists() && old_path != new_path {
-                    if let Some(parent) = new_path.parent() {
-                        std::fs::create_dir_all(parent)
-                            .map_err(|e| format!("Failed to create parent directory: {}", e))?;
-                    }
-                    std::fs::rename(&old_path, &new_path)
-                        .map_err(|e| format!("Failed to move folder to archive: {}", e))?;
-                }
-            }
-
-            // Move properties without code to NOT_FOUND
-            for fp in &without_code_paths {
-                let folder_path_buf = folder_path_to_pathbuf(fp);
-                let old_path = done_base.join(&folder_path_buf);
-                let new_path = not_found_base.join(&folder_path_buf);
-
-                if old_path.exists() && old_path != new_path {
-                    if let Some(parent) = new_path.parent() {
-                        std::fs::create_dir_all(parent)
-                            .map_err(|e| format!("Failed to create parent directory: {}", e))?;
-                    }
-                    std::fs::rename(&old_path, &new_path)
-                        .map_err(|e| format!("Failed to move folder to not found: {}", e))?;
-                }
-            }
-
-            Ok::<_, String>(())
-        })
+    tx.commit()
         .await
-        .map_err(|e| format!("Task join error: {e}"))??;
-    }
-
-    Ok(CompleteSetResult {
-        set_id,
-        set_name,
-        zip_path: zip_path_str,
-        properties_archived,
-        properties_moved_to_not_found,
-    })
+        .map_err(|e| format!("Failed to save set: {e}"))?;
+    Ok(set_id)
 }
 
 /// Get all sets
@@ -304,21 +450,25 @@ pub async fn complete_set(app: tauri::AppHandle) -> Result<CompleteSetResult, St
 pub async fn get_sets(app: tauri::AppHandle) -> Result<CommandResult, String> {
     let pool = get_database_pool(&app)?;
 
-    let sets: Vec<Set> = sqlx::query_as::<_, (i64, String, String, i64, i64)>(
-        "SELECT id, name, zip_path, property_count, created_at FROM sets ORDER BY created_at DESC",
+    let sets: Vec<Set> = sqlx::query_as::<_, (i64, String, String, i64, i64, Option<i64>)>(
+        "SELECT id, name, zip_path, property_count, created_at, reverted_at
+         FROM sets ORDER BY created_at DESC",
     )
     .fetch_all(pool)
     .await
     .map_err(|e| format!("Failed to fetch sets: {}", e))?
     .into_iter()
-    .map(|(id, name, zip_path, property_count, created_at)| Set {
-        id: Some(id),
-        name,
-        zip_path,
-        property_count,
-        created_at: chrono::DateTime::from_timestamp_millis(created_at)
-            .unwrap_or_else(chrono::Utc::now),
-    })
+    .map(
+        |(id, name, zip_path, property_count, created_at, reverted_at)| Set {
+            id: Some(id),
+            name,
+            zip_path,
+            property_count,
+            created_at: chrono::DateTime::from_timestamp_millis(created_at)
+                .unwrap_or_else(chrono::Utc::now),
+            reverted_at: reverted_at.and_then(chrono::DateTime::from_timestamp_millis),
+        },
+    )
     .collect();
 
     Ok(CommandResult {
@@ -336,27 +486,31 @@ pub async fn get_set_properties(
 ) -> Result<CommandResult, String> {
     let pool = get_database_pool(&app)?;
 
-    let set_properties: Vec<SetProperty> =
-        sqlx::query_as::<_, (i64, i64, Option<i64>, String, String, Option<String>)>(
-            "SELECT id, set_id, property_id, property_name, property_city, property_code
+    let set_properties: Vec<SetProperty> = sqlx::query(
+        "SELECT id, set_id, property_id, property_name, property_city, property_code, property_codes
          FROM set_properties WHERE set_id = ?",
-        )
-        .bind(set_id)
-        .fetch_all(pool)
-        .await
-        .map_err(|e| format!("Failed to fetch set properties: {}", e))?
-        .into_iter()
-        .map(
-            |(id, set_id, property_id, property_name, property_city, property_code)| SetProperty {
-                id: Some(id),
-                set_id,
-                property_id,
-                property_name,
-                property_city,
-                property_code,
-            },
-        )
-        .collect();
+    )
+    .bind(set_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch set properties: {}", e))?
+    .into_iter()
+    .map(|row| {
+        // JSON snapshot of every portal code; absent on rows from old builds
+        let property_codes: Option<String> = row.get("property_codes");
+        SetProperty {
+            id: Some(row.get("id")),
+            set_id: row.get("set_id"),
+            property_id: row.get("property_id"),
+            property_name: row.get("property_name"),
+            property_city: row.get("property_city"),
+            property_code: row.get("property_code"),
+            property_codes: property_codes
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default(),
+        }
+    })
+    .collect();
 
     Ok(CommandResult {
         success: true,
@@ -472,3 +626,107 @@ pub async fn delete_set(
         data: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+    use std::fs;
+
+    async fn id_of(pool: &sqlx::SqlitePool, name: &str) -> i64 {
+        sqlx::query_scalar("SELECT id FROM properties WHERE name = ?")
+            .bind(name)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn preview_honours_selection_and_keeps_codeless() {
+        let pool = setup_test_db().await;
+        for (name, status, code) in [
+            ("Villa", "DONE", Some("1")),
+            ("Loft", "DONE", Some("2")),
+            ("Hut", "DONE", None),
+            ("Barn", "NEW", Some("3")),
+        ] {
+            add_property_to_database(&pool, name, "Athens", status, name, code)
+                .await
+                .unwrap();
+        }
+        let tmp = tempfile::tempdir().unwrap();
+        let villa = tmp.path().join("DONE/Athens/Villa");
+        fs::create_dir_all(villa.join("INTERNET")).unwrap();
+        fs::write(villa.join("1.jpg"), b"one").unwrap();
+        fs::write(villa.join("INTERNET/1.jpg"), b"internet").unwrap();
+        let config = AppConfig {
+            done_folder_path: tmp.path().join("DONE").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        };
+
+        let everything =
+            preview_set_completion_in(&pool, &config, &SetCompletionOptions::default())
+                .await
+                .unwrap();
+        assert_eq!(everything.to_archive.len(), 2);
+        assert_eq!(everything.to_not_found.len(), 1);
+        assert!(everything.left_in_done.is_empty());
+
+        let barn = id_of(&pool, "Barn").await;
+        let options = SetCompletionOptions {
+            property_ids: Some(vec![
+                id_of(&pool, "Villa").await,
+                id_of(&pool, "Hut").await,
+                barn,
+            ]),
+            keep_codeless_in_done: true,
+        };
+        let preview = preview_set_completion_in(&pool, &config, &options)
+            .await
+            .unwrap();
+        assert_eq!(preview.to_archive.len(), 1);
+        let villa = &preview.to_archive[0];
+        assert!(villa.folder_exists);
+        assert_eq!((villa.file_count, villa.total_bytes), (2, 11));
+        assert_eq!((preview.zip_file_count, preview.zip_bytes), (2, 11));
+        assert!(preview.to_not_found.is_empty());
+        assert_eq!(preview.left_in_done[0].name, "Hut");
+        assert!(!preview.left_in_done[0].folder_exists);
+        assert_eq!(
+            preview.not_eligible,
+            vec![format!("Property {barn} is not a DONE property")]
+        );
+    }
+
+    #[tokio::test]
+    async fn set_records_are_written_together() {
+        let pool = setup_test_db().await;
+        for (name, code) in [("Villa", "1"), ("Loft", "2")] {
+            add_property_to_database(&pool, name, "Athens", "DONE", name, Some(code))
+                .await
+                .unwrap();
+        }
+        let done = load_done_properties(&pool).await.unwrap();
+
+        let set_id = insert_set_records(&pool, "Done - test", "/tmp/set.zip", &done)
+            .await
+            .unwrap();
+
+        let count: i64 = sqlx::query_scalar("SELECT property_count FROM sets WHERE id = ?")
+            .bind(set_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+        let codes: Vec<String> = sqlx::query_scalar(
+            "SELECT property_codes FROM set_properties WHERE set_id = ? ORDER BY property_name",
+        )
+        .bind(set_id)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(codes.len(), 2);
+        assert!(codes[0].contains("\"2\""));
+    }
+}
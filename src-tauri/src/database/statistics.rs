@@ -0,0 +1,433 @@
+//! Throughput reports: how many properties were shot, delivered and
+//! archived per week and per city, how long they took to reach DONE, and
+//! how many images sit in each workflow folder.
+//!
+//! "Shot" is a property row created in the range, "delivered" a row in
+//! `set_properties` whose set was completed in the range, and "archived" a
+//! `status_change` event to ARCHIVE in the range. Trashed properties are
+//! left out of everything except deliveries, which are history.
+
+use std::collections::{BTreeMap, HashMap};
+
+use rayon::prelude::*;
+use sqlx::{Row, SqlitePool};
+
+use crate::config::AppConfig;
+use crate::database::catalogue::count_property_images;
+use crate::database::get_database_pool;
+use crate::database::types::{
+    CityThroughput, CommandResult, ImageCounts, Statistics, StatusCount, Throughput,
+    WeeklyThroughput,
+};
+
+const WEEK_MS: i64 = 7 * 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy)]
+enum Flow {
+    Shot,
+    Delivered,
+    Archived,
+}
+
+/// One shot, delivery or archive: when, where and which.
+struct Activity {
+    at: i64,
+    city: String,
+    flow: Flow,
+}
+
+fn add_flow(throughput: &mut Throughput, flow: Flow) {
+    match flow {
+        Flow::Shot => throughput.shot += 1,
+        Flow::Delivered => throughput.delivered += 1,
+        Flow::Archived => throughput.archived += 1,
+    }
+}
+
+fn add_images(total: &mut ImageCounts, counts: ImageCounts) {
+    total.originals += counts.originals;
+    total.internet += counts.internet;
+    total.internet_aggelia += counts.internet_aggelia;
+    total.watermark += counts.watermark;
+    total.watermark_aggelia += counts.watermark_aggelia;
+}
+
+/// Start of the UTC week (Monday 00:00) containing `at`, in milliseconds.
+const fn week_start(at: i64) -> i64 {
+    // The epoch was a Thursday, so weeks counted from it start on Thursday;
+    // shift by three days to land on Monday.
+    const MONDAY_OFFSET: i64 = 3 * 24 * 60 * 60 * 1000;
+    (at + MONDAY_OFFSET).div_euclid(WEEK_MS) * WEEK_MS - MONDAY_OFFSET
+}
+
+/// `?`-bound range condition on `column`, for `from` and `to` as given.
+fn range_condition(column: &str, from: Option<i64>, to: Option<i64>) -> String {
+    let mut conditions = vec!["1 = 1".to_string()];
+    if from.is_some() {
+        conditions.push(format!("{column} >= ?"));
+    }
+    if to.is_some() {
+        conditions.push(format!("{column} <= ?"));
+    }
+    conditions.join(" AND ")
+}
+
+async fn load_activities(
+    pool: &SqlitePool,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Vec<Activity>, String> {
+    let queries = [
+        (
+            Flow::Shot,
+            format!(
+                "SELECT created_at AS at, city FROM properties
+                 WHERE deleted_at IS NULL AND {}",
+                range_condition("created_at", from, to)
+            ),
+        ),
+        (
+            Flow::Delivered,
+            format!(
+                "SELECT s.created_at AS at, sp.property_city AS city
+                 FROM set_properties sp JOIN sets s ON s.id = sp.set_id
+                 WHERE {}",
+                range_condition("s.created_at", from, to)
+            ),
+        ),
+        (
+            Flow::Archived,
+            format!(
+                "SELECT e.created_at AS at, p.city FROM property_events e
+                 JOIN properties p ON p.id = e.property_id
+                 WHERE e.event_type = 'status_change' AND e.new_value = 'ARCHIVE'
+                   AND p.deleted_at IS NULL AND {}",
+                range_condition("e.created_at", from, to)
+            ),
+        ),
+    ];
+
+    let mut activities = Vec::new();
+    for (flow, sql) in queries {
+        let mut query = sqlx::query(&sql);
+        for bound in [from, to].into_iter().flatten() {
+            query = query.bind(bound);
+        }
+        let rows = query
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("Failed to load statistics: {e}"))?;
+        activities.extend(rows.into_iter().map(|row| Activity {
+            at: row.get("at"),
+            city: row.get("city"),
+            flow,
+        }));
+    }
+    Ok(activities)
+}
+
+/// Per-week, per-city and overall throughput. Weeks run from the first to
+/// the last with activity; cities are sorted busiest first, then by name.
+fn tally(activities: &[Activity]) -> (Throughput, Vec<WeeklyThroughput>, Vec<CityThroughput>) {
+    let mut total = Throughput::default();
+    let mut weeks: BTreeMap<i64, Throughput> = BTreeMap::new();
+    let mut cities: HashMap<&str, Throughput> = HashMap::new();
+    for activity in activities {
+        add_flow(&mut total, activity.flow);
+        add_flow(
+            weeks.entry(week_start(activity.at)).or_default(),
+            activity.flow,
+        );
+        add_flow(cities.entry(&activity.city).or_default(), activity.flow);
+    }
+
+    let mut weekly = Vec::new();
+    if let (Some(&first), Some(&last)) = (weeks.keys().next(), weeks.keys().next_back()) {
+        let mut week = first;
+        while week <= last {
+            weekly.push(WeeklyThroughput {
+                week_start: week,
+                throughput: weeks.get(&week).copied().unwrap_or_default(),
+            });
+            week += WEEK_MS;
+        }
+    }
+
+    let mut by_city: Vec<CityThroughput> = cities
+        .into_iter()
+        .map(|(city, throughput)| CityThroughput {
+            city: city.to_string(),
+            throughput,
+        })
+        .collect();
+    by_city.sort_by(|a, b| {
+        let volume = |t: &Throughput| t.shot + t.delivered + t.archived;
+        volume(&b.throughput)
+            .cmp(&volume(&a.throughput))
+            .then_with(|| a.city.to_lowercase().cmp(&b.city.to_lowercase()))
+    });
+
+    (total, weekly, by_city)
+}
+
+/// Mean milliseconds from creation to the first DONE, over properties whose
+/// first DONE falls in the range, and how many there were.
+async fn time_to_done(
+    pool: &SqlitePool,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<(Option<f64>, usize), String> {
+    let sql = format!(
+        "SELECT created_at, done_at FROM (
+             SELECT p.created_at, MIN(e.created_at) AS done_at
+             FROM property_events e JOIN properties p ON p.id = e.property_id
+             WHERE e.event_type = 'status_change' AND e.new_value = 'DONE'
+               AND p.deleted_at IS NULL
+             GROUP BY p.id
+         ) WHERE {}",
+        range_condition("done_at", from, to)
+    );
+    let mut query = sqlx::query(&sql);
+    for bound in [from, to].into_iter().flatten() {
+        query = query.bind(bound);
+    }
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load statistics: {e}"))?;
+
+    let durations: Vec<i64> = rows
+        .iter()
+        .map(|row| row.get::<i64, _>("done_at") - row.get::<i64, _>("created_at"))
+        .collect();
+    if durations.is_empty() {
+        return Ok((None, 0));
+    }
+    #[allow(clippy::cast_precision_loss)] // Millisecond sums stay well below 2^53
+    let average = durations.iter().sum::<i64>() as f64 / durations.len() as f64;
+    Ok((Some(average), durations.len()))
+}
+
+pub(super) async fn get_statistics_in(
+    pool: &SqlitePool,
+    config: AppConfig,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Statistics, String> {
+    let activities = load_activities(pool, from, to).await?;
+    let (total, weeks, cities) = tally(&activities);
+    let (average_time_to_done, done_count) = time_to_done(pool, from, to).await?;
+
+    let sql = format!(
+        "SELECT status, folder_path FROM properties WHERE deleted_at IS NULL AND {}",
+        range_condition("created_at", from, to)
+    );
+    let mut query = sqlx::query(&sql);
+    for bound in [from, to].into_iter().flatten() {
+        query = query.bind(bound);
+    }
+    let shot: Vec<(String, String)> = query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to load statistics: {e}"))?
+        .into_iter()
+        .map(|row| (row.get("status"), row.get("folder_path")))
+        .collect();
+
+    let image_counts: Vec<(String, Option<ImageCounts>)> = tokio::task::spawn_blocking(move || {
+        shot.into_par_iter()
+            .map(|(status, folder_path)| {
+                let counts = count_property_images(&config, &status, &folder_path);
+                (status, counts)
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Task join error: {e}"))?;
+
+    let mut by_status: BTreeMap<String, StatusCount> = BTreeMap::new();
+    for (status, counts) in image_counts {
+        let entry = by_status
+            .entry(status.clone())
+            .or_insert_with(|| StatusCount {
+                status,
+                count: 0,
+                image_counts: ImageCounts::default(),
+            });
+        entry.count += 1;
+        if let Some(counts) = counts {
+            add_images(&mut entry.image_counts, counts);
+        }
+    }
+    let statuses = by_status.into_values().collect();
+
+    Ok(Statistics {
+        from,
+        to,
+        total,
+        weeks,
+        cities,
+        statuses,
+        average_time_to_done,
+        done_count,
+    })
+}
+
+/// Throughput report for properties over `from..=to` (milliseconds since
+/// the epoch; either bound may be omitted). Counting images reads every
+/// property folder in the range, so a wide range takes a while.
+#[tauri::command]
+pub async fn get_statistics(
+    app: tauri::AppHandle,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+    let config = crate::config::get_cached_config(&app)
+        .await?
+        .ok_or("App configuration not found")?;
+
+    match get_statistics_in(pool, config, from, to).await {
+        Ok(statistics) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::to_value(statistics).map_err(|e| e.to_string())?),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+
+    /// Monday 2026-10-05 00:00 UTC.
+    const MONDAY: i64 = 1_791_158_400_000;
+    const DAY: i64 = 24 * 60 * 60 * 1000;
+
+    #[test]
+    fn weeks_start_on_monday() {
+        assert_eq!(week_start(MONDAY), MONDAY);
+        assert_eq!(week_start(MONDAY + 6 * DAY + 1), MONDAY);
+        assert_eq!(week_start(MONDAY - 1), MONDAY - WEEK_MS);
+        assert_eq!(week_start(0), -3 * DAY);
+    }
+
+    #[tokio::test]
+    async fn statistics_count_shots_deliveries_and_archives() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        for (name, city) in [("Villa", "Athens"), ("Loft", "Athens"), ("Flat", "Rome")] {
+            add_property_to_database(&pool, name, city, "NEW", name, None)
+                .await
+                .unwrap();
+        }
+        // Villa shot on Monday, done two days later and archived the next
+        // week; Loft and Flat shot the week after.
+        for (name, created_at) in [
+            ("Villa", MONDAY),
+            ("Loft", MONDAY + WEEK_MS),
+            ("Flat", MONDAY + WEEK_MS + DAY),
+        ] {
+            sqlx::query("UPDATE properties SET created_at = ? WHERE name = ?")
+                .bind(created_at)
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("UPDATE properties SET status = 'ARCHIVE' WHERE name = 'Villa'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (new_value, at) in [("DONE", MONDAY + 2 * DAY), ("ARCHIVE", MONDAY + WEEK_MS)] {
+            sqlx::query(
+                "INSERT INTO property_events (property_id, event_type, new_value, source, created_at)
+                 SELECT id, 'status_change', ?, 'test', ? FROM properties WHERE name = 'Villa'",
+            )
+            .bind(new_value)
+            .bind(at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO sets (id, name, zip_path, property_count, created_at)
+             VALUES (1, 'Set 1', 'set.zip', 1, ?)",
+        )
+        .bind(MONDAY + 3 * DAY)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO set_properties (set_id, property_name, property_city)
+             VALUES (1, 'Villa', 'Athens')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let loft = tmp.path().join("Athens").join("Loft");
+        std::fs::create_dir_all(loft.join("INTERNET")).unwrap();
+        std::fs::write(loft.join("1.jpg"), b"").unwrap();
+        std::fs::write(loft.join("2.jpg"), b"").unwrap();
+        std::fs::write(loft.join("INTERNET").join("1.jpg"), b"").unwrap();
+
+        let villa = tmp.path().join("ARCHIVE").join("Athens").join("Villa");
+        std::fs::create_dir_all(&villa).unwrap();
+        std::fs::write(villa.join("1.jpg"), b"").unwrap();
+
+        let config = AppConfig {
+            new_folder_path: tmp.path().to_string_lossy().to_string(),
+            archive_folder_path: tmp.path().join("ARCHIVE").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        };
+        let stats = get_statistics_in(&pool, config.clone(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            stats.total,
+            Throughput {
+                shot: 3,
+                delivered: 1,
+                archived: 1
+            }
+        );
+        assert_eq!(stats.weeks.len(), 2);
+        assert_eq!(stats.weeks[0].week_start, MONDAY);
+        assert_eq!(stats.weeks[0].throughput.delivered, 1);
+        assert_eq!(stats.weeks[1].throughput.shot, 2);
+        assert_eq!(stats.cities[0].city, "Athens");
+        assert_eq!(stats.cities[0].throughput.shot, 2);
+        // Two days.
+        assert_eq!(stats.average_time_to_done, Some(172_800_000.0));
+        assert_eq!(stats.done_count, 1);
+        // Images are counted per workflow folder.
+        let [archive, new] = stats.statuses.as_slice() else {
+            panic!("expected ARCHIVE and NEW: {:?}", stats.statuses);
+        };
+        assert_eq!(
+            (archive.status.as_str(), new.status.as_str()),
+            ("ARCHIVE", "NEW")
+        );
+        assert_eq!((archive.count, archive.image_counts.originals), (1, 1));
+        assert_eq!((new.count, new.image_counts.originals), (2, 2));
+        assert_eq!(new.image_counts.internet, 1);
+
+        // The second week alone: only the two new shoots and the archive.
+        let stats = get_statistics_in(&pool, config, Some(MONDAY + WEEK_MS), None)
+            .await
+            .unwrap();
+        assert_eq!(stats.total.shot, 2);
+        assert_eq!(stats.total.delivered, 0);
+        assert_eq!(stats.total.archived, 1);
+        assert_eq!(stats.done_count, 0);
+        assert_eq!(stats.statuses.len(), 1);
+        assert_eq!(stats.statuses[0].count, 2);
+    }
+}
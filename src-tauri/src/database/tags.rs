@@ -0,0 +1,409 @@
+//! Free-form property tags ("rush", "reshoot", "agent: Maria").
+//!
+//! Tags live in `tags` and are attached through the `property_tags`
+//! junction. `tags.usage_count` is kept by triggers on `property_tags`
+//! (see migration 9), so it always equals the number of tagged properties
+//! and drives autocomplete ordering the same way `cities.usage_count` does.
+
+use std::collections::HashMap;
+
+use sqlx::{Row, SqlitePool};
+
+use crate::database::get_database_pool;
+use crate::database::types::{CommandResult, Property, Tag};
+
+/// Trim and collapse inner whitespace so "  agent:   Maria " and
+/// "agent: Maria" are the same tag. Case is matched by the NOCASE column.
+fn normalize_tag_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn normalize_tag_names(names: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = names
+        .iter()
+        .map(|name| normalize_tag_name(name))
+        .filter(|name| !name.is_empty())
+        .collect();
+    normalized.sort_by_key(|name| name.to_lowercase());
+    normalized.dedup_by_key(|name| name.to_lowercase());
+    normalized
+}
+
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+fn row_to_tag(row: &sqlx::sqlite::SqliteRow) -> Tag {
+    Tag {
+        id: row.get("id"),
+        name: row.get("name"),
+        usage_count: row.get("usage_count"),
+        created_at: chrono::DateTime::from_timestamp_millis(row.get("created_at"))
+            .unwrap_or_else(chrono::Utc::now),
+    }
+}
+
+/// Fill `Property::tags` for every property in `properties` with one query.
+pub(super) async fn attach_tags(
+    pool: &SqlitePool,
+    properties: &mut [Property],
+) -> Result<(), String> {
+    let ids: Vec<i64> = properties.iter().filter_map(|p| p.id).collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let sql = format!(
+        "SELECT pt.property_id, t.name FROM property_tags pt
+         JOIN tags t ON t.id = pt.tag_id
+         WHERE pt.property_id IN ({})
+         ORDER BY t.name COLLATE NOCASE",
+        placeholders(ids.len())
+    );
+    let mut query = sqlx::query(&sql);
+    for id in &ids {
+        query = query.bind(id);
+    }
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch property tags: {e}"))?;
+
+    let mut by_property: HashMap<i64, Vec<String>> = HashMap::new();
+    for row in rows {
+        by_property
+            .entry(row.get("property_id"))
+            .or_default()
+            .push(row.get("name"));
+    }
+    for property in properties.iter_mut() {
+        if let Some(tags) = property.id.and_then(|id| by_property.remove(&id)) {
+            property.tags = tags;
+        }
+    }
+
+    Ok(())
+}
+
+/// SQL condition (and its bind values) restricting `properties` to rows
+/// carrying every tag in `tags`. `None` when there is nothing to filter on.
+pub(super) fn has_all_tags_condition(tags: &[String]) -> Option<(String, Vec<String>)> {
+    let tags = normalize_tag_names(tags);
+    if tags.is_empty() {
+        return None;
+    }
+    let condition = format!(
+        "id IN (SELECT pt.property_id FROM property_tags pt
+                JOIN tags t ON t.id = pt.tag_id
+                WHERE t.name IN ({})
+                GROUP BY pt.property_id
+                HAVING COUNT(*) = {})",
+        placeholders(tags.len()),
+        tags.len()
+    );
+    Some((condition, tags))
+}
+
+/// Attach every tag in `tags` to every property in `property_ids`, creating
+/// tags that do not exist yet. Returns the number of new links.
+pub(super) async fn add_tags(
+    pool: &SqlitePool,
+    property_ids: &[i64],
+    tags: &[String],
+) -> Result<u64, String> {
+    let tags = normalize_tag_names(tags);
+    if tags.is_empty() || property_ids.is_empty() {
+        return Ok(0);
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("Failed to start transaction: {e}"))?;
+
+    let mut added = 0;
+    for tag in &tags {
+        sqlx::query("INSERT OR IGNORE INTO tags (name, usage_count, created_at) VALUES (?, 0, ?)")
+            .bind(tag)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to create tag '{tag}': {e}"))?;
+
+        for property_id in property_ids {
+            added += sqlx::query(
+                "INSERT OR IGNORE INTO property_tags (property_id, tag_id, created_at)
+                 SELECT p.id, t.id, ? FROM properties p, tags t
+                 WHERE p.id = ? AND t.name = ?",
+            )
+            .bind(now)
+            .bind(property_id)
+            .bind(tag)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to tag property {property_id}: {e}"))?
+            .rows_affected();
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| format!("Failed to commit tags: {e}"))?;
+    Ok(added)
+}
+
+/// Detach every tag in `tags` from every property in `property_ids`.
+/// Returns the number of links removed. Tags themselves are kept.
+pub(super) async fn remove_tags(
+    pool: &SqlitePool,
+    property_ids: &[i64],
+    tags: &[String],
+) -> Result<u64, String> {
+    let tags = normalize_tag_names(tags);
+    if tags.is_empty() || property_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let sql = format!(
+        "DELETE FROM property_tags
+         WHERE property_id IN ({})
+           AND tag_id IN (SELECT id FROM tags WHERE name IN ({}))",
+        placeholders(property_ids.len()),
+        placeholders(tags.len())
+    );
+    let mut query = sqlx::query(&sql);
+    for id in property_ids {
+        query = query.bind(id);
+    }
+    for tag in &tags {
+        query = query.bind(tag);
+    }
+
+    query
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| format!("Failed to remove tags: {e}"))
+}
+
+// Tag operations for autocomplete
+#[tauri::command]
+pub async fn get_tags(app: tauri::AppHandle) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    let rows = sqlx::query("SELECT * FROM tags ORDER BY usage_count DESC, name ASC")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("Failed to fetch tags: {e}"))?;
+
+    let tags: Vec<Tag> = rows.iter().map(row_to_tag).collect();
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(tags).map_err(|e| e.to_string())?),
+    })
+}
+
+#[tauri::command]
+pub async fn search_tags(app: tauri::AppHandle, query: String) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    let search_pattern = format!("%{}%", normalize_tag_name(&query));
+
+    let rows = sqlx::query(
+        "SELECT * FROM tags WHERE name LIKE ? ORDER BY usage_count DESC, name ASC LIMIT 10",
+    )
+    .bind(&search_pattern)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to search tags: {e}"))?;
+
+    let tags: Vec<Tag> = rows.iter().map(row_to_tag).collect();
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(tags).map_err(|e| e.to_string())?),
+    })
+}
+
+/// Add `tags` to all of `property_ids`. `data` is the number of new links.
+#[tauri::command]
+pub async fn tag_properties(
+    app: tauri::AppHandle,
+    property_ids: Vec<i64>,
+    tags: Vec<String>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    match add_tags(pool, &property_ids, &tags).await {
+        Ok(added) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::json!(added)),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+/// Remove `tags` from all of `property_ids`. `data` is the number of links
+/// removed.
+#[tauri::command]
+pub async fn untag_properties(
+    app: tauri::AppHandle,
+    property_ids: Vec<i64>,
+    tags: Vec<String>,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    match remove_tags(pool, &property_ids, &tags).await {
+        Ok(removed) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: Some(serde_json::json!(removed)),
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(e),
+            data: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+
+    async fn property_ids(pool: &SqlitePool) -> Vec<i64> {
+        sqlx::query_scalar("SELECT id FROM properties ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn usage_counts(pool: &SqlitePool) -> Vec<(String, i64)> {
+        sqlx::query_as("SELECT name, usage_count FROM tags ORDER BY name")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn tag_names_are_trimmed_and_deduplicated() {
+        let names = vec![
+            "  agent:   Maria ".to_string(),
+            "Rush".to_string(),
+            "rush".to_string(),
+            "   ".to_string(),
+        ];
+        assert_eq!(normalize_tag_names(&names), vec!["agent: Maria", "Rush"]);
+    }
+
+    #[tokio::test]
+    async fn bulk_tag_and_untag_keep_usage_counts() {
+        let pool = setup_test_db().await;
+        for name in ["Villa A", "Villa B"] {
+            add_property_to_database(&pool, name, "Athens", "NEW", name, None)
+                .await
+                .unwrap();
+        }
+        let ids = property_ids(&pool).await;
+
+        let added = add_tags(&pool, &ids, &["rush".to_string(), "luxury".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(added, 4);
+        // Re-tagging is a no-op, and tag names match case-insensitively.
+        let added = add_tags(&pool, &ids, &["RUSH".to_string()]).await.unwrap();
+        assert_eq!(added, 0);
+        assert_eq!(
+            usage_counts(&pool).await,
+            vec![("luxury".to_string(), 2), ("rush".to_string(), 2)]
+        );
+
+        let removed = remove_tags(&pool, &ids[..1], &["Rush".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(
+            usage_counts(&pool).await,
+            vec![("luxury".to_string(), 2), ("rush".to_string(), 1)]
+        );
+
+        // Deleting a property drops its links and their counts.
+        sqlx::query("DELETE FROM properties WHERE id = ?")
+            .bind(ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            usage_counts(&pool).await,
+            vec![("luxury".to_string(), 1), ("rush".to_string(), 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn tag_filter_requires_every_tag() {
+        let pool = setup_test_db().await;
+        for name in ["Villa A", "Villa B"] {
+            add_property_to_database(&pool, name, "Athens", "NEW", name, None)
+                .await
+                .unwrap();
+        }
+        let ids = property_ids(&pool).await;
+        add_tags(&pool, &ids, &["rush".to_string()]).await.unwrap();
+        add_tags(&pool, &ids[..1], &["reshoot".to_string()])
+            .await
+            .unwrap();
+
+        let (condition, binds) =
+            has_all_tags_condition(&["Rush".to_string(), "reshoot".to_string()]).unwrap();
+        let sql = format!("SELECT id FROM properties WHERE {condition}");
+        let mut query = sqlx::query_scalar::<_, i64>(&sql);
+        for tag in &binds {
+            query = query.bind(tag);
+        }
+        assert_eq!(query.fetch_all(&pool).await.unwrap(), vec![ids[0]]);
+
+        assert!(has_all_tags_condition(&[" ".to_string()]).is_none());
+    }
+
+    #[tokio::test]
+    async fn attach_tags_fills_each_property() {
+        let pool = setup_test_db().await;
+        add_property_to_database(&pool, "Villa A", "Athens", "NEW", "Villa A", None)
+            .await
+            .unwrap();
+        let ids = property_ids(&pool).await;
+        add_tags(
+            &pool,
+            &ids,
+            &["rush".to_string(), "agent: Maria".to_string()],
+        )
+        .await
+        .unwrap();
+
+        let mut properties = vec![Property {
+            id: Some(ids[0]),
+            name: "Villa A".to_string(),
+            city: "Athens".to_string(),
+            status: "NEW".to_string(),
+            folder_path: "Athens/Villa A".to_string(),
+            notes: None,
+            code: None,
+            tags: Vec::new(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            completed: None,
+        }];
+        attach_tags(&pool, &mut properties).await.unwrap();
+        assert_eq!(properties[0].tags, vec!["agent: Maria", "rush"]);
+    }
+}
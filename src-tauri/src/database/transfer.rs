@@ -0,0 +1,289 @@
+//! Folder moves across filesystems.
+//!
+//! `fs::rename` cannot move a folder to another drive or network share; it
+//! fails with EXDEV (`ERROR_NOT_SAME_DEVICE` on Windows). That is the normal
+//! case when the NEW, DONE and ARCHIVE roots live on different volumes, so
+//! `move_folder` falls back to `copy_verify_and_remove` here: every file is
+//! copied into a hidden staging folder next to the destination, its SHA-256
+//! is checked against the source, and only once the whole tree matches is
+//! the staging folder renamed into place and the source deleted. Any failure
+//! before that point removes the staging folder and leaves the source as it
+//! was.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::Emitter;
+use walkdir::WalkDir;
+
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+const PROGRESS_EVENT: &str = "folder-move-progress";
+
+/// How far a copying move has got. Only reported when the folder has to be
+/// copied; a plain rename finishes at once.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CopyProgress {
+    pub files_done: usize,
+    pub total_files: usize,
+    pub bytes_done: u64,
+    pub total_bytes: u64,
+}
+
+/// Payload of the `folder-move-progress` event.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FolderMoveProgressEvent {
+    property_id: i64,
+    #[serde(flatten)]
+    progress: CopyProgress,
+}
+
+/// Progress callback that emits `folder-move-progress` for one property.
+pub(super) fn emit_move_progress(
+    app: &tauri::AppHandle,
+    property_id: i64,
+) -> impl FnMut(CopyProgress) + Send + 'static {
+    let app = app.clone();
+    move |progress| {
+        let _ = app.emit(
+            PROGRESS_EVENT,
+            FolderMoveProgressEvent {
+                property_id,
+                progress,
+            },
+        );
+    }
+}
+
+/// Whether a rename failed only because source and destination are on
+/// different filesystems.
+pub(super) fn is_cross_device(error: &io::Error) -> bool {
+    #[cfg(windows)]
+    const CROSS_DEVICE: i32 = 17; // ERROR_NOT_SAME_DEVICE
+    #[cfg(not(windows))]
+    const CROSS_DEVICE: i32 = 18; // EXDEV
+
+    error.raw_os_error() == Some(CROSS_DEVICE)
+}
+
+/// Lowercase hex SHA-256 of a file's contents. Blocking.
+pub(super) fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; COPY_BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Lowercase hex of a digest.
+pub(super) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
+
+/// Move `from` to `to` by copying and verifying every file, then deleting
+/// the source. `to` must not exist yet. Returns a warning when the copy is
+/// complete but the source could not be fully removed. Blocking.
+pub(super) fn copy_verify_and_remove(
+    from: &Path,
+    to: &Path,
+    on_progress: &mut dyn FnMut(CopyProgress),
+) -> Result<Option<String>, String> {
+    if to.exists() {
+        return Err(format!("Destination already exists: {}", to.display()));
+    }
+    let name = to
+        .file_name()
+        .ok_or_else(|| format!("Invalid destination: {}", to.display()))?;
+    let staging = to.with_file_name(format!(".{}.moving", name.to_string_lossy()));
+
+    // Left behind by a move that was interrupted; the source is still intact.
+    if staging.exists() {
+        fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to clear {}: {e}", staging.display()))?;
+    }
+
+    let copied = copy_tree(from, &staging, on_progress).and_then(|()| {
+        fs::rename(&staging, to).map_err(|e| format!("Failed to move copied folder: {e}"))
+    });
+    if let Err(e) = copied {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    // The destination is complete and verified, so a source that cannot be
+    // fully deleted is only clutter, which the caller is told about.
+    Ok(remove_source(from).err().map(|e| {
+        format!(
+            "Moved {} to {} but could not remove the original: {e}",
+            from.display(),
+            to.display()
+        )
+    }))
+}
+
+/// Delete the source of a finished copy, retrying once after a short pause
+/// for handles that are still being released. Blocking.
+pub(super) fn remove_source(from: &Path) -> io::Result<()> {
+    if fs::remove_dir_all(from).is_ok() {
+        return Ok(());
+    }
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    match fs::remove_dir_all(from) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Copy the tree under `from` into `to`, verifying each file as it goes.
+fn copy_tree(
+    from: &Path,
+    to: &Path,
+    on_progress: &mut dyn FnMut(CopyProgress),
+) -> Result<(), String> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let mut files: Vec<(PathBuf, u64)> = Vec::new();
+    for entry in WalkDir::new(from).min_depth(1) {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {e}", from.display()))?;
+        let relative = entry
+            .path()
+            .strip_prefix(from)
+            .map_err(|e| e.to_string())?
+            .to_path_buf();
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            dirs.push(relative);
+        } else if file_type.is_file() {
+            let len = entry.metadata().map_err(|e| e.to_string())?.len();
+            files.push((relative, len));
+        } else {
+            return Err(format!(
+                "Cannot copy {}: only plain files and folders can be moved between drives",
+                entry.path().display()
+            ));
+        }
+    }
+
+    let mut progress = CopyProgress {
+        total_files: files.len(),
+        total_bytes: files.iter().map(|(_, len)| len).sum(),
+        ..CopyProgress::default()
+    };
+    on_progress(progress);
+
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {e}", to.display()))?;
+    for dir in &dirs {
+        let dir = to.join(dir);
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+
+    for (relative, len) in &files {
+        let source = from.join(relative);
+        let target = to.join(relative);
+        let source_hash = copy_file_hashed(&source, &target)
+            .map_err(|e| format!("Failed to copy {}: {e}", source.display()))?;
+        let target_hash = sha256_file(&target)
+            .map_err(|e| format!("Failed to verify {}: {e}", target.display()))?;
+        if source_hash != target_hash {
+            return Err(format!(
+                "Checksum mismatch after copying {}",
+                source.display()
+            ));
+        }
+        progress.files_done += 1;
+        progress.bytes_done += len;
+        on_progress(progress);
+    }
+
+    Ok(())
+}
+
+/// Copy one file and return the SHA-256 of what was read from the source.
+fn copy_file_hashed(from: &Path, to: &Path) -> io::Result<String> {
+    let mut source = fs::File::open(from)?;
+    let mut target = fs::File::create(to)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; COPY_BUFFER_SIZE];
+    loop {
+        let read = source.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        target.write_all(&buffer[..read])?;
+    }
+    // Make sure verification reads what reached the disk, not the cache.
+    target.sync_all()?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copy_moves_tree_and_reports_progress() {
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("DONE/Athens/Villa");
+        fs::create_dir_all(from.join("INTERNET")).unwrap();
+        fs::write(from.join("1.jpg"), b"one").unwrap();
+        fs::write(from.join("INTERNET/1.jpg"), b"internet").unwrap();
+        let to = tmp.path().join("ARCHIVE/Athens/Villa");
+        fs::create_dir_all(to.parent().unwrap()).unwrap();
+
+        let mut reports = Vec::new();
+        let warning = copy_verify_and_remove(&from, &to, &mut |p| reports.push(p)).unwrap();
+        assert!(warning.is_none());
+
+        assert!(!from.exists());
+        assert_eq!(fs::read(to.join("INTERNET/1.jpg")).unwrap(), b"internet");
+        let last = reports.last().unwrap();
+        assert_eq!((last.files_done, last.total_files), (2, 2));
+        assert_eq!(last.bytes_done, 11);
+        assert_eq!(reports.len(), 3);
+        assert!(!to.with_file_name(".Villa.moving").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_copy_rolls_back_and_keeps_source() {
+        let tmp = tempfile::tempdir().unwrap();
+        let from = tmp.path().join("Villa");
+        fs::create_dir_all(&from).unwrap();
+        fs::write(from.join("1.jpg"), b"one").unwrap();
+        std::os::unix::fs::symlink(from.join("1.jpg"), from.join("link.jpg")).unwrap();
+        let to = tmp.path().join("archive").join("Villa");
+        fs::create_dir_all(to.parent().unwrap()).unwrap();
+
+        let result = copy_verify_and_remove(&from, &to, &mut |_| {});
+
+        assert!(result.is_err());
+        assert!(from.join("1.jpg").exists());
+        assert!(!to.exists());
+        assert!(!to.with_file_name(".Villa.moving").exists());
+    }
+
+    #[test]
+    fn sha256_matches_known_digest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("abc");
+        fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,557 @@
+//! Trash for deleted properties. `delete_property` stamps `deleted_at`
+//! instead of removing the row and, when a trash directory is configured,
+//! moves the folder to `{trash}/{property id}/{folder_path}`. Trashed
+//! properties are hidden from the property lists and search until they are
+//! restored or purged. Purging keeps the audit log.
+
+use std::path::{Path, PathBuf};
+
+use sqlx::{Row, SqlitePool};
+
+use crate::database::types::{CommandResult, PurgeTrashResult, TrashedProperty};
+use crate::database::{
+    find_actual_folder_location, folder_path_to_pathbuf, get_base_path_for_status,
+    get_database_pool, move_folder, record_property_event, PropertyEventKind,
+};
+
+/// Where a trashed folder lives: one directory per property id so two
+/// deleted properties with the same folder name never collide.
+fn trash_folder_for(trash_root: &Path, property_id: i64, folder_path: &str) -> PathBuf {
+    trash_root
+        .join(property_id.to_string())
+        .join(folder_path_to_pathbuf(folder_path))
+}
+
+/// Remove the now-empty `{trash}/{id}/{city}` directories left above a
+/// trashed folder once it has been restored or purged. Stops at the first
+/// directory that still has something in it.
+fn remove_empty_trash_parents(trash_path: &Path, folder_path: &str) {
+    let depth = folder_path_to_pathbuf(folder_path).components().count();
+    for dir in trash_path.ancestors().skip(1).take(depth) {
+        if std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+/// Move a property's folder (if any), then write the row change and its
+/// audit event in one transaction. If the database write fails the folder
+/// is moved back, so the two never disagree.
+async fn move_and_mark(
+    pool: &SqlitePool,
+    property_id: i64,
+    folder_move: Option<(PathBuf, PathBuf)>,
+    deleted_at: Option<i64>,
+    kind: PropertyEventKind,
+    source: &str,
+) -> Result<(), String> {
+    if let Some((from, to)) = folder_move.clone() {
+        tokio::task::spawn_blocking(move || move_folder(&from, &to))
+            .await
+            .map_err(|e| format!("Task join error: {e}"))??;
+    }
+
+    let trash_path = match (kind, &folder_move) {
+        (PropertyEventKind::Trashed, Some((_, to))) => Some(to.to_string_lossy().to_string()),
+        _ => None,
+    };
+
+    let result: Result<(), String> = async {
+        let mut tx = pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {e}"))?;
+        sqlx::query(
+            "UPDATE properties SET deleted_at = ?, trash_path = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(deleted_at)
+        .bind(&trash_path)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(property_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to update property: {e}"))?;
+
+        let (old_value, new_value) = match (kind, &folder_move) {
+            (PropertyEventKind::Trashed, _) => (None, trash_path.clone()),
+            (_, Some((from, _))) => (Some(from.to_string_lossy().to_string()), None),
+            (_, None) => (None, None),
+        };
+        record_property_event(
+            &mut tx,
+            property_id,
+            kind,
+            old_value.as_deref(),
+            new_value.as_deref(),
+            source,
+        )
+        .await?;
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit: {e}"))
+    }
+    .await;
+
+    if let Err(e) = result {
+        if let Some((from, to)) = folder_move {
+            let _ = tokio::task::spawn_blocking(move || move_folder(&to, &from)).await;
+        }
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+/// Trash a property, moving its folder to `trash_root` when one is given.
+/// `source` is the command recorded on the audit event.
+pub(super) async fn trash_property(
+    pool: &SqlitePool,
+    property_id: i64,
+    current_folder: Option<PathBuf>,
+    trash_root: Option<&Path>,
+    source: &str,
+) -> Result<(), String> {
+    let row = sqlx::query("SELECT folder_path, deleted_at FROM properties WHERE id = ?")
+        .bind(property_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("Failed to load property: {e}"))?
+        .ok_or("Property not found")?;
+
+    if row.get::<Option<i64>, _>("deleted_at").is_some() {
+        return Err("Property is already in the trash".to_string());
+    }
+    let folder_path: String = row.get("folder_path");
+
+    let folder_move = match (current_folder, trash_root) {
+        (Some(from), Some(root)) => Some((from, trash_folder_for(root, property_id, &folder_path))),
+        _ => None,
+    };
+
+    move_and_mark(
+        pool,
+        property_id,
+        folder_move,
+        Some(chrono::Utc::now().timestamp_millis()),
+        PropertyEventKind::Trashed,
+        source,
+    )
+    .await
+}
+
+/// Take a property out of the trash, moving its folder back to
+/// `restore_to` if it was moved into the trash directory.
+pub(super) async fn restore_trashed_property(
+    pool: &SqlitePool,
+    property_id: i64,
+    restore_to: Option<PathBuf>,
+) -> Result<(), String> {
+    let row =
+        sqlx::query("SELECT folder_path, deleted_at, trash_path FROM properties WHERE id = ?")
+            .bind(property_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("Failed to load property: {e}"))?
+            .ok_or("Property not found")?;
+
+    if row.get::<Option<i64>, _>("deleted_at").is_none() {
+        return Err("Property is not in the trash".to_string());
+    }
+    let folder_path: String = row.get("folder_path");
+    let trash_path: Option<String> = row.get("trash_path");
+
+    let folder_move = match (trash_path, restore_to) {
+        (Some(trash_path), Some(restore_to)) => {
+            let trash_path = PathBuf::from(trash_path);
+            let (exists_in_trash, destination_taken) = {
+                let (from, to) = (trash_path.clone(), restore_to.clone());
+                tokio::task::spawn_blocking(move || (from.exists(), to.exists()))
+                    .await
+                    .map_err(|e| format!("Task join error: {e}"))?
+            };
+            if destination_taken {
+                return Err(format!(
+                    "Cannot restore: a folder already exists at {}",
+                    restore_to.display()
+                ));
+            }
+            exists_in_trash.then_some((trash_path, restore_to))
+        }
+        (Some(_), None) => {
+            return Err("Cannot restore: app configuration not found".to_string());
+        }
+        (None, _) => None,
+    };
+
+    let trash_path = folder_move.as_ref().map(|(from, _)| from.clone());
+    move_and_mark(
+        pool,
+        property_id,
+        folder_move,
+        None,
+        PropertyEventKind::Restored,
+        "restore_property",
+    )
+    .await?;
+
+    if let Some(trash_path) = trash_path {
+        let _ = tokio::task::spawn_blocking(move || {
+            remove_empty_trash_parents(&trash_path, &folder_path);
+        })
+        .await;
+    }
+
+    Ok(())
+}
+
+/// Permanently delete properties trashed before `cutoff_ms`, together with
+/// their folders in the trash directory. A property whose folder cannot be
+/// removed keeps its row so the purge can be retried.
+pub(super) async fn purge_trashed_before(
+    pool: &SqlitePool,
+    cutoff_ms: i64,
+) -> Result<PurgeTrashResult, String> {
+    let rows: Vec<(i64, String, String, Option<String>)> = sqlx::query_as(
+        "SELECT id, name, folder_path, trash_path FROM properties
+         WHERE deleted_at IS NOT NULL AND deleted_at < ?",
+    )
+    .bind(cutoff_ms)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to load trashed properties: {e}"))?;
+
+    let mut result = PurgeTrashResult {
+        purged: 0,
+        errors: Vec::new(),
+    };
+
+    for (id, name, folder_path, trash_path) in rows {
+        if let Some(trash_path) = trash_path {
+            let removed = tokio::task::spawn_blocking(move || {
+                let trash_path = PathBuf::from(trash_path);
+                if trash_path.exists() {
+                    std::fs::remove_dir_all(&trash_path).map_err(|e| e.to_string())?;
+                }
+                remove_empty_trash_parents(&trash_path, &folder_path);
+                Ok::<_, String>(())
+            })
+            .await
+            .map_err(|e| format!("Task join error: {e}"))?;
+
+            if let Err(e) = removed {
+                result
+                    .errors
+                    .push(format!("Failed to delete folder for '{name}': {e}"));
+                continue;
+            }
+        }
+
+        match sqlx::query("DELETE FROM properties WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+        {
+            Ok(_) => result.purged += 1,
+            Err(e) => result
+                .errors
+                .push(format!("Failed to delete '{name}': {e}")),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Move a property to the trash. The row is kept with `deleted_at` set and
+/// the folder goes to the configured trash directory, if any.
+#[tauri::command]
+pub async fn delete_property(
+    app: tauri::AppHandle,
+    property_id: i64,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    let config = crate::config::get_cached_config(&app).await?;
+    let trash_root = config
+        .as_ref()
+        .filter(|c| !c.trash_folder_path.is_empty())
+        .map(|c| PathBuf::from(&c.trash_folder_path));
+
+    let current_folder = match (&config, &trash_root) {
+        (Some(config), Some(_)) => {
+            let folder_path: Option<String> =
+                sqlx::query_scalar("SELECT folder_path FROM properties WHERE id = ?")
+                    .bind(property_id)
+                    .fetch_optional(pool)
+                    .await
+                    .map_err(|e| format!("Failed to load property: {e}"))?;
+            let config = config.clone();
+            tokio::task::spawn_blocking(move || {
+                folder_path.and_then(|fp| find_actual_folder_location(&config, &fp))
+            })
+            .await
+            .map_err(|e| format!("Task join error: {e}"))?
+            .map(|(path, _)| path)
+        }
+        _ => None,
+    };
+
+    match trash_property(
+        pool,
+        property_id,
+        current_folder,
+        trash_root.as_deref(),
+        "delete_property",
+    )
+    .await
+    {
+        Ok(()) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: None,
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(format!("Failed to delete property: {e}")),
+            data: None,
+        }),
+    }
+}
+
+#[tauri::command]
+pub async fn get_trashed_properties(app: tauri::AppHandle) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    let rows = sqlx::query(
+        "SELECT id, name, city, status, folder_path, code, trash_path, deleted_at
+         FROM properties WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("Failed to fetch trashed properties: {e}"))?;
+
+    let properties: Vec<TrashedProperty> = rows
+        .into_iter()
+        .map(|row| TrashedProperty {
+            id: row.get("id"),
+            name: row.get("name"),
+            city: row.get("city"),
+            status: row.get("status"),
+            folder_path: row.get("folder_path"),
+            code: row.get("code"),
+            trash_path: row.get("trash_path"),
+            deleted_at: chrono::DateTime::from_timestamp_millis(row.get("deleted_at"))
+                .unwrap_or_else(chrono::Utc::now),
+        })
+        .collect();
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(properties).map_err(|e| e.to_string())?),
+    })
+}
+
+/// Restore a trashed property, moving its folder back into the workflow
+/// folder for its status.
+#[tauri::command]
+pub async fn restore_property(
+    app: tauri::AppHandle,
+    property_id: i64,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    let row: Option<(String, String)> =
+        sqlx::query_as("SELECT status, folder_path FROM properties WHERE id = ?")
+            .bind(property_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| format!("Failed to load property: {e}"))?;
+
+    let restore_to = match (crate::config::get_cached_config(&app).await?, row) {
+        (Some(config), Some((status, folder_path))) => Some(
+            get_base_path_for_status(&config, &status)?.join(folder_path_to_pathbuf(&folder_path)),
+        ),
+        _ => None,
+    };
+
+    match restore_trashed_property(pool, property_id, restore_to).await {
+        Ok(()) => Ok(CommandResult {
+            success: true,
+            error: None,
+            data: None,
+        }),
+        Err(e) => Ok(CommandResult {
+            success: false,
+            error: Some(format!("Failed to restore property: {e}")),
+            data: None,
+        }),
+    }
+}
+
+/// Permanently remove properties that have been in the trash for more than
+/// `older_than_days` days, both the rows and their trashed folders.
+#[tauri::command]
+pub async fn purge_trash(
+    app: tauri::AppHandle,
+    older_than_days: i64,
+) -> Result<CommandResult, String> {
+    let pool = get_database_pool(&app)?;
+
+    if older_than_days < 0 {
+        return Ok(CommandResult {
+            success: false,
+            error: Some("Days must not be negative".to_string()),
+            data: None,
+        });
+    }
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(older_than_days);
+    let result = purge_trashed_before(pool, cutoff.timestamp_millis()).await?;
+
+    Ok(CommandResult {
+        success: true,
+        error: None,
+        data: Some(serde_json::to_value(result).map_err(|e| e.to_string())?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+    use std::fs;
+
+    async fn villa_id(pool: &SqlitePool) -> i64 {
+        add_property_to_database(pool, "Villa", "Athens", "NEW", "Villa", None)
+            .await
+            .unwrap();
+        sqlx::query_scalar("SELECT id FROM properties WHERE name = 'Villa'")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn trash_columns(pool: &SqlitePool, id: i64) -> (Option<i64>, Option<String>) {
+        sqlx::query_as("SELECT deleted_at, trash_path FROM properties WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn trash_and_restore_round_trip_the_folder() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let original = tmp.path().join("NEW").join("Athens").join("Villa");
+        fs::create_dir_all(&original).unwrap();
+        fs::write(original.join("1.jpg"), b"x").unwrap();
+        let trash_root = tmp.path().join("TRASH");
+        let id = villa_id(&pool).await;
+
+        trash_property(
+            &pool,
+            id,
+            Some(original.clone()),
+            Some(&trash_root),
+            "delete_property",
+        )
+        .await
+        .unwrap();
+
+        let in_trash = trash_root.join(id.to_string()).join("Athens").join("Villa");
+        assert!(!original.exists());
+        assert!(in_trash.join("1.jpg").exists());
+        let (deleted_at, trash_path) = trash_columns(&pool, id).await;
+        assert!(deleted_at.is_some());
+        assert_eq!(trash_path, Some(in_trash.to_string_lossy().to_string()));
+
+        restore_trashed_property(&pool, id, Some(original.clone()))
+            .await
+            .unwrap();
+
+        assert!(original.join("1.jpg").exists());
+        assert!(!trash_root.join(id.to_string()).exists());
+        assert_eq!(trash_columns(&pool, id).await, (None, None));
+    }
+
+    #[tokio::test]
+    async fn trash_without_trash_dir_leaves_folder_in_place() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let original = tmp.path().join("NEW").join("Athens").join("Villa");
+        fs::create_dir_all(&original).unwrap();
+        let id = villa_id(&pool).await;
+
+        trash_property(&pool, id, Some(original.clone()), None, "delete_property")
+            .await
+            .unwrap();
+
+        assert!(original.exists());
+        let (deleted_at, trash_path) = trash_columns(&pool, id).await;
+        assert!(deleted_at.is_some());
+        assert!(trash_path.is_none());
+        assert!(trash_property(&pool, id, None, None, "delete_property")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn restore_refuses_to_overwrite_existing_folder() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let original = tmp.path().join("NEW").join("Athens").join("Villa");
+        fs::create_dir_all(&original).unwrap();
+        let id = villa_id(&pool).await;
+        trash_property(
+            &pool,
+            id,
+            Some(original.clone()),
+            Some(&tmp.path().join("TRASH")),
+            "delete_property",
+        )
+        .await
+        .unwrap();
+
+        fs::create_dir_all(&original).unwrap();
+        let err = restore_trashed_property(&pool, id, Some(original))
+            .await
+            .unwrap_err();
+        assert!(err.contains("already exists"));
+        assert!(trash_columns(&pool, id).await.0.is_some());
+    }
+
+    #[tokio::test]
+    async fn purge_removes_only_old_entries() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        let original = tmp.path().join("NEW").join("Athens").join("Villa");
+        fs::create_dir_all(&original).unwrap();
+        let trash_root = tmp.path().join("TRASH");
+        let id = villa_id(&pool).await;
+        trash_property(
+            &pool,
+            id,
+            Some(original),
+            Some(&trash_root),
+            "delete_property",
+        )
+        .await
+        .unwrap();
+        let deleted_at = trash_columns(&pool, id).await.0.unwrap();
+
+        let result = purge_trashed_before(&pool, deleted_at).await.unwrap();
+        assert_eq!(result.purged, 0);
+
+        let result = purge_trashed_before(&pool, deleted_at + 1).await.unwrap();
+        assert_eq!(result.purged, 1);
+        assert!(result.errors.is_empty());
+        assert!(!trash_root.join(id.to_string()).exists());
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
+}
@@ -35,6 +35,8 @@ pub struct Property {
     pub folder_path: String,
     pub notes: Option<String>,
     pub code: Option<String>, // Website listing code (e.g., "45164")
+    #[serde(default)]
+    pub tags: Vec<String>, // Tag names, see database::tags
     #[serde(with = "chrono::serde::ts_milliseconds")]
     #[ts(type = "number")]
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -65,9 +67,57 @@ pub struct City {
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// One folder `merge_cities` moves from a source city into the target city.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct CityFolderMove {
+    pub status: String,
+    /// `None` for folders the database does not track.
+    #[ts(type = "number | null")]
+    pub property_id: Option<i64>,
+    pub from: String,
+    pub to: String,
+}
+
+/// Preview (and, without `dry_run`, outcome) of merging cities into one.
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct CityMergePlan {
+    pub target: String,
+    pub sources: Vec<String>,
+    /// Rows whose city changes, trashed ones included.
+    #[ts(type = "number")]
+    pub properties: usize,
+    /// `usage_count` of the target city after the merge.
+    #[ts(type = "number")]
+    pub usage_count: i64,
+    pub moves: Vec<CityFolderMove>,
+    /// Target folders that already exist. Any conflict blocks the merge.
+    pub conflicts: Vec<String>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct Tag {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub name: String,
+    #[ts(type = "number")]
+    pub usage_count: i64,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    #[ts(type = "number")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Outcome of a scan. Folders in skipped cities are not counted in
+/// `found_properties` or `existing_properties`.
+#[derive(Debug, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
 pub struct ScanResult {
     #[ts(type = "number")]
     pub found_properties: usize,
@@ -76,6 +126,28 @@ pub struct ScanResult {
     #[ts(type = "number")]
     pub existing_properties: usize,
     pub errors: Vec<String>,
+    /// City folders not read because they had not changed since the last
+    /// scan.
+    #[serde(default)]
+    #[ts(type = "number")]
+    pub skipped_cities: usize,
+    #[serde(default)]
+    pub root_timings: Vec<RootScanTiming>,
+}
+
+/// How long one status folder took to scan.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct RootScanTiming {
+    pub status: String,
+    /// Reading the folders and importing what was new.
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    #[ts(type = "number")]
+    pub cities_scanned: usize,
+    #[ts(type = "number")]
+    pub cities_skipped: usize,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -101,6 +173,10 @@ pub struct Set {
     #[serde(with = "chrono::serde::ts_milliseconds")]
     #[ts(type = "number")]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// When `restore_set` last moved properties back out of ARCHIVE.
+    #[serde(default, with = "chrono::serde::ts_milliseconds_option")]
+    #[ts(type = "number | null")]
+    pub reverted_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, TS)]
@@ -115,7 +191,20 @@ pub struct SetProperty {
     pub property_id: Option<i64>,
     pub property_name: String,
     pub property_city: String,
-    pub property_code: Option<String>,
+    pub property_code: Option<String>, // Primary code at the time of the set
+    #[serde(default)]
+    pub property_codes: Vec<PropertyCode>, // Every portal code at the time of the set
+}
+
+/// One portal's listing code for a property. The primary code is mirrored
+/// into `properties.code` and the folder name suffix.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct PropertyCode {
+    pub portal: String,
+    pub code: String,
+    pub is_primary: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -130,6 +219,213 @@ pub struct CompleteSetResult {
     pub properties_archived: usize,
     #[ts(type = "number")]
     pub properties_moved_to_not_found: usize,
+    #[ts(type = "number")]
+    pub properties_left_in_done: usize,
+    /// Moves that finished but left part of the original folder behind.
+    pub warnings: Vec<String>,
+    /// Properties that could not be moved; they stay in DONE.
+    pub errors: Vec<String>,
+}
+
+/// Which DONE properties `complete_set` takes. The defaults complete every
+/// DONE property, as the dashboard button always has.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase", default)]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct SetCompletionOptions {
+    /// Only these properties; `None` takes every DONE property.
+    #[ts(type = "Array<number> | null")]
+    pub property_ids: Option<Vec<i64>>,
+    /// Leave properties without a code in DONE instead of moving them to
+    /// `NOT_FOUND`.
+    pub keep_codeless_in_done: bool,
+}
+
+/// A property as `preview_set_completion` would handle it.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct SetPreviewProperty {
+    #[ts(type = "number")]
+    pub property_id: i64,
+    pub name: String,
+    pub city: String,
+    pub code: Option<String>,
+    pub folder_path: String,
+    /// Whether the folder is in DONE; missing folders are left out of the ZIP.
+    pub folder_exists: bool,
+    #[ts(type = "number")]
+    pub file_count: usize,
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+}
+
+/// What `complete_set` would do with the same options, without doing it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct SetCompletionPreview {
+    /// Zipped, then moved to ARCHIVE.
+    pub to_archive: Vec<SetPreviewProperty>,
+    /// Moved to `NOT_FOUND` without being zipped.
+    pub to_not_found: Vec<SetPreviewProperty>,
+    /// Selected, but kept in DONE because they have no code.
+    pub left_in_done: Vec<SetPreviewProperty>,
+    /// Requested IDs that are not DONE properties, with the reason.
+    pub not_eligible: Vec<String>,
+    #[ts(type = "number")]
+    pub zip_file_count: usize,
+    #[ts(type = "number")]
+    pub zip_bytes: u64,
+}
+
+/// One row of a property's audit trail (see `database::events`).
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct PropertyEvent {
+    #[ts(type = "number")]
+    pub id: i64,
+    #[ts(type = "number")]
+    pub property_id: i64,
+    pub event_type: String, // "status_change", "folder_move", "rename", ...
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub source: String, // Name of the command that made the change
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    #[ts(type = "number")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Outcome for one property in `bulk_update_property_status`.
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct BulkStatusItemResult {
+    #[ts(type = "number")]
+    pub property_id: i64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct BulkStatusResult {
+    #[ts(type = "number")]
+    pub updated: usize,
+    #[ts(type = "number")]
+    pub failed: usize,
+    pub results: Vec<BulkStatusItemResult>,
+}
+
+/// A soft-deleted property as listed on the Trash page.
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct TrashedProperty {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub name: String,
+    pub city: String,
+    pub status: String, // Status the property is restored to
+    pub folder_path: String,
+    pub code: Option<String>,
+    /// Absolute path of the folder inside the trash directory, or null when
+    /// the folder was left where it was.
+    pub trash_path: Option<String>,
+    #[serde(with = "chrono::serde::ts_milliseconds")]
+    #[ts(type = "number")]
+    pub deleted_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct PurgeTrashResult {
+    #[ts(type = "number")]
+    pub purged: usize,
+    pub errors: Vec<String>,
+}
+
+/// What kind of mismatch an `OrphanFinding` describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub enum OrphanKind {
+    /// A database row whose folder is in no status folder.
+    MissingFolder,
+    /// The same property folder exists in more than one status folder.
+    DuplicateFolder,
+    /// A city folder with nothing in it.
+    EmptyCity,
+}
+
+/// A fix for an orphan finding, applied with `apply_orphan_actions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub enum OrphanAction {
+    /// Move the row of a property whose folder is gone to the trash.
+    DeleteRow {
+        #[ts(type = "number")]
+        property_id: i64,
+    },
+    /// Point a row at an untracked folder with the same property name.
+    Relink {
+        #[ts(type = "number")]
+        property_id: i64,
+        status: String,
+        folder_path: String,
+    },
+    /// Move the contents of every other copy into the copy under
+    /// `keep_status`. Files that exist in both are left where they are.
+    Merge {
+        folder_path: String,
+        keep_status: String,
+    },
+    /// Remove an empty city folder.
+    RemoveCityFolder { status: String, city: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct OrphanFinding {
+    pub kind: OrphanKind,
+    #[ts(type = "number | null")]
+    pub property_id: Option<i64>,
+    /// `City/Folder` for property findings, the city name for empty cities.
+    pub folder_path: String,
+    /// Status folders involved: the row's status for a missing folder,
+    /// every status holding a copy for duplicates.
+    pub statuses: Vec<String>,
+    pub suggested_actions: Vec<OrphanAction>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct OrphanReport {
+    #[ts(type = "number")]
+    pub properties_checked: usize,
+    #[ts(type = "number")]
+    pub folders_checked: usize,
+    pub findings: Vec<OrphanFinding>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct OrphanActionResult {
+    #[ts(type = "number")]
+    pub applied: usize,
+    pub errors: Vec<String>,
 }
 
 /// Request item for batch thumbnail path resolution.
@@ -153,3 +449,276 @@ pub struct ThumbnailBatchResult {
     pub total_count: usize,
     pub paths: Vec<String>,
 }
+
+/// File format written by `export_properties`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Which properties `export_properties` writes. Empty lists and missing
+/// bounds do not filter. The date range applies to `created_at` and is
+/// inclusive, in milliseconds since the epoch.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct PropertyExportOptions {
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub statuses: Vec<String>,
+    #[serde(default)]
+    pub cities: Vec<String>,
+    #[ts(type = "number | null")]
+    pub created_from: Option<i64>,
+    #[ts(type = "number | null")]
+    pub created_to: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct PropertyExportResult {
+    pub path: String,
+    #[ts(type = "number")]
+    pub exported: usize,
+}
+
+/// Outcome of `import_properties`. `errors` holds one message per skipped
+/// CSV line or failed status change.
+#[derive(Debug, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct PropertyImportResult {
+    #[ts(type = "number")]
+    pub created: usize,
+    #[ts(type = "number")]
+    pub updated: usize,
+    #[ts(type = "number")]
+    pub unchanged: usize,
+    pub errors: Vec<String>,
+    pub dry_run: bool,
+}
+
+/// A database backup in `{app data}/backups`.
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct BackupInfo {
+    pub file_name: String,
+    /// Why it was taken: startup, daily, manual, pre-restore or pre-reset.
+    pub kind: String,
+    /// Milliseconds since the epoch, from the file name.
+    #[ts(type = "number")]
+    pub created_at: i64,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+}
+
+/// Image files in each workflow subfolder of a property folder.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct ImageCounts {
+    #[ts(type = "number")]
+    pub originals: usize,
+    #[ts(type = "number")]
+    pub internet: usize,
+    #[ts(type = "number")]
+    pub internet_aggelia: usize,
+    #[ts(type = "number")]
+    pub watermark: usize,
+    #[ts(type = "number")]
+    pub watermark_aggelia: usize,
+}
+
+/// Properties shot (created), delivered (included in a set) and archived
+/// (moved to ARCHIVE) over some period.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct Throughput {
+    #[ts(type = "number")]
+    pub shot: usize,
+    #[ts(type = "number")]
+    pub delivered: usize,
+    #[ts(type = "number")]
+    pub archived: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct WeeklyThroughput {
+    /// Monday 00:00 UTC, in milliseconds since the epoch.
+    #[ts(type = "number")]
+    pub week_start: i64,
+    pub throughput: Throughput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct CityThroughput {
+    pub city: String,
+    pub throughput: Throughput,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct StatusCount {
+    pub status: String,
+    #[ts(type = "number")]
+    pub count: usize,
+    /// Images on disk across these properties, in this status's folder.
+    pub image_counts: ImageCounts,
+}
+
+/// Report returned by `get_statistics`. The range bounds are inclusive, in
+/// milliseconds since the epoch; a missing bound does not limit.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct Statistics {
+    #[ts(type = "number | null")]
+    pub from: Option<i64>,
+    #[ts(type = "number | null")]
+    pub to: Option<i64>,
+    pub total: Throughput,
+    /// Every week from the first to the last with activity, empty weeks
+    /// included.
+    pub weeks: Vec<WeeklyThroughput>,
+    /// Busiest city first.
+    pub cities: Vec<CityThroughput>,
+    /// Current status of the properties shot in the range, with their
+    /// images in each workflow folder.
+    pub statuses: Vec<StatusCount>,
+    /// Mean milliseconds from creation to the first move to DONE, over the
+    /// properties that reached DONE in the range.
+    #[ts(type = "number | null")]
+    pub average_time_to_done: Option<f64>,
+    #[ts(type = "number")]
+    pub done_count: usize,
+}
+
+/// Payload of the `folders-synced` event, sent after the folder watcher
+/// imported or updated properties.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct FolderSyncResult {
+    /// Status folders the changes were seen in.
+    pub statuses: Vec<String>,
+    #[ts(type = "number")]
+    pub imported: usize,
+    /// Properties whose status was updated to match the disk.
+    #[ts(type = "number")]
+    pub updated: usize,
+    pub errors: Vec<String>,
+}
+
+/// What is known about one image file, from the `images` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct ImageRecord {
+    #[ts(type = "number")]
+    pub property_id: i64,
+    /// Folder relative to the property folder, `""` for the originals and
+    /// e.g. `"INTERNET/AGGELIA"` for the others.
+    pub subfolder: String,
+    pub filename: String,
+    /// Bytes.
+    #[ts(type = "number")]
+    pub size: i64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Modification time in milliseconds since the epoch.
+    #[ts(type = "number")]
+    pub mtime: i64,
+    /// Lowercase hex SHA-256 of the file contents.
+    pub sha256: String,
+    /// EXIF `DateTimeOriginal` as `YYYY-MM-DDTHH:MM:SS`, in the camera's
+    /// local time.
+    pub captured_at: Option<String>,
+}
+
+/// A ZIP entry that failed verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct SetEntryIssue {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Result of `verify_set`. The set is intact when `ok` is true.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct SetVerificationReport {
+    #[ts(type = "number")]
+    pub set_id: i64,
+    pub zip_path: String,
+    pub ok: bool,
+    /// Why the ZIP could not be opened at all, e.g. missing or truncated.
+    pub open_error: Option<String>,
+    /// Sets completed before manifests were added have none.
+    pub has_manifest: bool,
+    #[ts(type = "number")]
+    pub entries_checked: usize,
+    /// Files listed in the manifest but not in the ZIP.
+    pub missing_entries: Vec<String>,
+    /// Files in the ZIP outside every property folder of the set, and
+    /// images in the indexed subfolders that the manifest does not list.
+    pub extra_entries: Vec<String>,
+    /// Entries that fail their CRC or differ from the manifest.
+    pub corrupt_entries: Vec<SetEntryIssue>,
+    /// `city/name` of the set's properties with no folder in the ZIP.
+    pub missing_properties: Vec<String>,
+}
+
+/// Result of `restore_set`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct RestoreSetResult {
+    #[ts(type = "number")]
+    pub set_id: i64,
+    /// Status the properties were moved back to.
+    pub status: String,
+    #[ts(type = "number")]
+    pub restored: usize,
+    /// Of those, properties whose folder was re-extracted from the ZIP.
+    #[ts(type = "number")]
+    pub extracted: usize,
+    /// Properties left alone, with the reason.
+    pub skipped: Vec<String>,
+    /// Moves that finished but left part of the original folder behind.
+    pub warnings: Vec<String>,
+}
+
+/// Payload of the `set-zip-progress` event emitted while `complete_set`
+/// indexes the images for the manifest and then writes the set ZIP.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "../../../src/lib/types/generated/")]
+pub struct SetZipProgress {
+    /// Properties indexed so far; below `total_properties` while indexing.
+    #[ts(type = "number")]
+    pub properties_indexed: usize,
+    /// Properties to index, or 0 once the ZIP is being written.
+    #[ts(type = "number")]
+    pub total_properties: usize,
+    #[ts(type = "number")]
+    pub files_done: usize,
+    #[ts(type = "number")]
+    pub total_files: usize,
+    #[ts(type = "number")]
+    pub bytes_done: u64,
+    #[ts(type = "number")]
+    pub total_bytes: u64,
+}
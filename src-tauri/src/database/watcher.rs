@@ -0,0 +1,369 @@
+//! Folder watcher: imports new shoots as their folders appear, instead of
+//! waiting for someone to run `scan_and_import_properties`.
+//!
+//! The NEW folder (or, with `watchAllRoots`, every status folder) is
+//! watched recursively. Only changes at the city or property level count;
+//! image edits deeper in a property folder are ignored. After a quiet
+//! period the watcher first updates the status of properties in the changed
+//! city folders whose folder moved to another status folder, and then
+//! imports folders the database does not know yet. Other drift, such as a
+//! gained code suffix, is left to the repair command. The UI is told
+//! through the `folders-synced` event. While a journaled folder move is in
+//! flight the sync waits for it to finish.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use sqlx::SqlitePool;
+use tauri::{Emitter, Manager};
+
+use crate::config::AppConfig;
+use crate::database::repair::{apply_repair_diff, load_repair_diffs, RepairReason};
+use crate::database::scan::{get_existing_properties_set, scan_folder_for_properties};
+use crate::database::types::FolderSyncResult;
+use crate::database::{get_base_path_for_status, get_database_pool};
+
+/// Quiet time after the last change before the folders are synced, so a
+/// folder being copied in is imported once, not once per file.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+const SYNC_EVENT: &str = "folders-synced";
+const SYNC_SOURCE: &str = "folder_watcher";
+/// How many times a sync waits one `DEBOUNCE` for a journaled folder move
+/// to finish before it gives up.
+const PENDING_RETRIES: u32 = 30;
+
+type WatchedRoots = Vec<(String, PathBuf)>;
+
+/// The running watcher, managed by Tauri. Dropping the debouncer stops it.
+#[derive(Default)]
+pub struct FolderWatcherState {
+    watcher: Mutex<Option<(WatchedRoots, Debouncer<RecommendedWatcher>)>>,
+    /// Runs one sync at a time, so two bursts of events never import the
+    /// same folder twice.
+    sync_lock: tokio::sync::Mutex<()>,
+}
+
+/// Status folders to watch, as `(status, root)`. Empty when watching is off.
+fn watched_roots(config: &AppConfig) -> WatchedRoots {
+    if !config.watch_folders {
+        return Vec::new();
+    }
+    let statuses: &[&str] = if config.watch_all_roots {
+        &["NEW", "DONE", "NOT_FOUND", "ARCHIVE"]
+    } else {
+        &["NEW"]
+    };
+    statuses
+        .iter()
+        .filter_map(|status| {
+            get_base_path_for_status(config, status)
+                .ok()
+                .filter(|root| root.is_dir())
+                .map(|root| ((*status).to_string(), root))
+        })
+        .collect()
+}
+
+/// Statuses and city folders that saw a change at the city or property
+/// level.
+fn changed_folders<'a>(
+    roots: &WatchedRoots,
+    paths: impl IntoIterator<Item = &'a Path>,
+) -> (Vec<String>, Vec<String>) {
+    let mut statuses = Vec::new();
+    let mut cities = Vec::new();
+    for path in paths {
+        let changed = roots.iter().find_map(|(status, root)| {
+            let relative = path.strip_prefix(root).ok()?;
+            let city = match relative.components().count() {
+                1 | 2 => relative.components().next()?.as_os_str().to_str()?,
+                _ => return None,
+            };
+            Some((status, city.to_string()))
+        });
+        if let Some((status, city)) = changed {
+            if !statuses.contains(status) {
+                statuses.push(status.clone());
+            }
+            if !cities.contains(&city) {
+                cities.push(city);
+            }
+        }
+    }
+    (statuses, cities)
+}
+
+/// Bring the database in line with the status folders in `statuses`:
+/// follow properties in `cities` whose folder moved to another status
+/// folder, then import unknown folders. Returns `None` without touching
+/// anything while a journaled folder move is in flight.
+pub(super) async fn sync_watched_folders(
+    pool: &SqlitePool,
+    config: &AppConfig,
+    statuses: Vec<String>,
+    cities: &[String],
+) -> Result<Option<FolderSyncResult>, String> {
+    // A move in flight has a half-copied staging folder or a moved folder
+    // whose row is not updated yet; neither is a change to import or
+    // "repair".
+    let pending: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pending_operations")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("Failed to check pending operations: {e}"))?;
+    if pending > 0 {
+        return Ok(None);
+    }
+
+    let mut result = FolderSyncResult {
+        statuses,
+        ..FolderSyncResult::default()
+    };
+    let (_, diffs, _) = load_repair_diffs(pool, config, Some(cities)).await?;
+    for diff in diffs
+        .iter()
+        .filter(|diff| diff.reasons == [RepairReason::Moved])
+    {
+        match apply_repair_diff(pool, diff, SYNC_SOURCE).await {
+            Ok(()) => result.updated += 1,
+            Err(e) => result
+                .errors
+                .push(format!("Failed to update '{}': {e}", diff.name)),
+        }
+    }
+
+    let existing = get_existing_properties_set(pool).await?;
+    for status in &result.statuses {
+        let Ok(root) = get_base_path_for_status(config, status) else {
+            continue;
+        };
+        if !root.is_dir() {
+            continue;
+        }
+        let scan = scan_folder_for_properties(&root, status, &existing, pool).await?;
+        result.imported += scan.new_properties;
+        result.errors.extend(scan.errors);
+    }
+
+    Ok(Some(result))
+}
+
+async fn sync_after_change(app: &tauri::AppHandle, statuses: Vec<String>, cities: Vec<String>) {
+    let state = app.state::<FolderWatcherState>();
+    let _running = state.sync_lock.lock().await;
+
+    let result = async {
+        let pool = get_database_pool(app)?;
+        let config = crate::config::get_cached_config(app)
+            .await?
+            .ok_or("App configuration not found")?;
+        for _ in 0..PENDING_RETRIES {
+            let synced = sync_watched_folders(pool, &config, statuses.clone(), &cities).await?;
+            if synced.is_some() {
+                return Ok(synced);
+            }
+            tokio::time::sleep(DEBOUNCE).await;
+        }
+        Ok::<_, String>(None)
+    }
+    .await;
+
+    match result {
+        Ok(Some(synced)) if synced.imported + synced.updated > 0 || !synced.errors.is_empty() => {
+            let _ = app.emit(SYNC_EVENT, synced);
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => eprintln!("Folder watcher sync skipped: a folder move did not finish"),
+        Err(e) => eprintln!("Folder watcher sync failed: {e}"),
+    }
+}
+
+/// Start, restart or stop the watcher to match the current config. Does
+/// nothing when the watched folders have not changed.
+pub async fn restart_folder_watcher(app: &tauri::AppHandle) -> Result<(), String> {
+    let config = crate::config::get_cached_config(app).await?;
+    let roots = config.as_ref().map(watched_roots).unwrap_or_default();
+
+    let state = app.state::<FolderWatcherState>();
+    let mut running = state.watcher.lock().map_err(|e| e.to_string())?;
+    if running.as_ref().map(|(current, _)| current) == Some(&roots) {
+        return Ok(());
+    }
+    // Stop the old watcher before watching the new folders.
+    *running = None;
+    if roots.is_empty() {
+        return Ok(());
+    }
+
+    let handler_app = app.clone();
+    let handler_roots = roots.clone();
+    let mut debouncer = new_debouncer(DEBOUNCE, move |events: DebounceEventResult| match events {
+        Ok(events) => {
+            let (statuses, cities) =
+                changed_folders(&handler_roots, events.iter().map(|e| e.path.as_path()));
+            if !statuses.is_empty() {
+                let app = handler_app.clone();
+                tauri::async_runtime::spawn(async move {
+                    sync_after_change(&app, statuses, cities).await;
+                });
+            }
+        }
+        Err(e) => eprintln!("Folder watcher error: {e}"),
+    })
+    .map_err(|e| format!("Failed to start folder watcher: {e}"))?;
+
+    for (_, root) in &roots {
+        debouncer
+            .watcher()
+            .watch(root, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {e}", root.display()))?;
+    }
+    *running = Some((roots, debouncer));
+    drop(running);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_support::{add_property_to_database, setup_test_db};
+    use sqlx::Row;
+    use std::fs;
+
+    fn config_for(root: &Path) -> AppConfig {
+        AppConfig {
+            new_folder_path: root.join("NEW").to_string_lossy().to_string(),
+            done_folder_path: root.join("DONE").to_string_lossy().to_string(),
+            not_found_folder_path: root.join("NOT_FOUND").to_string_lossy().to_string(),
+            archive_folder_path: root.join("ARCHIVE").to_string_lossy().to_string(),
+            ..AppConfig::default()
+        }
+    }
+
+    #[test]
+    fn only_city_and_property_level_changes_count() {
+        let tmp = tempfile::tempdir().unwrap();
+        for status in ["NEW", "DONE"] {
+            fs::create_dir_all(tmp.path().join(status)).unwrap();
+        }
+        let mut config = config_for(tmp.path());
+        let roots = watched_roots(&config);
+        assert_eq!(roots.len(), 1);
+
+        let new = tmp.path().join("NEW");
+        let paths = [
+            new.clone(),
+            new.join("Athens").join("Villa").join("INTERNET"),
+            tmp.path().join("DONE").join("Athens"),
+        ];
+        assert!(changed_folders(&roots, paths.iter().map(PathBuf::as_path))
+            .0
+            .is_empty());
+        let paths = [
+            new.join("Athens"),
+            new.join("Athens").join("Villa"),
+            new.join("Rome").join("Loft"),
+        ];
+        assert_eq!(
+            changed_folders(&roots, paths.iter().map(PathBuf::as_path)),
+            (
+                vec!["NEW".to_string()],
+                vec!["Athens".to_string(), "Rome".to_string()]
+            )
+        );
+
+        config.watch_all_roots = true;
+        assert_eq!(watched_roots(&config).len(), 2);
+        config.watch_folders = false;
+        assert!(watched_roots(&config).is_empty());
+    }
+
+    #[tokio::test]
+    async fn sync_imports_new_folders_and_follows_moved_ones() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        add_property_to_database(&pool, "Villa", "Athens", "NEW", "Villa", None)
+            .await
+            .unwrap();
+        add_property_to_database(&pool, "House", "Sparta", "NEW", "House", None)
+            .await
+            .unwrap();
+        // Villa was moved to DONE by hand; Loft is a new shoot. House was
+        // moved too, but its city folder saw no change.
+        fs::create_dir_all(tmp.path().join("DONE/Athens/Villa")).unwrap();
+        fs::create_dir_all(tmp.path().join("DONE/Sparta/House")).unwrap();
+        fs::create_dir_all(tmp.path().join("NEW/Rome/Loft")).unwrap();
+        let cities = ["Athens".to_string(), "Rome".to_string()];
+
+        let result =
+            sync_watched_folders(&pool, &config_for(tmp.path()), vec!["NEW".into()], &cities)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(result.imported, 1);
+        assert_eq!(result.updated, 1);
+
+        let rows = sqlx::query("SELECT name, status FROM properties ORDER BY name")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        let rows: Vec<(String, String)> = rows
+            .iter()
+            .map(|row| (row.get("name"), row.get("status")))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("House".to_string(), "NEW".to_string()),
+                ("Loft".to_string(), "NEW".to_string()),
+                ("Villa".to_string(), "DONE".to_string())
+            ]
+        );
+        let source: String = sqlx::query_scalar(
+            "SELECT source FROM property_events WHERE event_type = 'repair_fix'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(source, SYNC_SOURCE);
+
+        // A second sync finds nothing to do.
+        let again =
+            sync_watched_folders(&pool, &config_for(tmp.path()), vec!["NEW".into()], &cities)
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(again.imported + again.updated, 0);
+    }
+
+    #[tokio::test]
+    async fn sync_waits_for_journaled_moves() {
+        let pool = setup_test_db().await;
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("NEW/Rome/Loft")).unwrap();
+        fs::create_dir_all(tmp.path().join("NEW/Rome/.Flat.moving")).unwrap();
+        sqlx::query(
+            "INSERT INTO pending_operations
+             (property_id, old_status, new_status, folder_path, source_path, dest_path, source, created_at)
+             VALUES (1, 'DONE', 'NEW', 'Rome/Flat', 'DONE/Rome/Flat', 'NEW/Rome/Flat',
+                     'update_property_status', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let cities = ["Rome".to_string()];
+
+        let result =
+            sync_watched_folders(&pool, &config_for(tmp.path()), vec!["NEW".into()], &cities)
+                .await
+                .unwrap();
+        assert!(result.is_none());
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM properties")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+}
@@ -21,17 +21,24 @@ use config::{
 // #[allow(dead_code)] in database.rs) and will be deleted when database.rs
 // is split into modules in a follow-up commit.
 use database::{
+    add_property_code, apply_orphan_actions, bulk_update_property_status, cancel_set_completion,
     clear_aggelia_folder, clear_internet_folder, clear_watermark_folders, complete_set,
-    copy_and_watermark_images, copy_images_to_aggelia, copy_images_to_internet, create_property,
-    debug_database_dates, delete_property, delete_set, fill_aggelia_to_25,
-    generate_watermark_preview, get_cities, get_full_property_path, get_gallery_thumbnail_path,
-    get_properties, get_properties_by_status, get_property_by_id, get_set_properties, get_sets,
-    get_thumbnail_paths_batch, init_database, list_aggelia_images, list_internet_images,
-    list_original_images, list_thumbnails, list_watermark_aggelia_images, list_watermark_images,
+    copy_and_watermark_images, copy_images_to_aggelia, copy_images_to_internet, create_backup,
+    create_property, debug_database_dates, delete_property, delete_set, export_properties,
+    fill_aggelia_to_25, find_orphans, generate_watermark_preview, get_cities,
+    get_full_property_path, get_gallery_thumbnail_path, get_properties, get_properties_by_status,
+    get_property_by_code, get_property_by_id, get_property_codes, get_property_events,
+    get_property_images, get_set_properties, get_sets, get_statistics, get_tags,
+    get_thumbnail_paths_batch, get_trashed_properties, import_properties, init_database,
+    list_aggelia_images, list_backups, list_internet_images, list_original_images, list_thumbnails,
+    list_watermark_aggelia_images, list_watermark_images, merge_cities,
     open_image_in_advanced_editor, open_image_in_editor, open_images_in_folder,
-    open_property_folder, open_sets_folder, pregenerate_gallery_thumbnails, rename_internet_images,
-    repair_property_statuses, reset_database_with_proper_dates, scan_and_import_properties,
-    search_cities, set_property_code, update_property, update_property_status,
+    open_property_folder, open_sets_folder, pregenerate_gallery_thumbnails, preview_set_completion,
+    purge_trash, remove_property_code, rename_city, rename_internet_images,
+    repair_property_statuses, reset_database_with_proper_dates, restore_backup, restore_property,
+    restore_set, scan_and_import_properties, search_cities, search_properties, search_tags,
+    set_primary_property_code, set_property_code, tag_properties, untag_properties,
+    update_property, update_property_status, verify_set,
 };
 use image_editor::{
     batch_analyze_for_enhance, batch_apply_enhancements, editor_analyze_image,
@@ -46,6 +53,8 @@ use perspective::commands::{
 
 use tauri::Manager;
 
+// Grows with every command registered in the invoke handler below.
+#[allow(clippy::too_many_lines)]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -83,6 +92,18 @@ pub fn run() {
             eprintln!("Image processor: {}", processor.description());
             app_handle.manage(processor);
 
+            // Import new property folders as they appear
+            app_handle.manage(database::FolderWatcherState::default());
+            app_handle.manage(database::SetZipState::default());
+            if let Err(e) =
+                tauri::async_runtime::block_on(database::restart_folder_watcher(&app_handle))
+            {
+                eprintln!("Failed to start folder watcher: {e}");
+            }
+
+            // Back up the database now and once a day
+            database::spawn_backup_schedule(app_handle);
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -95,13 +116,37 @@ pub fn run() {
             create_property,
             get_properties,
             get_property_by_id,
+            get_property_by_code,
             get_properties_by_status,
+            get_property_events,
+            get_property_images,
             update_property_status,
+            bulk_update_property_status,
             set_property_code,
+            get_property_codes,
+            add_property_code,
+            remove_property_code,
+            set_primary_property_code,
             update_property,
             delete_property,
+            get_trashed_properties,
+            restore_property,
+            purge_trash,
             get_cities,
             search_cities,
+            merge_cities,
+            rename_city,
+            search_properties,
+            get_statistics,
+            export_properties,
+            list_backups,
+            create_backup,
+            restore_backup,
+            import_properties,
+            get_tags,
+            search_tags,
+            tag_properties,
+            untag_properties,
             scan_and_import_properties,
             debug_database_dates,
             reset_database_with_proper_dates,
@@ -135,12 +180,18 @@ pub fn run() {
             get_original_image_for_comparison,
             // Sets commands
             complete_set,
+            cancel_set_completion,
+            preview_set_completion,
             get_sets,
             get_set_properties,
             open_sets_folder,
             delete_set,
+            verify_set,
+            restore_set,
             // Repair commands
             repair_property_statuses,
+            find_orphans,
+            apply_orphan_actions,
             // Image editor commands
             editor_get_dimensions,
             editor_load_image,
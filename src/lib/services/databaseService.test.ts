@@ -60,7 +60,10 @@ describe('DatabaseService — property commands', () => {
   it('getPropertiesByStatus passes the status arg', async () => {
     invokeMock.mockResolvedValue({ success: true, data: [] });
     await DatabaseService.getPropertiesByStatus('DONE');
-    expect(invokeMock).toHaveBeenCalledWith('get_properties_by_status', { status: 'DONE' });
+    expect(invokeMock).toHaveBeenCalledWith('get_properties_by_status', {
+      status: 'DONE',
+      tags: null
+    });
   });
 
   it('updatePropertyStatus uses camelCase propertyId/newStatus', async () => {
@@ -103,6 +106,161 @@ describe('DatabaseService — property commands', () => {
     const result = await DatabaseService.getPropertyById(123);
     expect(result).toBeNull();
   });
+
+  it('getPropertyByCode passes the code and unwraps the property', async () => {
+    const fake = { id: 3, name: 'Villa', code: 'AB1' };
+    invokeMock.mockResolvedValue({ success: true, data: fake });
+    const result = await DatabaseService.getPropertyByCode('AB1');
+    expect(invokeMock).toHaveBeenCalledWith('get_property_by_code', { code: 'AB1' });
+    expect(result).toEqual(fake);
+  });
+
+  it('getPropertyCodes unwraps the list and defaults to empty', async () => {
+    const fake = [{ portal: 'website', code: 'AB1', isPrimary: true }];
+    invokeMock.mockResolvedValueOnce({ success: true, data: fake });
+    expect(await DatabaseService.getPropertyCodes(3)).toEqual(fake);
+    expect(invokeMock).toHaveBeenCalledWith('get_property_codes', { propertyId: 3 });
+    invokeMock.mockResolvedValueOnce({ success: false, error: 'x' });
+    expect(await DatabaseService.getPropertyCodes(3)).toEqual([]);
+  });
+
+  it('getPropertyImages passes the subfolder and throws on failure', async () => {
+    invokeMock.mockResolvedValueOnce({ success: true, data: [] });
+    expect(await DatabaseService.getPropertyImages(3, 'INTERNET')).toEqual([]);
+    expect(invokeMock).toHaveBeenCalledWith('get_property_images', {
+      propertyId: 3,
+      subfolder: 'INTERNET'
+    });
+    invokeMock.mockResolvedValueOnce({ success: false, error: 'Property not found' });
+    await expect(DatabaseService.getPropertyImages(3)).rejects.toThrow('Property not found');
+  });
+
+  it('addPropertyCode passes the portal and defaults primary to null', async () => {
+    invokeMock.mockResolvedValue({ success: true });
+    await DatabaseService.addPropertyCode(3, 'spitogatos', '778');
+    expect(invokeMock).toHaveBeenCalledWith('add_property_code', {
+      propertyId: 3,
+      portal: 'spitogatos',
+      code: '778',
+      primary: null
+    });
+  });
+
+  it('removePropertyCode and setPrimaryPropertyCode pass the portal', async () => {
+    invokeMock.mockResolvedValue({ success: true });
+    await DatabaseService.removePropertyCode(3, 'xe');
+    expect(invokeMock).toHaveBeenCalledWith('remove_property_code', { propertyId: 3, portal: 'xe' });
+    await DatabaseService.setPrimaryPropertyCode(3, 'xe');
+    expect(invokeMock).toHaveBeenCalledWith('set_primary_property_code', {
+      propertyId: 3,
+      portal: 'xe'
+    });
+  });
+});
+
+describe('DatabaseService — bulk status', () => {
+  it('bulkUpdatePropertyStatus passes ids and status and unwraps data', async () => {
+    const fake = { updated: 1, failed: 0, results: [{ propertyId: 4, success: true, error: null }] };
+    invokeMock.mockResolvedValue({ success: true, data: fake });
+    const result = await DatabaseService.bulkUpdatePropertyStatus([4], 'DONE');
+    expect(invokeMock).toHaveBeenCalledWith('bulk_update_property_status', {
+      propertyIds: [4],
+      newStatus: 'DONE'
+    });
+    expect(result).toEqual(fake);
+  });
+});
+
+describe('DatabaseService — trash', () => {
+  it('getTrashedProperties invokes get_trashed_properties', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: [] });
+    await DatabaseService.getTrashedProperties();
+    expect(invokeMock).toHaveBeenCalledWith('get_trashed_properties');
+  });
+
+  it('restoreProperty passes propertyId', async () => {
+    invokeMock.mockResolvedValue({ success: true });
+    await DatabaseService.restoreProperty(7);
+    expect(invokeMock).toHaveBeenCalledWith('restore_property', { propertyId: 7 });
+  });
+
+  it('purgeTrash passes olderThanDays and unwraps the result', async () => {
+    const fake = { purged: 2, errors: [] };
+    invokeMock.mockResolvedValue({ success: true, data: fake });
+    const result = await DatabaseService.purgeTrash(30);
+    expect(invokeMock).toHaveBeenCalledWith('purge_trash', { olderThanDays: 30 });
+    expect(result).toEqual(fake);
+  });
+});
+
+describe('DatabaseService — property events', () => {
+  it('getPropertyEvents passes propertyId and unwraps data', async () => {
+    const fake = [{ id: 1, propertyId: 3, eventType: 'status_change' }];
+    invokeMock.mockResolvedValue({ success: true, data: fake });
+    const result = await DatabaseService.getPropertyEvents(3);
+    expect(invokeMock).toHaveBeenCalledWith('get_property_events', { propertyId: 3 });
+    expect(result).toEqual(fake);
+  });
+});
+
+describe('DatabaseService — property search', () => {
+  it('searchProperties passes query, statuses and limit', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: [] });
+    await DatabaseService.searchProperties('γλυφαδα', ['NEW', 'DONE'], 20);
+    expect(invokeMock).toHaveBeenCalledWith('search_properties', {
+      query: 'γλυφαδα',
+      statuses: ['NEW', 'DONE'],
+      limit: 20
+    });
+  });
+
+  it('searchProperties returns [] on failure', async () => {
+    invokeMock.mockResolvedValue({ success: false, error: 'Invalid status: X' });
+    const result = await DatabaseService.searchProperties('villa');
+    expect(invokeMock).toHaveBeenCalledWith('search_properties', {
+      query: 'villa',
+      statuses: null,
+      limit: null
+    });
+    expect(result).toEqual([]);
+  });
+});
+
+describe('DatabaseService — tag commands', () => {
+  it('getPropertiesByStatus passes the tag filter', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: [] });
+    await DatabaseService.getPropertiesByStatus('NEW', ['rush']);
+    expect(invokeMock).toHaveBeenCalledWith('get_properties_by_status', {
+      status: 'NEW',
+      tags: ['rush']
+    });
+  });
+
+  it('getTags invokes get_tags', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: [] });
+    await DatabaseService.getTags();
+    expect(invokeMock).toHaveBeenCalledWith('get_tags');
+  });
+
+  it('searchTags passes query', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: [] });
+    await DatabaseService.searchTags('ru');
+    expect(invokeMock).toHaveBeenCalledWith('search_tags', { query: 'ru' });
+  });
+
+  it('tagProperties / untagProperties pass ids and tags', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: 2 });
+    await DatabaseService.tagProperties([1, 2], ['luxury']);
+    expect(invokeMock).toHaveBeenCalledWith('tag_properties', {
+      propertyIds: [1, 2],
+      tags: ['luxury']
+    });
+    await DatabaseService.untagProperties([1], ['luxury']);
+    expect(invokeMock).toHaveBeenCalledWith('untag_properties', {
+      propertyIds: [1],
+      tags: ['luxury']
+    });
+  });
 });
 
 describe('DatabaseService — city commands', () => {
@@ -112,10 +270,96 @@ describe('DatabaseService — city commands', () => {
     expect(invokeMock).toHaveBeenCalledWith('get_cities');
   });
 
-  it('searchCities passes query', async () => {
+  it('searchCities passes query and defaults transliterate to null', async () => {
     invokeMock.mockResolvedValue({ success: true, data: [] });
     await DatabaseService.searchCities('athens');
-    expect(invokeMock).toHaveBeenCalledWith('search_cities', { query: 'athens' });
+    expect(invokeMock).toHaveBeenCalledWith('search_cities', {
+      query: 'athens',
+      transliterate: null
+    });
+    await DatabaseService.searchCities('αθηνα', false);
+    expect(invokeMock).toHaveBeenCalledWith('search_cities', {
+      query: 'αθηνα',
+      transliterate: false
+    });
+  });
+
+  it('mergeCities passes sources, target and dryRun', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: { moves: [] } });
+    await DatabaseService.mergeCities(['Athína', 'Athina'], 'Athens', true);
+    expect(invokeMock).toHaveBeenCalledWith('merge_cities', {
+      sources: ['Athína', 'Athina'],
+      target: 'Athens',
+      dryRun: true
+    });
+  });
+
+  it('renameCity defaults dryRun to null', async () => {
+    invokeMock.mockResolvedValue({ success: true });
+    await DatabaseService.renameCity('Athína', 'Athens');
+    expect(invokeMock).toHaveBeenCalledWith('rename_city', {
+      from: 'Athína',
+      to: 'Athens',
+      dryRun: null
+    });
+  });
+
+  it('listBackups returns the backup list', async () => {
+    const backups = [
+      {
+        fileName: 'properties-20261017-093000-123-daily.db',
+        kind: 'daily',
+        createdAt: 1,
+        sizeBytes: 2
+      }
+    ];
+    invokeMock.mockResolvedValue({ success: true, data: backups });
+    const result = await DatabaseService.listBackups();
+    expect(invokeMock).toHaveBeenCalledWith('list_backups');
+    expect(result).toEqual(backups);
+  });
+
+  it('restoreBackup passes the file name', async () => {
+    invokeMock.mockResolvedValue({ success: true });
+    await DatabaseService.restoreBackup('properties-20261017-093000-123-daily.db');
+    expect(invokeMock).toHaveBeenCalledWith('restore_backup', {
+      fileName: 'properties-20261017-093000-123-daily.db'
+    });
+  });
+});
+
+describe('DatabaseService — catalogue export/import', () => {
+  it('exportProperties passes path and options', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: { path: '/tmp/p.csv', exported: 2 } });
+    const options = {
+      format: 'csv' as const,
+      statuses: ['DONE'],
+      cities: [],
+      createdFrom: 0,
+      createdTo: null
+    };
+    await DatabaseService.exportProperties('/tmp/p.csv', options);
+    expect(invokeMock).toHaveBeenCalledWith('export_properties', { path: '/tmp/p.csv', options });
+  });
+
+  it('getStatistics defaults both bounds to null', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: { weeks: [] } });
+    await DatabaseService.getStatistics();
+    expect(invokeMock).toHaveBeenCalledWith('get_statistics', { from: null, to: null });
+  });
+
+  it('getStatistics throws on failure', async () => {
+    invokeMock.mockResolvedValue({ success: false, error: 'boom' });
+    await expect(DatabaseService.getStatistics(1, 2)).rejects.toThrow('boom');
+  });
+
+  it('importProperties defaults dryRun to null', async () => {
+    invokeMock.mockResolvedValue({ success: true });
+    await DatabaseService.importProperties('/tmp/p.csv');
+    expect(invokeMock).toHaveBeenCalledWith('import_properties', {
+      path: '/tmp/p.csv',
+      dryRun: null
+    });
   });
 });
 
@@ -160,10 +404,33 @@ describe('DatabaseService — thumbnail / asset-protocol commands', () => {
 });
 
 describe('DatabaseService — sets commands', () => {
-  it('completeSet invokes complete_set', async () => {
+  it('completeSet invokes complete_set with null options by default', async () => {
     invokeMock.mockResolvedValue({ setId: 1 });
     await DatabaseService.completeSet();
-    expect(invokeMock).toHaveBeenCalledWith('complete_set');
+    expect(invokeMock).toHaveBeenCalledWith('complete_set', { options: null });
+  });
+
+  it('completeSet passes selected properties', async () => {
+    invokeMock.mockResolvedValue({ setId: 1 });
+    const options = { propertyIds: [1, 2], keepCodelessInDone: true };
+    await DatabaseService.completeSet(options);
+    expect(invokeMock).toHaveBeenCalledWith('complete_set', { options });
+  });
+
+  it('cancelSetCompletion invokes cancel_set_completion', async () => {
+    invokeMock.mockResolvedValue({ success: true });
+    await DatabaseService.cancelSetCompletion();
+    expect(invokeMock).toHaveBeenCalledWith('cancel_set_completion');
+  });
+
+  it('previewSetCompletion unwraps data and throws on failure', async () => {
+    invokeMock.mockResolvedValueOnce({ success: true, data: { zipFileCount: 3 } });
+    expect((await DatabaseService.previewSetCompletion()).zipFileCount).toBe(3);
+    expect(invokeMock).toHaveBeenCalledWith('preview_set_completion', { options: null });
+    invokeMock.mockResolvedValueOnce({ success: false, error: 'App configuration not found' });
+    await expect(DatabaseService.previewSetCompletion()).rejects.toThrow(
+      'App configuration not found'
+    );
   });
 
   it('getSets unwraps data on success', async () => {
@@ -179,6 +446,28 @@ describe('DatabaseService — sets commands', () => {
     expect(invokeMock).toHaveBeenCalledWith('get_set_properties', { setId: 5 });
   });
 
+  it('verifySet passes setId and throws on failure', async () => {
+    invokeMock.mockResolvedValueOnce({ success: true, data: { setId: 5, ok: true } });
+    expect((await DatabaseService.verifySet(5)).ok).toBe(true);
+    expect(invokeMock).toHaveBeenCalledWith('verify_set', { setId: 5 });
+    invokeMock.mockResolvedValueOnce({ success: false, error: 'Set not found' });
+    await expect(DatabaseService.verifySet(5)).rejects.toThrow('Set not found');
+  });
+
+  it('restoreSet defaults extractMissing to null', async () => {
+    invokeMock.mockResolvedValueOnce({ success: true, data: { setId: 5, restored: 2 } });
+    expect((await DatabaseService.restoreSet(5, 'DONE')).restored).toBe(2);
+    expect(invokeMock).toHaveBeenCalledWith('restore_set', {
+      setId: 5,
+      targetStatus: 'DONE',
+      extractMissing: null
+    });
+    invokeMock.mockResolvedValueOnce({ success: false, error: 'Set has already been restored' });
+    await expect(DatabaseService.restoreSet(5, 'NEW', true)).rejects.toThrow(
+      'Set has already been restored'
+    );
+  });
+
   it('deleteSet defaults deleteZip to false', async () => {
     invokeMock.mockResolvedValue({ success: true });
     await DatabaseService.deleteSet(5);
@@ -211,10 +500,26 @@ describe('DatabaseService — scan + repair', () => {
       data: { foundProperties: 2, newProperties: 1, existingProperties: 1, errors: [] }
     });
     const result = await DatabaseService.scanAndImportProperties();
-    expect(invokeMock).toHaveBeenCalledWith('scan_and_import_properties');
+    expect(invokeMock).toHaveBeenCalledWith('scan_and_import_properties', { full: null });
     expect(result?.foundProperties).toBe(2);
   });
 
+  it('scanAndImportProperties passes the full flag', async () => {
+    invokeMock.mockResolvedValue({
+      success: true,
+      data: {
+        foundProperties: 0,
+        newProperties: 0,
+        existingProperties: 0,
+        errors: [],
+        skippedCities: 0,
+        rootTimings: []
+      }
+    });
+    await DatabaseService.scanAndImportProperties(true);
+    expect(invokeMock).toHaveBeenCalledWith('scan_and_import_properties', { full: true });
+  });
+
   it('scanAndImportProperties throws on failure', async () => {
     invokeMock.mockResolvedValue({ success: false, error: 'oops' });
     await expect(DatabaseService.scanAndImportProperties()).rejects.toThrow('oops');
@@ -225,4 +530,37 @@ describe('DatabaseService — scan + repair', () => {
     const result = await DatabaseService.repairPropertyStatuses();
     expect(result).toBeNull();
   });
+
+  it('findOrphans returns the report', async () => {
+    const report = { propertiesChecked: 1, foldersChecked: 1, findings: [], errors: [] };
+    invokeMock.mockResolvedValue({ success: true, data: report });
+    const result = await DatabaseService.findOrphans();
+    expect(invokeMock).toHaveBeenCalledWith('find_orphans');
+    expect(result).toEqual(report);
+  });
+
+  it('applyOrphanActions forwards the chosen actions', async () => {
+    invokeMock.mockResolvedValue({ success: true, data: { applied: 1, errors: [] } });
+    const actions = [{ type: 'delete_row' as const, propertyId: 3 }];
+    const result = await DatabaseService.applyOrphanActions(actions);
+    expect(invokeMock).toHaveBeenCalledWith('apply_orphan_actions', { actions });
+    expect(result).toEqual({ applied: 1, errors: [] });
+  });
+
+  it('repairPropertyStatuses passes dry run and selected ids', async () => {
+    const report = {
+      propertiesChecked: 2,
+      propertiesFixed: 0,
+      dryRun: true,
+      diffs: [],
+      errors: []
+    };
+    invokeMock.mockResolvedValue({ success: true, data: report });
+    const result = await DatabaseService.repairPropertyStatuses(true, [4]);
+    expect(invokeMock).toHaveBeenCalledWith('repair_property_statuses', {
+      dryRun: true,
+      propertyIds: [4]
+    });
+    expect(result).toEqual(report);
+  });
 });
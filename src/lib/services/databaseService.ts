@@ -2,6 +2,8 @@ import type { AppConfig } from '$lib/utils/configManager';
 import { invoke } from '@tauri-apps/api/core';
 import type {
   AcceptedCorrection,
+  BackupInfo,
+  BulkStatusResult,
   City,
   CommandResult,
   CompleteSetResult,
@@ -9,15 +11,30 @@ import type {
   EnhanceAnalysisResult,
   EnhanceApplyResult,
   EnhanceRequest,
+  ImageRecord,
+  OrphanAction,
+  OrphanActionResult,
+  OrphanReport,
   PerspectiveCommandResult,
   Property,
+  PropertyCode,
+  PropertyEvent,
+  PropertyExportOptions,
   PropertyStatus,
+  PurgeTrashResult,
   RepairResult,
+  RestoreSetResult,
   ScanResult,
   Set,
+  SetCompletionOptions,
+  SetCompletionPreview,
   SetProperty,
+  SetVerificationReport,
+  Statistics,
+  Tag,
   ThumbnailBatchRequest,
-  ThumbnailBatchResult
+  ThumbnailBatchResult,
+  TrashedProperty
 } from '../types/database';
 
 export class DatabaseService {
@@ -38,9 +55,10 @@ export class DatabaseService {
     return [];
   }
 
-  static async getPropertiesByStatus(status: PropertyStatus): Promise<Property[]> {
+  static async getPropertiesByStatus(status: PropertyStatus, tags?: string[]): Promise<Property[]> {
     const result = await invoke<CommandResult>('get_properties_by_status', {
-      status
+      status,
+      tags: tags ?? null
     });
     if (result.success && result.data) {
       return result.data as Property[];
@@ -58,6 +76,21 @@ export class DatabaseService {
     });
   }
 
+  // Moves many properties at once; listen to 'bulk-status-progress' for progress
+  static async bulkUpdatePropertyStatus(
+    propertyIds: number[],
+    newStatus: PropertyStatus
+  ): Promise<BulkStatusResult | null> {
+    const result = await invoke<CommandResult>('bulk_update_property_status', {
+      propertyIds,
+      newStatus
+    });
+    if (result.success && result.data) {
+      return result.data as BulkStatusResult;
+    }
+    return null;
+  }
+
   static async deleteProperty(propertyId: number): Promise<CommandResult> {
     return await invoke<CommandResult>('delete_property', {
       propertyId
@@ -95,6 +128,145 @@ export class DatabaseService {
     return null;
   }
 
+  static async getPropertyByCode(code: string): Promise<Property | null> {
+    const result = await invoke<CommandResult>('get_property_by_code', { code });
+    if (result.success && result.data) {
+      return result.data as Property;
+    }
+    return null;
+  }
+
+  // Portal codes: the primary one is mirrored into property.code and the
+  // folder name. The mutating commands return the updated list as data.
+  static async getPropertyCodes(propertyId: number): Promise<PropertyCode[]> {
+    const result = await invoke<CommandResult>('get_property_codes', { propertyId });
+    if (result.success && result.data) {
+      return result.data as PropertyCode[];
+    }
+    return [];
+  }
+
+  // Image metadata from the images table, indexed on demand. `subfolder`
+  // limits the list to one folder: '' for the originals, or e.g. 'INTERNET/AGGELIA'.
+  static async getPropertyImages(propertyId: number, subfolder?: string): Promise<ImageRecord[]> {
+    const result = await invoke<CommandResult>('get_property_images', {
+      propertyId,
+      subfolder: subfolder ?? null
+    });
+    if (result.success && result.data) {
+      return result.data as ImageRecord[];
+    }
+    throw new Error(result.error || 'Failed to load images');
+  }
+
+  static async addPropertyCode(
+    propertyId: number,
+    portal: string,
+    code: string,
+    primary?: boolean
+  ): Promise<CommandResult> {
+    return await invoke<CommandResult>('add_property_code', {
+      propertyId,
+      portal,
+      code,
+      primary: primary ?? null
+    });
+  }
+
+  static async removePropertyCode(propertyId: number, portal: string): Promise<CommandResult> {
+    return await invoke<CommandResult>('remove_property_code', { propertyId, portal });
+  }
+
+  static async setPrimaryPropertyCode(propertyId: number, portal: string): Promise<CommandResult> {
+    return await invoke<CommandResult>('set_primary_property_code', { propertyId, portal });
+  }
+
+  // Trash: delete_property only soft-deletes; these list, restore and purge
+  static async getTrashedProperties(): Promise<TrashedProperty[]> {
+    const result = await invoke<CommandResult>('get_trashed_properties');
+    if (result.success && result.data) {
+      return result.data as TrashedProperty[];
+    }
+    return [];
+  }
+
+  static async restoreProperty(propertyId: number): Promise<CommandResult> {
+    return await invoke<CommandResult>('restore_property', {
+      propertyId
+    });
+  }
+
+  static async purgeTrash(olderThanDays: number): Promise<PurgeTrashResult | null> {
+    const result = await invoke<CommandResult>('purge_trash', {
+      olderThanDays
+    });
+    if (result.success && result.data) {
+      return result.data as PurgeTrashResult;
+    }
+    return null;
+  }
+
+  // Audit trail: every status change, folder move, rename and code assignment
+  static async getPropertyEvents(propertyId: number): Promise<PropertyEvent[]> {
+    const result = await invoke<CommandResult>('get_property_events', {
+      propertyId
+    });
+    if (result.success && result.data) {
+      return result.data as PropertyEvent[];
+    }
+    return [];
+  }
+
+  // Full-text search over name, city, code and notes (accent-insensitive)
+  static async searchProperties(
+    query: string,
+    statuses?: PropertyStatus[],
+    limit?: number
+  ): Promise<Property[]> {
+    const result = await invoke<CommandResult>('search_properties', {
+      query,
+      statuses: statuses ?? null,
+      limit: limit ?? null
+    });
+    if (result.success && result.data) {
+      return result.data as Property[];
+    }
+    return [];
+  }
+
+  // Tag operations (autocomplete + bulk tagging)
+  static async getTags(): Promise<Tag[]> {
+    const result = await invoke<CommandResult>('get_tags');
+    if (result.success && result.data) {
+      return result.data as Tag[];
+    }
+    return [];
+  }
+
+  static async searchTags(query: string): Promise<Tag[]> {
+    const result = await invoke<CommandResult>('search_tags', {
+      query
+    });
+    if (result.success && result.data) {
+      return result.data as Tag[];
+    }
+    return [];
+  }
+
+  static async tagProperties(propertyIds: number[], tags: string[]): Promise<CommandResult> {
+    return await invoke<CommandResult>('tag_properties', {
+      propertyIds,
+      tags
+    });
+  }
+
+  static async untagProperties(propertyIds: number[], tags: string[]): Promise<CommandResult> {
+    return await invoke<CommandResult>('untag_properties', {
+      propertyIds,
+      tags
+    });
+  }
+
   // City operations for autocomplete
   static async getCities(): Promise<City[]> {
     const result = await invoke<CommandResult>('get_cities');
@@ -104,9 +276,12 @@ export class DatabaseService {
     return [];
   }
 
-  static async searchCities(query: string): Promise<City[]> {
+  // Ignores accents and case; transliterate (default on) also matches
+  // Greek names against Latin spellings and vice versa.
+  static async searchCities(query: string, transliterate?: boolean): Promise<City[]> {
     const result = await invoke<CommandResult>('search_cities', {
-      query
+      query,
+      transliterate: transliterate ?? null
     });
     if (result.success && result.data) {
       return result.data as City[];
@@ -114,8 +289,72 @@ export class DatabaseService {
     return [];
   }
 
-  static async scanAndImportProperties(): Promise<ScanResult | null> {
-    const result = await invoke<CommandResult>('scan_and_import_properties');
+  // City merge/rename: run with dryRun first to preview the folder moves.
+  // data is a CityMergePlan, also when existing folders block the merge.
+  static async mergeCities(
+    sources: string[],
+    target: string,
+    dryRun?: boolean
+  ): Promise<CommandResult> {
+    return await invoke<CommandResult>('merge_cities', {
+      sources,
+      target,
+      dryRun: dryRun ?? null
+    });
+  }
+
+  static async renameCity(from: string, to: string, dryRun?: boolean): Promise<CommandResult> {
+    return await invoke<CommandResult>('rename_city', { from, to, dryRun: dryRun ?? null });
+  }
+
+  // Catalogue: export writes CSV or JSON to path (data: PropertyExportResult);
+  // import reads a CSV, dryRun previews it (data: PropertyImportResult).
+  // Bounds are inclusive, in milliseconds; omit either for an open range.
+  static async getStatistics(from?: number | null, to?: number | null): Promise<Statistics> {
+    const result = await invoke<CommandResult>('get_statistics', {
+      from: from ?? null,
+      to: to ?? null
+    });
+    if (result.success && result.data) {
+      return result.data as Statistics;
+    }
+    throw new Error(result.error || 'Failed to load statistics');
+  }
+
+  static async exportProperties(
+    path: string,
+    options: PropertyExportOptions
+  ): Promise<CommandResult> {
+    return await invoke<CommandResult>('export_properties', { path, options });
+  }
+
+  static async importProperties(path: string, dryRun?: boolean): Promise<CommandResult> {
+    return await invoke<CommandResult>('import_properties', { path, dryRun: dryRun ?? null });
+  }
+
+  // Backups are listed newest first.
+  static async listBackups(): Promise<BackupInfo[]> {
+    const result = await invoke<CommandResult>('list_backups');
+    if (result.success && result.data) {
+      return result.data as BackupInfo[];
+    }
+    return [];
+  }
+
+  static async createBackup(): Promise<CommandResult> {
+    return await invoke<CommandResult>('create_backup');
+  }
+
+  // Backs up the current database first; data.safetyBackup names that file.
+  static async restoreBackup(fileName: string): Promise<CommandResult> {
+    return await invoke<CommandResult>('restore_backup', { fileName });
+  }
+
+  // Cities unchanged since the last scan are skipped unless `full` is set.
+  static async scanAndImportProperties(full?: boolean): Promise<ScanResult | null> {
+    const result = await invoke<CommandResult>('scan_and_import_properties', {
+      full: full ?? null
+    });
     if (result.success && result.data) {
       return result.data as ScanResult;
     }
@@ -255,8 +494,24 @@ export class DatabaseService {
   }
 
   // Sets Operations
-  static async completeSet(): Promise<CompleteSetResult> {
-    return await invoke<CompleteSetResult>('complete_set');
+  static async completeSet(options?: SetCompletionOptions): Promise<CompleteSetResult> {
+    return await invoke<CompleteSetResult>('complete_set', { options: options ?? null });
+  }
+
+  static async cancelSetCompletion(): Promise<CommandResult> {
+    return await invoke<CommandResult>('cancel_set_completion');
+  }
+
+  static async previewSetCompletion(
+    options?: SetCompletionOptions
+  ): Promise<SetCompletionPreview> {
+    const result = await invoke<CommandResult>('preview_set_completion', {
+      options: options ?? null
+    });
+    if (result.success && result.data) {
+      return result.data as SetCompletionPreview;
+    }
+    throw new Error(result.error || 'Failed to preview set');
   }
 
   static async getSets(): Promise<Set[]> {
@@ -275,6 +530,31 @@ export class DatabaseService {
     return [];
   }
 
+  // Reads every entry of the set's ZIP, so this can take a while on the NAS.
+  static async verifySet(setId: number): Promise<SetVerificationReport> {
+    const result = await invoke<CommandResult>('verify_set', { setId });
+    if (result.success && result.data) {
+      return result.data as SetVerificationReport;
+    }
+    throw new Error(result.error || 'Failed to verify set');
+  }
+
+  static async restoreSet(
+    setId: number,
+    targetStatus: 'DONE' | 'NEW',
+    extractMissing?: boolean
+  ): Promise<RestoreSetResult> {
+    const result = await invoke<CommandResult>('restore_set', {
+      setId,
+      targetStatus,
+      extractMissing: extractMissing ?? null
+    });
+    if (result.success && result.data) {
+      return result.data as RestoreSetResult;
+    }
+    throw new Error(result.error || 'Failed to restore set');
+  }
+
   static async openSetsFolder(): Promise<CommandResult> {
     return await invoke<CommandResult>('open_sets_folder');
   }
@@ -284,14 +564,36 @@ export class DatabaseService {
   }
 
   // Repair operations
-  static async repairPropertyStatuses(): Promise<RepairResult | null> {
-    const result = await invoke<CommandResult>('repair_property_statuses');
+  static async repairPropertyStatuses(
+    dryRun?: boolean,
+    propertyIds?: number[]
+  ): Promise<RepairResult | null> {
+    const result = await invoke<CommandResult>('repair_property_statuses', {
+      dryRun: dryRun ?? null,
+      propertyIds: propertyIds ?? null
+    });
     if (result.success && result.data) {
       return result.data as RepairResult;
     }
     return null;
   }
 
+  static async findOrphans(): Promise<OrphanReport | null> {
+    const result = await invoke<CommandResult>('find_orphans');
+    if (result.success && result.data) {
+      return result.data as OrphanReport;
+    }
+    return null;
+  }
+
+  static async applyOrphanActions(actions: OrphanAction[]): Promise<OrphanActionResult | null> {
+    const result = await invoke<CommandResult>('apply_orphan_actions', { actions });
+    if (result.success && result.data) {
+      return result.data as OrphanActionResult;
+    }
+    return null;
+  }
+
   // Batch Auto-Enhance Operations
   static async batchAnalyzeForEnhance(
     folderPath: string,
@@ -6,26 +6,96 @@
 // We re-export them as `interface` extensions so the rest of the
 // codebase keeps using familiar type names like `Property` and the
 // PropertyStatus union still narrows the `status` field.
+import type { BackupInfo } from './generated/BackupInfo';
+import type { BulkStatusItemResult } from './generated/BulkStatusItemResult';
+import type { BulkStatusResult } from './generated/BulkStatusResult';
 import type { City } from './generated/City';
+import type { CityThroughput } from './generated/CityThroughput';
 import type { CommandResult as GeneratedCommandResult } from './generated/CommandResult';
 import type { CompleteSetResult } from './generated/CompleteSetResult';
+import type { ExportFormat } from './generated/ExportFormat';
+import type { FolderSyncResult } from './generated/FolderSyncResult';
+import type { ImageCounts } from './generated/ImageCounts';
+import type { ImageRecord } from './generated/ImageRecord';
+import type { OrphanAction } from './generated/OrphanAction';
+import type { OrphanActionResult } from './generated/OrphanActionResult';
+import type { OrphanFinding } from './generated/OrphanFinding';
+import type { OrphanKind } from './generated/OrphanKind';
+import type { OrphanReport } from './generated/OrphanReport';
 import type { Property as GeneratedProperty } from './generated/Property';
+import type { PropertyExportOptions } from './generated/PropertyExportOptions';
+import type { PropertyExportResult } from './generated/PropertyExportResult';
+import type { PropertyImportResult } from './generated/PropertyImportResult';
+import type { PropertyCode } from './generated/PropertyCode';
+import type { PropertyEvent } from './generated/PropertyEvent';
+import type { PurgeTrashResult } from './generated/PurgeTrashResult';
+import type { RepairDiff } from './generated/RepairDiff';
+import type { RepairReason } from './generated/RepairReason';
 import type { RepairResult } from './generated/RepairResult';
+import type { RestoreSetResult } from './generated/RestoreSetResult';
+import type { RootScanTiming } from './generated/RootScanTiming';
 import type { ScanResult } from './generated/ScanResult';
 import type { Set } from './generated/Set';
+import type { SetCompletionOptions } from './generated/SetCompletionOptions';
+import type { SetCompletionPreview } from './generated/SetCompletionPreview';
+import type { SetEntryIssue } from './generated/SetEntryIssue';
+import type { SetPreviewProperty } from './generated/SetPreviewProperty';
 import type { SetProperty } from './generated/SetProperty';
+import type { SetVerificationReport } from './generated/SetVerificationReport';
+import type { SetZipProgress } from './generated/SetZipProgress';
+import type { Statistics } from './generated/Statistics';
+import type { StatusCount } from './generated/StatusCount';
+import type { Tag } from './generated/Tag';
 import type { ThumbnailBatchRequest } from './generated/ThumbnailBatchRequest';
 import type { ThumbnailBatchResult } from './generated/ThumbnailBatchResult';
+import type { Throughput } from './generated/Throughput';
+import type { TrashedProperty } from './generated/TrashedProperty';
+import type { WeeklyThroughput } from './generated/WeeklyThroughput';
 
 export type {
+  BackupInfo,
+  BulkStatusItemResult,
+  BulkStatusResult,
   City,
+  CityThroughput,
   CompleteSetResult,
+  ExportFormat,
+  FolderSyncResult,
+  ImageCounts,
+  ImageRecord,
+  OrphanAction,
+  OrphanActionResult,
+  OrphanFinding,
+  OrphanKind,
+  OrphanReport,
+  PropertyCode,
+  PropertyEvent,
+  PropertyExportOptions,
+  PropertyExportResult,
+  PropertyImportResult,
+  PurgeTrashResult,
+  RepairDiff,
+  RepairReason,
   RepairResult,
+  RestoreSetResult,
+  RootScanTiming,
   ScanResult,
   Set,
+  SetCompletionOptions,
+  SetCompletionPreview,
+  SetEntryIssue,
+  SetPreviewProperty,
   SetProperty,
+  SetVerificationReport,
+  SetZipProgress,
+  Statistics,
+  StatusCount,
+  Tag,
   ThumbnailBatchRequest,
-  ThumbnailBatchResult
+  ThumbnailBatchResult,
+  Throughput,
+  TrashedProperty,
+  WeeklyThroughput
 };
 
 export type PropertyStatus = 'NEW' | 'DONE' | 'NOT_FOUND' | 'ARCHIVE';
@@ -69,6 +139,11 @@ export interface AppConfig {
   notFoundFolderPath: string;
   archiveFolderPath: string;
   setsFolderPath: string;
+  trashFolderPath?: string;
+  codePattern?: string;
+  backupRetention?: number;
+  watchFolders?: boolean;
+  watchAllRoots?: boolean;
   isValidPath: boolean;
   lastUpdated: string | null;
   // Image editor settings
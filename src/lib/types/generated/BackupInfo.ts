@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A database backup in `{app data}/backups`.
+ */
+export type BackupInfo = { fileName: string, 
+/**
+ * Why it was taken: startup, daily, manual, pre-restore or pre-reset.
+ */
+kind: string, 
+/**
+ * Milliseconds since the epoch, from the file name.
+ */
+createdAt: number, sizeBytes: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome for one property in `bulk_update_property_status`.
+ */
+export type BulkStatusItemResult = { propertyId: number, success: boolean, error: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One folder `merge_cities` moves from a source city into the target city.
+ */
+export type CityFolderMove = { status: string, 
+/**
+ * `None` for folders the database does not track.
+ */
+propertyId: number | null, from: string, to: string, };
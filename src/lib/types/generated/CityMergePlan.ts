@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CityFolderMove } from "./CityFolderMove";
+
+/**
+ * Preview (and, without `dry_run`, outcome) of merging cities into one.
+ */
+export type CityMergePlan = { target: string, sources: Array<string>, 
+/**
+ * Rows whose city changes, trashed ones included.
+ */
+properties: number, 
+/**
+ * `usage_count` of the target city after the merge.
+ */
+usageCount: number, moves: Array<CityFolderMove>, 
+/**
+ * Target folders that already exist. Any conflict blocks the merge.
+ */
+conflicts: Array<string>, dryRun: boolean, };
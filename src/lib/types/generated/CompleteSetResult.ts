@@ -1,3 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type CompleteSetResult = { setId: number, setName: string, zipPath: string, propertiesArchived: number, propertiesMovedToNotFound: number, };
+export type CompleteSetResult = { setId: number, setName: string, zipPath: string, propertiesArchived: number, propertiesMovedToNotFound: number, propertiesLeftInDone: number, 
+/**
+ * Moves that finished but left part of the original folder behind.
+ */
+warnings: Array<string>, 
+/**
+ * Properties that could not be moved; they stay in DONE.
+ */
+errors: Array<string>, };
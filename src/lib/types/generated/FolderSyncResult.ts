@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `folders-synced` event, sent after the folder watcher
+ * imported or updated properties.
+ */
+export type FolderSyncResult = { 
+/**
+ * Status folders the changes were seen in.
+ */
+statuses: Array<string>, imported: number, 
+/**
+ * Properties whose status was updated to match the disk.
+ */
+updated: number, errors: Array<string>, };
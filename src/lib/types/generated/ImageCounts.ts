@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Image files in each workflow subfolder of a property folder.
+ */
+export type ImageCounts = { originals: number, internet: number, internetAggelia: number, watermark: number, watermarkAggelia: number, };
@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What is known about one image file, from the `images` table.
+ */
+export type ImageRecord = { propertyId: number, 
+/**
+ * Folder relative to the property folder, `""` for the originals and
+ * e.g. `"INTERNET/AGGELIA"` for the others.
+ */
+subfolder: string, filename: string, 
+/**
+ * Bytes.
+ */
+size: number, width: number | null, height: number | null, 
+/**
+ * Modification time in milliseconds since the epoch.
+ */
+mtime: number, 
+/**
+ * Lowercase hex SHA-256 of the file contents.
+ */
+sha256: string, 
+/**
+ * EXIF `DateTimeOriginal` as `YYYY-MM-DDTHH:MM:SS`, in the camera's
+ * local time.
+ */
+capturedAt: string | null, };
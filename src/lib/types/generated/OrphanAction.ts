@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A fix for an orphan finding, applied with `apply_orphan_actions`.
+ */
+export type OrphanAction = { "type": "delete_row", propertyId: number, } | { "type": "relink", propertyId: number, status: string, folderPath: string, } | { "type": "merge", folderPath: string, keepStatus: string, } | { "type": "remove_city_folder", status: string, city: string, };
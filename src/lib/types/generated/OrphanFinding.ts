@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrphanAction } from "./OrphanAction";
+import type { OrphanKind } from "./OrphanKind";
+
+export type OrphanFinding = { kind: OrphanKind, propertyId: number | null, 
+/**
+ * `City/Folder` for property findings, the city name for empty cities.
+ */
+folderPath: string, 
+/**
+ * Status folders involved: the row's status for a missing folder,
+ * every status holding a copy for duplicates.
+ */
+statuses: Array<string>, suggestedActions: Array<OrphanAction>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What kind of mismatch an `OrphanFinding` describes.
+ */
+export type OrphanKind = "missing_folder" | "duplicate_folder" | "empty_city";
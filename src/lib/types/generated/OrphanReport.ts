@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrphanFinding } from "./OrphanFinding";
+
+export type OrphanReport = { propertiesChecked: number, foldersChecked: number, findings: Array<OrphanFinding>, errors: Array<string>, };
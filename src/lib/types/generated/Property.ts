@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type Property = { id: number | null, name: string, city: string, status: string, folder_path: string, notes: string | null, code: string | null, created_at: number, updated_at: number, };
+export type Property = { id: number | null, name: string, city: string, status: string, folder_path: string, notes: string | null, code: string | null, tags: Array<string>, created_at: number, updated_at: number, };
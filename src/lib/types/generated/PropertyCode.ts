@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One portal's listing code for a property. The primary code is mirrored
+ * into `properties.code` and the folder name suffix.
+ */
+export type PropertyCode = { portal: string, code: string, isPrimary: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One row of a property's audit trail (see `database::events`).
+ */
+export type PropertyEvent = { id: number, propertyId: number, eventType: string, oldValue: string | null, newValue: string | null, source: string, createdAt: number, };
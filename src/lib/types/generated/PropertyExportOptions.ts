@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExportFormat } from "./ExportFormat";
+
+/**
+ * Which properties `export_properties` writes. Empty lists and missing
+ * bounds do not filter. The date range applies to `created_at` and is
+ * inclusive, in milliseconds since the epoch.
+ */
+export type PropertyExportOptions = { format: ExportFormat, statuses: Array<string>, cities: Array<string>, createdFrom: number | null, createdTo: number | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of `import_properties`. `errors` holds one message per skipped
+ * CSV line or failed status change.
+ */
+export type PropertyImportResult = { created: number, updated: number, unchanged: number, errors: Array<string>, dryRun: boolean, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RepairReason } from "./RepairReason";
+
+/**
+ * One property whose row does not match the disk.
+ */
+export type RepairDiff = { propertyId: number, name: string, currentStatus: string, currentFolderPath: string, 
+/**
+ * Where the folder was found; `None` when it is missing.
+ */
+diskStatus: string | null, diskFolderPath: string | null, reasons: Array<RepairReason>, };
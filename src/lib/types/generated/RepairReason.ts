@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why a property's row disagrees with what is on disk.
+ */
+export type RepairReason = "moved" | "code_suffix_added" | "missing";
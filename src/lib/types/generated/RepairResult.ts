@@ -1,6 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RepairDiff } from "./RepairDiff";
 
 /**
  * Repair result structure
  */
-export type RepairResult = { propertiesChecked: number, propertiesFixed: number, errors: Array<string>, };
+export type RepairResult = { propertiesChecked: number, propertiesFixed: number, dryRun: boolean, 
+/**
+ * In a dry run every drift found; otherwise the fixes that were
+ * attempted plus the missing properties.
+ */
+diffs: Array<RepairDiff>, errors: Array<string>, };
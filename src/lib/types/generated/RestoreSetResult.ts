@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of `restore_set`.
+ */
+export type RestoreSetResult = { setId: number, 
+/**
+ * Status the properties were moved back to.
+ */
+status: string, restored: number, 
+/**
+ * Of those, properties whose folder was re-extracted from the ZIP.
+ */
+extracted: number, 
+/**
+ * Properties left alone, with the reason.
+ */
+skipped: Array<string>, 
+/**
+ * Moves that finished but left part of the original folder behind.
+ */
+warnings: Array<string>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How long one status folder took to scan.
+ */
+export type RootScanTiming = { status: string, 
+/**
+ * Reading the folders and importing what was new.
+ */
+durationMs: number, citiesScanned: number, citiesSkipped: number, };
@@ -1,3 +1,13 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RootScanTiming } from "./RootScanTiming";
 
-export type ScanResult = { foundProperties: number, newProperties: number, existingProperties: number, errors: Array<string>, };
+/**
+ * Outcome of a scan. Folders in skipped cities are not counted in
+ * `found_properties` or `existing_properties`.
+ */
+export type ScanResult = { foundProperties: number, newProperties: number, existingProperties: number, errors: Array<string>, 
+/**
+ * City folders not read because they had not changed since the last
+ * scan.
+ */
+skippedCities: number, rootTimings: Array<RootScanTiming>, };
@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type Set = { id: number | null, name: string, zip_path: string, property_count: number, created_at: number, };
+export type Set = { id: number | null, name: string, zip_path: string, property_count: number, created_at: number, 
+/**
+ * When `restore_set` last moved properties back out of ARCHIVE.
+ */
+reverted_at: number | null, };
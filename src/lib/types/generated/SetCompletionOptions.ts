@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which DONE properties `complete_set` takes. The defaults complete every
+ * DONE property, as the dashboard button always has.
+ */
+export type SetCompletionOptions = { 
+/**
+ * Only these properties; `None` takes every DONE property.
+ */
+propertyIds: Array<number> | null, 
+/**
+ * Leave properties without a code in DONE instead of moving them to
+ * `NOT_FOUND`.
+ */
+keepCodelessInDone: boolean, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SetPreviewProperty } from "./SetPreviewProperty";
+
+/**
+ * What `complete_set` would do with the same options, without doing it.
+ */
+export type SetCompletionPreview = { 
+/**
+ * Zipped, then moved to ARCHIVE.
+ */
+toArchive: Array<SetPreviewProperty>, 
+/**
+ * Moved to `NOT_FOUND` without being zipped.
+ */
+toNotFound: Array<SetPreviewProperty>, 
+/**
+ * Selected, but kept in DONE because they have no code.
+ */
+leftInDone: Array<SetPreviewProperty>, 
+/**
+ * Requested IDs that are not DONE properties, with the reason.
+ */
+notEligible: Array<string>, zipFileCount: number, zipBytes: number, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A property as `preview_set_completion` would handle it.
+ */
+export type SetPreviewProperty = { propertyId: number, name: string, city: string, code: string | null, folderPath: string, 
+/**
+ * Whether the folder is in DONE; missing folders are left out of the ZIP.
+ */
+folderExists: boolean, fileCount: number, totalBytes: number, };
@@ -1,3 +1,4 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PropertyCode } from "./PropertyCode";
 
-export type SetProperty = { id: number | null, setId: number, propertyId: number | null, propertyName: string, propertyCity: string, propertyCode: string | null, };
+export type SetProperty = { id: number | null, setId: number, propertyId: number | null, propertyName: string, propertyCity: string, propertyCode: string | null, propertyCodes: Array<PropertyCode>, };
@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SetEntryIssue } from "./SetEntryIssue";
+
+/**
+ * Result of `verify_set`. The set is intact when `ok` is true.
+ */
+export type SetVerificationReport = { setId: number, zipPath: string, ok: boolean, 
+/**
+ * Why the ZIP could not be opened at all, e.g. missing or truncated.
+ */
+openError: string | null, 
+/**
+ * Sets completed before manifests were added have none.
+ */
+hasManifest: boolean, entriesChecked: number, 
+/**
+ * Files listed in the manifest but not in the ZIP.
+ */
+missingEntries: Array<string>, 
+/**
+ * Files in the ZIP outside every property folder of the set, and
+ * images in the indexed subfolders that the manifest does not list.
+ */
+extraEntries: Array<string>, 
+/**
+ * Entries that fail their CRC or differ from the manifest.
+ */
+corruptEntries: Array<SetEntryIssue>, 
+/**
+ * `city/name` of the set's properties with no folder in the ZIP.
+ */
+missingProperties: Array<string>, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Payload of the `set-zip-progress` event emitted while `complete_set`
+ * indexes the images for the manifest and then writes the set ZIP.
+ */
+export type SetZipProgress = { 
+/**
+ * Properties indexed so far; below `total_properties` while indexing.
+ */
+propertiesIndexed: number, 
+/**
+ * Properties to index, or 0 once the ZIP is being written.
+ */
+totalProperties: number, filesDone: number, totalFiles: number, bytesDone: number, totalBytes: number, };
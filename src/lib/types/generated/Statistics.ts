@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CityThroughput } from "./CityThroughput";
+import type { StatusCount } from "./StatusCount";
+import type { Throughput } from "./Throughput";
+import type { WeeklyThroughput } from "./WeeklyThroughput";
+
+/**
+ * Report returned by `get_statistics`. The range bounds are inclusive, in
+ * milliseconds since the epoch; a missing bound does not limit.
+ */
+export type Statistics = { from: number | null, to: number | null, total: Throughput, 
+/**
+ * Every week from the first to the last with activity, empty weeks
+ * included.
+ */
+weeks: Array<WeeklyThroughput>, 
+/**
+ * Busiest city first.
+ */
+cities: Array<CityThroughput>, 
+/**
+ * Current status of the properties shot in the range, with their
+ * images in each workflow folder.
+ */
+statuses: Array<StatusCount>, 
+/**
+ * Mean milliseconds from creation to the first move to DONE, over the
+ * properties that reached DONE in the range.
+ */
+averageTimeToDone: number | null, doneCount: number, };
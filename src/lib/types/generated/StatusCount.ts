@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ImageCounts } from "./ImageCounts";
+
+export type StatusCount = { status: string, count: number, 
+/**
+ * Images on disk across these properties, in this status's folder.
+ */
+imageCounts: ImageCounts, };
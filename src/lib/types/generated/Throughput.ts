@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Properties shot (created), delivered (included in a set) and archived
+ * (moved to ARCHIVE) over some period.
+ */
+export type Throughput = { shot: number, delivered: number, archived: number, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A soft-deleted property as listed on the Trash page.
+ */
+export type TrashedProperty = { id: number, name: string, city: string, status: string, folderPath: string, code: string | null, 
+/**
+ * Absolute path of the folder inside the trash directory, or null when
+ * the folder was left where it was.
+ */
+trashPath: string | null, deletedAt: number, };
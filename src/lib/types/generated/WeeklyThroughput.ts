@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Throughput } from "./Throughput";
+
+export type WeeklyThroughput = { 
+/**
+ * Monday 00:00 UTC, in milliseconds since the epoch.
+ */
+weekStart: number, throughput: Throughput, };